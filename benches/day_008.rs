@@ -19,6 +19,10 @@ pub fn bench(c: &mut Criterion) {
         b.iter(|| program.correct_recursive())
     });
 
+    group.bench_function(BenchmarkId::new("correct", "cfg"), |b| {
+        b.iter(|| program.correct_cfg())
+    });
+
     group.finish();
 }
 
@@ -1,11 +1,11 @@
 use aoc::combat::{Game, RecursiveGame};
-use aoc::util::load_input;
+use aoc::input::Input;
 use criterion::{criterion_group, BenchmarkId, Criterion};
 
 pub fn bench(c: &mut Criterion) {
-    let lines = load_input("022").expect("could not load input");
-    let game = Game::from_input(&lines).expect("could not create game");
-    let recursive_game = RecursiveGame::from_input(&lines).expect("could not create game");
+    let input = Input::load("022").expect("could not load input");
+    let game = Game::from_input(&input).expect("could not create game");
+    let recursive_game = RecursiveGame::from_input(&input).expect("could not create game");
 
     let mut group = c.benchmark_group("022 crab combat");
     group.bench_function(BenchmarkId::new("combat", "normal"), |b| {
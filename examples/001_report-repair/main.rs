@@ -1,4 +1,6 @@
+use aoc::dump;
 use aoc::expense::{expense_report, triple_expense};
+use aoc::timing;
 use aoc::util::load_input;
 
 fn main() {
@@ -17,4 +19,7 @@ fn main() {
         "part 2: {}",
         triple_expense(&vals, 2020).expect("Could not fix expense report")
     );
+
+    timing::example(1).expect("could not time day");
+    dump::example(1).expect("could not dump model");
 }
@@ -1,4 +1,6 @@
+use aoc::dump;
 use aoc::password::{count_valid_passwords, PolicyType};
+use aoc::timing;
 use aoc::util::load_input;
 
 fn main() {
@@ -12,4 +14,7 @@ fn main() {
         "part 2: {}",
         count_valid_passwords(&lines, &PolicyType::Position).expect("invalid input")
     );
+
+    timing::example(2).expect("could not time day");
+    dump::example(2).expect("could not dump model");
 }
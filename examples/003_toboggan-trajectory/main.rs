@@ -1,3 +1,5 @@
+use aoc::dump;
+use aoc::timing;
 use aoc::toboggan::Forest;
 use aoc::util::load_input;
 
@@ -12,4 +14,7 @@ fn main() {
         .fold(1, |acc, count| acc * count);
 
     println!("part 2: {}", res);
+
+    timing::example(3).expect("could not time day");
+    dump::example(3).expect("could not dump model");
 }
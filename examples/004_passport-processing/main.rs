@@ -1,9 +1,11 @@
+use aoc::dump;
+use aoc::input::Input;
 use aoc::passport::Passport;
-use aoc::util::load_input;
+use aoc::timing;
 
 fn main() {
-    let lines = load_input("004").expect("could not load input");
-    let res = Passport::from_input(&lines);
+    let input = Input::load("004").expect("could not load input");
+    let res = Passport::from_input(&input);
 
     let valid_passports = res
         .into_iter()
@@ -19,4 +21,7 @@ fn main() {
         .count();
 
     println!("part 2: {}", count);
+
+    timing::example(4).expect("could not time day");
+    dump::example(4).expect("could not dump model");
 }
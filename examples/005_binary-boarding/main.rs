@@ -1,5 +1,7 @@
 use aoc::boarding::{find_highest_id, Pass, Seat};
+use aoc::dump;
 use aoc::error::Result;
+use aoc::timing;
 use aoc::util::load_input;
 
 fn main() {
@@ -36,4 +38,7 @@ fn main() {
         }
         prev = id;
     }
+
+    timing::example(5).expect("could not time day");
+    dump::example(5).expect("could not dump model");
 }
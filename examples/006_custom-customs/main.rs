@@ -1,4 +1,6 @@
 use aoc::customs::Group;
+use aoc::dump;
+use aoc::timing;
 use aoc::util::load_input;
 
 fn main() {
@@ -13,4 +15,7 @@ fn main() {
     let collective_answers = res.iter().map(|g| g.collective_answers()).sum::<usize>();
 
     println!("part 2: {}", collective_answers);
+
+    timing::example(6).expect("could not time day");
+    dump::example(6).expect("could not dump model");
 }
@@ -1,4 +1,6 @@
+use aoc::dump;
 use aoc::luggage::Ruleset;
+use aoc::timing;
 use aoc::util::load_input;
 
 fn main() {
@@ -36,4 +38,7 @@ fn main() {
             .count_bags_memoized("shiny gold")
             .expect("could not count bags")
     );
+
+    timing::example(7).expect("could not time day");
+    dump::example(7).expect("could not dump model");
 }
@@ -0,0 +1,154 @@
+//! Step through the boot code, or any program for the VM, with a debugger:
+//!
+//! ```text
+//! cargo run --example 008_debug [path/to/program]
+//! ```
+//!
+//! Type `help` at the prompt for the commands.
+use std::env;
+use std::io::{self, BufRead, Write};
+
+use aoc::debugger::{Debugger, Stop};
+use aoc::util::{load_input, load_lines};
+use aoc::vm::InstructionSet;
+
+const HELP: &str = "\
+s, step [N]       run N instructions, 1 by default
+c, continue       run to a breakpoint, a watch, a repeat or the end
+b, break PC       stop before the instruction at PC
+d, delete PC      remove a breakpoint
+w, watch REG      stop when a register changes, e.g. acc
+u, unwatch REG    stop watching a register
+r, regs           show the registers
+l, list [PC]      show the instructions around PC, the current one by default
+t, trace [N]      show the last N instructions run, 10 by default
+reset             start again from the first instruction
+q, quit";
+
+fn main() {
+    let lines = match env::args().nth(1) {
+        Some(path) => load_lines(&path),
+        None => load_input("008"),
+    }
+    .expect("could not load program");
+    let program = InstructionSet::standard()
+        .program(&lines)
+        .expect("could not parse program");
+    let mut debugger = Debugger::new(&program).expect("could not load program");
+
+    println!("{} instructions, type help for commands", lines.len());
+    let stdin = io::stdin();
+    loop {
+        print!("{}> ", debugger.vm().pc());
+        io::stdout().flush().expect("could not write prompt");
+
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).expect("could not read") == 0 {
+            break;
+        }
+        let words = line.split_whitespace().collect::<Vec<&str>>();
+        let arg = words.get(1).map(|word| word.parse::<usize>());
+
+        let stop = match (words.first().cloned().unwrap_or_default(), arg) {
+            ("", _) => continue,
+            ("s", None) | ("step", None) => debugger.step(),
+            ("s", Some(Ok(n))) | ("step", Some(Ok(n))) => {
+                let mut stop = Ok(Stop::Stepped);
+                for _ in 0..n {
+                    stop = debugger.step();
+                    if !matches!(stop, Ok(Stop::Stepped)) {
+                        break;
+                    }
+                }
+                stop
+            }
+            ("c", None) | ("continue", None) => debugger.resume(),
+            ("b", Some(Ok(pc))) | ("break", Some(Ok(pc))) => {
+                debugger.break_at(pc);
+                continue;
+            }
+            ("d", Some(Ok(pc))) | ("delete", Some(Ok(pc))) => {
+                if !debugger.clear(pc) {
+                    println!("no breakpoint at {}", pc);
+                }
+                continue;
+            }
+            ("w", Some(_)) | ("watch", Some(_)) => {
+                debugger.watch(words[1]);
+                continue;
+            }
+            ("u", Some(_)) | ("unwatch", Some(_)) => {
+                if !debugger.unwatch(words[1]) {
+                    println!("{} is not watched", words[1]);
+                }
+                continue;
+            }
+            ("r", None) | ("regs", None) => {
+                for (name, value) in debugger.vm().registers().iter() {
+                    println!("{:>6} = {}", name, value);
+                }
+                println!("cycles = {}", debugger.vm().cycles());
+                continue;
+            }
+            ("l", arg) | ("list", arg) => {
+                let pc = match arg {
+                    Some(Ok(pc)) => pc,
+                    _ => debugger.vm().pc().max(0) as usize,
+                };
+                list(&debugger, pc);
+                continue;
+            }
+            ("t", arg) | ("trace", arg) => {
+                let n = match arg {
+                    Some(Ok(n)) => n,
+                    _ => 10,
+                };
+                let trace = debugger.trace();
+                for traced in &trace[trace.len().saturating_sub(n)..] {
+                    println!("{}", traced);
+                }
+                continue;
+            }
+            ("reset", None) => {
+                debugger.reset();
+                continue;
+            }
+            ("q", None) | ("quit", None) => break,
+            ("help", _) => {
+                println!("{}", HELP);
+                continue;
+            }
+            _ => {
+                println!("unknown command, type help for commands");
+                continue;
+            }
+        };
+
+        match stop {
+            Ok(Stop::Stepped) => {}
+            Ok(stop) => println!("{}", stop),
+            Err(err) => println!("error: {}", err),
+        }
+        if let Some(traced) = debugger.trace().last() {
+            println!("{}", traced);
+        }
+    }
+}
+
+/// Print the instructions a few either side of `pc`, marking the next one to
+/// run and any breakpoints.
+fn list(debugger: &Debugger, pc: usize) {
+    let instructions = debugger.program().instructions();
+    let breakpoints = debugger.breakpoints().collect::<Vec<usize>>();
+    let current = debugger.vm().pc();
+
+    for at in pc.saturating_sub(5)..(pc + 6).min(instructions.len()) {
+        println!(
+            "{}{} {:>4}  {}",
+            if at as i64 == current { ">" } else { " " },
+            if breakpoints.contains(&at) { "*" } else { " " },
+            at,
+            instructions[at]
+        );
+    }
+}
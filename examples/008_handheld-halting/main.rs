@@ -1,4 +1,6 @@
 use aoc::console::Program;
+use aoc::dump;
+use aoc::timing;
 use aoc::util::load_input;
 
 fn main() {
@@ -25,4 +27,7 @@ fn main() {
             .correct_recursive()
             .expect("could not correct program")
     );
+
+    timing::example(8).expect("could not time day");
+    dump::example(8).expect("could not dump model");
 }
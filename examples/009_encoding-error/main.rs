@@ -1,3 +1,5 @@
+use aoc::dump;
+use aoc::timing;
 use aoc::util::load_input;
 use aoc::xmas::Document;
 
@@ -20,4 +22,7 @@ fn main() {
             .find_weakness_slow(outlier)
             .expect("Could not find weakness")
     );
+
+    timing::example(9).expect("could not time day");
+    dump::example(9).expect("could not dump model");
 }
@@ -1,4 +1,6 @@
 use aoc::adapter::{compute_diffs_in_chain, permutations, permutations_faster, Adapter};
+use aoc::dump;
+use aoc::timing;
 use aoc::util::load_input;
 
 fn main() {
@@ -17,4 +19,7 @@ fn main() {
         "part 2: {}",
         permutations_faster(&adapters).expect("could not count permutation")
     );
+
+    timing::example(10).expect("could not time day");
+    dump::example(10).expect("could not dump model");
 }
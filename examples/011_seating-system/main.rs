@@ -1,32 +1,34 @@
+use aoc::dump;
+use aoc::render::Animator;
 use aoc::seating::Area;
+use aoc::timing;
 use aoc::util::load_input;
 
 fn main() {
     let lines = load_input("011").expect("could not load input");
-    let mut area = Area::new(&lines, Some(1), 4).expect("Could not make seating area");
-
-    loop {
-        let new_area = area.step().expect("Could not simulate next step");
+    let mut animator = Animator::from_env().expect("could not set up animation");
 
-        if area == new_area {
-            break;
-        }
+    let settle = |area: &mut Area| {
+        let new_area = area.step()?;
+        let changed = new_area != *area;
+        *area = new_area;
+        Ok(changed)
+    };
 
-        area = new_area;
-    }
+    let mut area = Area::new(&lines, Some(1), 4).expect("Could not make seating area");
+    animator
+        .animate("part 1", &mut area, usize::MAX, settle)
+        .expect("Could not simulate next step");
 
     println!("part 1: {}", area.occupied_seats());
 
     let mut area = Area::new(&lines, None, 5).expect("Could not make seating area");
-    loop {
-        let new_area = area.step().expect("Could not simulate next step");
-
-        if area == new_area {
-            break;
-        }
-
-        area = new_area;
-    }
+    animator
+        .animate("part 2", &mut area, usize::MAX, settle)
+        .expect("Could not simulate next step");
 
     println!("part 2: {}", area.occupied_seats());
+
+    timing::example(11).expect("could not time day");
+    dump::example(11).expect("could not dump model");
 }
@@ -1,4 +1,6 @@
+use aoc::dump;
 use aoc::navigation::{Plan, Ship, WaypointShip};
+use aoc::timing;
 use aoc::util::load_input;
 
 fn main() {
@@ -12,4 +14,7 @@ fn main() {
     let mut ship = WaypointShip::new();
     plan.execute(&mut ship).expect("could not execute plan");
     println!("part 2: {}", ship.manhattan_distance());
+
+    timing::example(12).expect("could not time day");
+    dump::example(12).expect("could not dump model");
 }
@@ -1,9 +1,13 @@
+use aoc::budget::Budget;
+use aoc::dump;
 use aoc::shuttle::Schedule;
+use aoc::timing;
 use aoc::util::load_input;
 use std::str::FromStr;
 
 fn main() {
     let lines = load_input("013").expect("could not load input");
+    let budget = Budget::from_env().expect("could not set up time budget");
     let mut lines = lines.iter();
 
     let start = lines
@@ -15,7 +19,8 @@ fn main() {
         Schedule::from_str(lines.next().expect("no second line")).expect("could not make schedule");
 
     let res = schedule
-        .earliest_departure(start)
+        .earliest_departure_within(start, &budget)
+        .expect("ran out of time")
         .expect("could not find bus");
 
     println!("part 1: {}", res.0 * res.1.id());
@@ -23,7 +28,11 @@ fn main() {
     println!(
         "part 2: {}",
         schedule
-            .sync_departures()
+            .sync_departures_within(&budget)
+            .expect("ran out of time")
             .expect("could not find sync departure")
     );
+
+    timing::example(13).expect("could not time day");
+    dump::example(13).expect("could not dump model");
 }
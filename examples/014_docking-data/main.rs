@@ -1,15 +1,25 @@
 use aoc::docking::Initializer;
+use aoc::dump;
+use aoc::memory;
+use aoc::timing;
 use aoc::util::load_input;
 
 fn main() {
     let lines = load_input("014").expect("could not load input");
-    let program = Initializer::initialize(&lines).expect("could not initialize");
+    let (program, usage) = memory::measure(|| Initializer::initialize(&lines));
+    let program = program.expect("could not initialize");
 
     println!("part 1: {}", program.memory_sum());
+    memory::report("part 1", usage);
 
-    let program = Initializer::initialize_v2(&lines).expect("could not initialize");
+    let (program, usage) = memory::measure(|| Initializer::initialize_v2(&lines));
+    let program = program.expect("could not initialize");
     println!("part 2: {}", program.memory_sum());
+    memory::report("part 2", usage);
 
     let program = Initializer::initialize_v2_memoized(&lines).expect("could not initialize");
     println!("part 2: {}", program.memory_sum());
+
+    timing::example(14).expect("could not time day");
+    dump::example(14).expect("could not dump model");
 }
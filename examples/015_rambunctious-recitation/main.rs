@@ -1,22 +1,30 @@
+use aoc::budget::Budget;
+use aoc::dump;
 use aoc::game::Game;
+use aoc::memory;
+use aoc::progress::ProgressBar;
+use aoc::timing;
 use aoc::util::load_input;
 use std::str::FromStr;
 
 fn main() {
     let lines = load_input("015").expect("could not load input");
+    let budget = Budget::from_env().expect("could not set up time budget");
     let mut game = Game::from_str(lines.first().expect("no lines in input"))
         .expect("could not initialize game");
 
-    while game.get_turn() <= 2020 {
-        game.take_turn().expect("could not take turn");
-    }
+    let (part_one, usage) = memory::measure(|| game.play_until(2020, &budget));
+    println!("part 1: {}", part_one.expect("could not take turn"));
+    memory::report("part 1", usage);
 
-    println!("part 1: {}", game.get_last_spoken());
-
-    while game.get_turn() <= 30_000_000 {
-        game.take_turn().expect("could not take turn");
-    }
-
-    println!("part 2: {}", game.get_last_spoken());
+    let mut bar = ProgressBar::from_env("part 2");
+    let (part_two, usage) = memory::measure(|| {
+        game.play_until_with(30_000_000, &budget, |progress, _| bar.update(progress))
+    });
+    println!("part 2: {}", part_two.expect("could not take turn"));
+    memory::report("part 2", usage);
     println!("part 2 game state: {}", game);
+
+    timing::example(15).expect("could not time day");
+    dump::example(15).expect("could not dump model");
 }
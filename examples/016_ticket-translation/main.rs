@@ -1,58 +1,38 @@
-use aoc::error::Result;
-use aoc::ticket::{Ticket, TicketValidator};
+use aoc::dump;
+use aoc::ticket::{Notes, Ticket};
+use aoc::timing;
 use aoc::util::load_input;
-use std::str::FromStr;
 
 fn main() {
     let lines = load_input("016").expect("could not load input");
-    let mut parts = lines.split(|line| line.is_empty());
 
-    let rules = parts.next().expect("invalid input");
+    let mut notes = Notes::from_input(&lines).expect("could not parse notes");
 
-    let mut validator = TicketValidator::from_input(&rules).expect("could not make validator");
+    println!("part 1: {}", notes.error_rate());
 
-    let our_ticket = Ticket::from_str(
-        parts
-            .next()
-            .expect("invalid input, missing our ticket")
-            .get(1)
-            .expect("invalid input, missing our ticket values"),
-    )
-    .expect("could not make our ticket");
-
-    let mut other_tickets = parts.next().expect("invalid input, no nearby tickets")[1..]
+    let valid_tickets = notes
+        .nearby_tickets
         .iter()
-        .map(|ticket| Ticket::from_str(ticket))
-        .collect::<Result<Vec<Ticket>>>()
-        .expect("could not make other tickets");
-
-    let mut sum = 0;
-    for ticket in other_tickets.iter_mut() {
-        if let Some(vals) = validator.validate(ticket) {
-            sum += vals.iter().sum::<usize>();
-        }
-    }
-
-    println!("part 1: {}", sum);
-
-    let valid_tickets = other_tickets
-        .into_iter()
         .filter(|ticket| ticket.is_valid)
+        .cloned()
         .collect::<Vec<Ticket>>();
 
-    // println!("{:#?}", validator.make_col_map(&valid_tickets).iter().map(|col| col.len()).collect::<Vec<usize>>());
-
-    validator
+    notes
+        .validator
         .determine_rule_order_fast(&valid_tickets)
         .expect("could not determine rule order");
 
-    let res = validator
+    let res = notes
+        .validator
         .rules
         .iter()
         .enumerate()
         .filter(|(_, r)| r.name.starts_with("departure"))
-        .map(|(index, _)| our_ticket.values[index])
+        .map(|(index, _)| notes.ticket.values[index])
         .product::<usize>();
 
     println!("part 2: {}", res);
+
+    timing::example(16).expect("could not time day");
+    dump::example(16).expect("could not dump model");
 }
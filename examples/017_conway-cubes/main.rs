@@ -1,18 +1,42 @@
+use aoc::dump;
 use aoc::power::{Coordinate, FourDCoordinate, Grid};
+use aoc::progress::ProgressBar;
+use aoc::render::Animator;
+use aoc::timing;
 use aoc::util::load_input;
 
 fn main() {
     let lines = load_input("017").expect("could not load input");
+    let mut animator = Animator::from_env().expect("could not set up animation");
 
     let mut grid: Grid<Coordinate> = Grid::from_input(&lines);
 
-    grid.boot(6);
+    animator
+        .animate("part 1", &mut grid, 6, |grid| {
+            grid.cycle();
+            Ok(true)
+        })
+        .expect("could not draw frame");
 
     println!("part 1: {}", grid.active());
 
     let mut grid: Grid<FourDCoordinate> = Grid::from_input(&lines);
 
-    grid.boot(6);
+    // a bar would only get in the way of the frames
+    if animator.is_active() {
+        animator
+            .animate("part 2", &mut grid, 6, |grid| {
+                grid.cycle();
+                Ok(true)
+            })
+            .expect("could not draw frame");
+    } else {
+        let mut bar = ProgressBar::from_env("part 2");
+        grid.boot_with(6, |progress, _| bar.update(progress));
+    }
 
     println!("part 2: {}", grid.active());
+
+    timing::example(17).expect("could not time day");
+    dump::example(17).expect("could not dump model");
 }
@@ -1,4 +1,6 @@
 use aoc::calculator::{AdvancedParser, Parser, StrParser};
+use aoc::dump;
+use aoc::timing;
 use aoc::util::load_input;
 
 fn main() {
@@ -11,4 +13,7 @@ fn main() {
     let advanced_parser = AdvancedParser {};
     let res: i64 = lines.iter().map(|line| advanced_parser.eval(line)).sum();
     println!("part 2: {}", res);
+
+    timing::example(18).expect("could not time day");
+    dump::example(18).expect("could not dump model");
 }
@@ -1,12 +1,17 @@
+use aoc::dump;
+use aoc::input::Input;
 use aoc::message::get_matching_messages_b;
-use aoc::util::{load_input, load_named_input};
+use aoc::timing;
 
 fn main() {
-    let lines = load_input("019").expect("could not load input");
-    let res = get_matching_messages_b(&lines).expect("could operate on input");
+    let input = Input::load("019").expect("could not load input");
+    let res = get_matching_messages_b(&input).expect("could operate on input");
     println!("part 1: {}", res.len());
 
-    let lines = load_named_input("019", "input_2").expect("could not load input");
-    let res = get_matching_messages_b(&lines).expect("could operate on input");
+    let input = Input::load_named("019", "input_2").expect("could not load input");
+    let res = get_matching_messages_b(&input).expect("could operate on input");
     println!("part 2: {}", res.len());
+
+    timing::example(19).expect("could not time day");
+    dump::example(19).expect("could not dump model");
 }
@@ -1,12 +1,23 @@
-use aoc::jigsaw::{Grid, Tile};
+use aoc::budget::Budget;
+use aoc::dump;
+use aoc::jigsaw::{Arrangement, Grid, Tile};
+use aoc::render::Animator;
+use aoc::timing;
 use aoc::util::{load_input, test_input};
 use std::collections::HashMap;
 
 fn main() {
     let lines = load_input("020").expect("could not load input");
+    let mut animator = Animator::from_env().expect("could not set up animation");
+    let budget = Budget::from_env().expect("could not set up time budget");
     let mut grid = Grid::from_input(&lines).expect("could not construct grid");
 
-    grid.arrange();
+    grid.arrange_with(&budget, |arrangement| {
+        animator
+            .frame("arranging", &Arrangement(arrangement))
+            .expect("could not draw frame")
+    })
+    .expect("could not arrange tiles");
 
     println!(
         "part 1: {}",
@@ -37,8 +48,15 @@ fn main() {
 
     for (_, tile) in combined_grid.variant_map.iter_mut() {
         if tile.find_shape(&shape) > 0 {
+            animator
+                .frame("sea monsters", tile)
+                .expect("could not draw frame");
             println!("part 2: {}", tile.count_char('#'));
             break;
         }
     }
+
+    animator.finish().expect("could not write frames");
+    timing::example(20).expect("could not time day");
+    dump::example(20).expect("could not dump model");
 }
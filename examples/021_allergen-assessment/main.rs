@@ -1,4 +1,6 @@
+use aoc::dump;
 use aoc::food::{Allergen, Ingredient, ShoppingList};
+use aoc::timing;
 use aoc::util::load_input;
 
 fn main() {
@@ -29,4 +31,7 @@ fn main() {
         .join(",");
 
     println!("part 2: {}", res);
+
+    timing::example(21).expect("could not time day");
+    dump::example(21).expect("could not dump model");
 }
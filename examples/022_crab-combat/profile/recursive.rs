@@ -1,9 +1,9 @@
 use aoc::combat::RecursiveGame;
-use aoc::util::load_input;
+use aoc::input::Input;
 
 fn main() {
-    let lines = load_input("022").expect("could not load input");
-    let mut game = RecursiveGame::from_input(&lines).expect("could not create game");
+    let input = Input::load("022").expect("could not load input");
+    let mut game = RecursiveGame::from_input(&input).expect("could not create game");
     let winner = game.play().expect("could not play game");
     println!("part 2: {}", winner.0.score());
 }
@@ -1,14 +1,47 @@
+use aoc::budget::Budget;
 use aoc::cups::Game;
+use aoc::dump;
+use aoc::memory;
+use aoc::progress::ProgressBar;
+use aoc::render::Animator;
+use aoc::timing;
+use aoc::util::load_input;
 use std::str::FromStr;
 
 fn main() {
-    let mut game = Game::from_str("459672813").expect("could not make game");
+    let lines = load_input("023").expect("could not load input");
+    let labels = lines.first().expect("no lines in input");
+    let budget = Budget::from_env().expect("could not set up time budget");
+    let mut animator = Animator::from_env().expect("could not set up animation");
 
-    game.simulate(100);
+    let mut game = Game::from_str(labels).expect("could not make game");
+
+    let (animated, usage) = memory::measure(|| {
+        animator.animate("part 1", &mut game, 100, |game| {
+            game.round();
+            Ok(true)
+        })
+    });
+    animated.expect("could not draw frame");
 
     println!("part 1: {}", game.order_string());
+    memory::report("part 1", usage);
 
-    let mut game = Game::from_str_with_len("459672813", 1_000_000).expect("could not make game");
-    game.simulate(10_000_000);
+    // ten million frames would be a bit much, so only the end is drawn
+    let mut bar = ProgressBar::from_env("part 2");
+    let (game, usage) = memory::measure(|| {
+        let mut game = Game::from_str_with_len(labels, 1_000_000).expect("could not make game");
+        game.simulate_with(10_000_000, &budget, |progress, _| bar.update(progress))
+            .expect("could not move cups");
+        game
+    });
+    animator
+        .frame("part 2: move 10000000", &game)
+        .expect("could not draw frame");
     println!("part 2: {}", game.crappy_checksum());
+    memory::report("part 2", usage);
+
+    animator.finish().expect("could not write frames");
+    timing::example(23).expect("could not time day");
+    dump::example(23).expect("could not dump model");
 }
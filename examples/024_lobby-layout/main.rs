@@ -1,8 +1,13 @@
+use aoc::dump;
 use aoc::lobby::{Address, Face, Lobby};
+use aoc::progress::ProgressBar;
+use aoc::render::Animator;
+use aoc::timing;
 use aoc::util::load_input;
 
 fn main() {
     let lines = load_input("024").expect("could not load input");
+    let mut animator = Animator::from_env().expect("could not set up animation");
     let mut lobby = Lobby::new();
     let addresses = Address::from_input(&lines).expect("could not load addresses");
 
@@ -10,6 +15,20 @@ fn main() {
 
     println!("part 1: {}", lobby.count_tiles(&Face::Black));
 
-    lobby.simulate(100);
+    // a bar would only get in the way of the frames
+    if animator.is_active() {
+        animator
+            .animate("part 2", &mut lobby, 100, |lobby| {
+                lobby.generation();
+                Ok(true)
+            })
+            .expect("could not draw frame");
+    } else {
+        let mut bar = ProgressBar::from_env("part 2");
+        lobby.simulate_with(100, |progress, _| bar.update(progress));
+    }
     println!("part 2: {}", lobby.count_tiles(&Face::Black));
+
+    timing::example(24).expect("could not time day");
+    dump::example(24).expect("could not dump model");
 }
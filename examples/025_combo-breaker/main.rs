@@ -1,4 +1,6 @@
+use aoc::dump;
 use aoc::encryption::{Device, Key};
+use aoc::timing;
 use aoc::util::load_input;
 
 fn main() {
@@ -17,4 +19,7 @@ fn main() {
     let encryption_key = device1.encryption_key(&device2.public_key);
 
     println!("part 1: {:?}", encryption_key);
+
+    timing::example(25).expect("could not time day");
+    dump::example(25).expect("could not dump model");
 }
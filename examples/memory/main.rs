@@ -0,0 +1,23 @@
+use std::env;
+use std::process;
+
+use aoc::memory::{self, Report};
+use aoc::solution;
+
+fn main() {
+    if !memory::enabled() {
+        eprintln!("heap usage is only counted with --features count-alloc");
+        process::exit(1);
+    }
+
+    let solutions = match env::args().nth(1) {
+        Some(day) => {
+            let day = day.parse::<usize>().expect("day must be a number");
+            vec![solution::get(day).expect("no solution for day")]
+        }
+        None => solution::solutions().to_vec(),
+    };
+
+    let report = Report::run(&solutions).expect("could not profile solutions");
+    print!("{}", report);
+}
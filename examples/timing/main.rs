@@ -0,0 +1,14 @@
+use std::env;
+
+use aoc::solution;
+use aoc::timing::{Format, Report};
+
+fn main() {
+    let format = env::args()
+        .nth(1)
+        .map(|arg| arg.parse::<Format>().expect("unknown format"))
+        .unwrap_or(Format::Table);
+
+    let report = Report::run(solution::solutions()).expect("could not time solutions");
+    print!("{}", report.render(format));
+}
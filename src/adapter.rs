@@ -2,18 +2,25 @@ use std::cmp::{Ord, Ordering};
 use std::collections::HashMap;
 
 use crate::error::{AocError, Result};
+use crate::input::Input;
+use crate::solution::{Part, Solution, Variant};
+use crate::trace;
+use crate::util::{collect_lines, parse_lines, Collected};
 
 #[derive(Debug, Clone, Eq, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Adapter {
     rating: i32,
 }
 
 impl Adapter {
-    pub fn from_input(input: &[String]) -> Result<Vec<Self>> {
-        input
-            .iter()
-            .map(|i| Self::new(i))
-            .collect::<Result<Vec<Self>>>()
+    pub fn from_input<S: AsRef<str>>(input: &[S]) -> Result<Vec<Self>> {
+        parse_lines(input, Self::new)
+    }
+
+    /// Every adapter that parsed, and the errors of those that did not.
+    pub fn from_input_lenient<S: AsRef<str>>(input: &[S]) -> Collected<Vec<Self>> {
+        collect_lines(input, Self::new)
     }
 
     pub fn from_rating(rating: i32) -> Result<Self> {
@@ -62,6 +69,7 @@ impl Ord for Adapter {
 }
 
 pub fn compute_diffs_in_chain(adapters: &[Adapter]) -> Result<i32> {
+    let _span = trace::span!("compute_diffs_in_chain");
     if adapters.is_empty() {
         return Err(AocError::InvalidInput("No adapters".to_string()));
     }
@@ -137,6 +145,7 @@ fn recur(adapters: &[Adapter], start: usize, cache: &mut HashMap<usize, usize>)
 }
 
 pub fn permutations_faster(adapters: &[Adapter]) -> Result<i64> {
+    let _span = trace::span!("permutations_faster");
     if adapters.is_empty() {
         return Err(AocError::InvalidInput("No adapters".to_string()));
     }
@@ -204,44 +213,51 @@ fn recur_slow(adapters: &[Adapter], start: usize) -> usize {
     return local_count;
 }
 
+pub struct AdapterArray;
+
+impl Solution for AdapterArray {
+    const DAY: usize = 10;
+    const NAME: &'static str = "Adapter Array";
+
+    type Parsed = Vec<Adapter>;
+
+    const VARIANTS: &'static [Variant<Self::Parsed>] = &[
+        Variant {
+            part: Part::Two,
+            name: "permutations",
+            slow: false,
+            solve: |adapters| Ok(permutations(adapters)?.to_string()),
+        },
+        // counts every arrangement one by one, which never finishes on a real
+        // input
+        Variant {
+            part: Part::Two,
+            name: "permutations_slow",
+            slow: true,
+            solve: |adapters| Ok(permutations_slow(adapters)?.to_string()),
+        },
+    ];
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed> {
+        Adapter::from_input(&input.lines())
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String> {
+        Ok(compute_diffs_in_chain(parsed)?.to_string())
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String> {
+        Ok(permutations_faster(parsed)?.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::util::load_example;
 
     fn input() -> Vec<String> {
-        vec![
-            "28".to_string(),
-            "33".to_string(),
-            "18".to_string(),
-            "42".to_string(),
-            "31".to_string(),
-            "14".to_string(),
-            "46".to_string(),
-            "20".to_string(),
-            "48".to_string(),
-            "47".to_string(),
-            "24".to_string(),
-            "23".to_string(),
-            "49".to_string(),
-            "45".to_string(),
-            "19".to_string(),
-            "38".to_string(),
-            "39".to_string(),
-            "11".to_string(),
-            "1".to_string(),
-            "32".to_string(),
-            "25".to_string(),
-            "35".to_string(),
-            "8".to_string(),
-            "17".to_string(),
-            "7".to_string(),
-            "9".to_string(),
-            "4".to_string(),
-            "2".to_string(),
-            "34".to_string(),
-            "10".to_string(),
-            "3".to_string(),
-        ]
+        load_example("010", 1).unwrap()
     }
 
     #[test]
@@ -326,4 +342,16 @@ mod tests {
             assert_eq!(res[res.len() - 1].rating(), 49);
         }
     }
+
+    #[test]
+    fn readme_examples() {
+        let small = load_example("010", 0).unwrap();
+        let parsed = AdapterArray.parse(&Input::from_lines(&small)).unwrap();
+        assert_eq!(AdapterArray.part_one(&parsed).unwrap(), "35");
+        assert_eq!(AdapterArray.part_two(&parsed).unwrap(), "8");
+
+        let parsed = AdapterArray.parse(&Input::from_lines(&input())).unwrap();
+        assert_eq!(AdapterArray.part_one(&parsed).unwrap(), "220");
+        assert_eq!(AdapterArray.part_two(&parsed).unwrap(), "19208");
+    }
 }
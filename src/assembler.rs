@@ -0,0 +1,281 @@
+//! Console programs with labels instead of counted offsets, which makes test
+//! programs easier to write and to read back. Disassembling labels every
+//! instruction jumped to.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::console::{Operand, Program};
+use crate::error::{AocError, Result};
+use crate::trace;
+use crate::vm::{is_label, InstructionSet, Kind};
+
+/// Turns console programs written with labels and comments into `Program`s,
+/// and `Program`s back into text.
+///
+/// A label is a name and a colon at the start of a line, e.g. `loop:`, and
+/// names the next instruction, or the end of the program if there is none.
+/// Wherever an opcode takes a jump offset a label may be used instead.
+/// Comments run from `;` or `#` to the end of the line:
+///
+/// ```text
+/// start:  acc +1      ; count
+///         jmp start
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Assembler {
+    set: InstructionSet,
+}
+
+/// A line with its comment and any label taken out. The code keeps the
+/// columns it had, so errors in it point at the right place.
+struct Line<'a> {
+    number: usize,
+    text: &'a str,
+    label: Option<&'a str>,
+    code: String,
+}
+
+impl Assembler {
+    pub fn new() -> Self {
+        Assembler::default()
+    }
+
+    pub fn with_set(set: InstructionSet) -> Self {
+        Assembler { set }
+    }
+
+    pub fn assemble<S: AsRef<str>>(&self, input: &[S]) -> Result<Program> {
+        let _span = trace::span!("Assembler::assemble");
+        let lines = input
+            .iter()
+            .enumerate()
+            .map(|(index, line)| split(index + 1, line.as_ref()))
+            .collect::<Vec<Line>>();
+
+        // a label is the address of the next instruction
+        let mut labels = HashMap::new();
+        let mut address = 0;
+        for line in &lines {
+            if let Some(label) = line.label {
+                if labels.insert(label, address).is_some() {
+                    return Err(AocError::DuplicateLabel(label.to_string())
+                        .locate(&[line.text], line.number));
+                }
+            }
+            if !line.code.trim().is_empty() {
+                address += 1;
+            }
+        }
+
+        let mut instructions = Vec::new();
+        for line in lines.iter().filter(|line| !line.code.trim().is_empty()) {
+            let pc = instructions.len() as i64;
+            let instruction = self
+                .set
+                .instruction_with_labels(&line.code, |label| {
+                    labels.get(label).map(|&address| address - pc)
+                })
+                .map_err(|err| match err.locate(&[line.text], line.number) {
+                    AocError::Located(mut span, err) => {
+                        span.text = line.text.to_string();
+                        AocError::Located(span, err)
+                    }
+                    err => err,
+                })?;
+            instructions.push(instruction);
+        }
+
+        Ok(Program::from_instructions(instructions))
+    }
+
+    /// The program as text with a label on every instruction jumped to,
+    /// named `l0`, `l1` and so on in order, and the instructions indented.
+    /// Assembling it gives the same program back, and jumps out of the
+    /// program keep their offsets.
+    pub fn disassemble(&self, program: &Program) -> Result<String> {
+        let instructions = program.instructions();
+        let len = instructions.len() as i64;
+
+        // the address jumped to by each operand that is a jump offset
+        let mut jumps = Vec::new();
+        for (pc, instruction) in instructions.iter().enumerate() {
+            let name = instruction.op().name();
+            let opcode = self
+                .set
+                .get(name)
+                .ok_or_else(|| AocError::UnknownOperation(name.to_string()))?;
+            let targets = opcode
+                .operands()
+                .iter()
+                .zip(instruction.args())
+                .map(|(kind, arg)| match (kind, arg) {
+                    (Kind::Offset, Operand::Value(offset)) => Some(pc as i64 + offset)
+                        .filter(|target| (0..=len).contains(target))
+                        .map(|target| target as usize),
+                    _ => None,
+                })
+                .collect::<Vec<Option<usize>>>();
+            jumps.push(targets);
+        }
+
+        let mut targets = jumps
+            .iter()
+            .flatten()
+            .flatten()
+            .cloned()
+            .collect::<Vec<usize>>();
+        targets.sort_unstable();
+        targets.dedup();
+        let label = |address: usize| {
+            targets
+                .binary_search(&address)
+                .ok()
+                .map(|index| format!("l{}", index))
+        };
+
+        let mut out = String::new();
+        for (pc, instruction) in instructions.iter().enumerate() {
+            if let Some(name) = label(pc) {
+                let _ = writeln!(out, "{}:", name);
+            }
+
+            let _ = write!(out, "    {}", instruction.op());
+            for (arg, target) in instruction.args().iter().zip(&jumps[pc]) {
+                let arg = target.and_then(label).unwrap_or_else(|| arg.to_string());
+                let _ = write!(out, " {}", arg);
+            }
+            out.push('\n');
+        }
+        if let Some(name) = label(instructions.len()) {
+            let _ = writeln!(out, "{}:", name);
+        }
+
+        Ok(out)
+    }
+}
+
+fn split(number: usize, text: &str) -> Line<'_> {
+    let code = match text.find([';', '#']) {
+        Some(comment) => &text[..comment],
+        None => text,
+    };
+
+    let trimmed = code.trim_start();
+    let label = trimmed
+        .find(':')
+        .map(|colon| &trimmed[..colon])
+        .filter(|label| is_label(label));
+
+    let code = match label {
+        Some(label) => {
+            // blank out the label so the code keeps its columns
+            let end = code.len() - trimmed.len() + label.len() + 1;
+            format!(
+                "{}{}",
+                " ".repeat(code[..end].chars().count()),
+                &code[end..]
+            )
+        }
+        None => code.to_string(),
+    };
+
+    Line {
+        number,
+        text,
+        label,
+        code,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::span::Span;
+    use crate::util::test_input;
+
+    #[test]
+    fn assembling() {
+        let source = test_input(
+            "
+            # the example from day 8, with labels
+                    nop +0
+            top:    acc +1
+                    jmp skip    ; forwards
+            back:   acc +3
+                    jmp top
+                    acc -99
+            skip:
+                    acc +1
+                    jmp back
+                    acc +6
+            ",
+        );
+        let program = Assembler::new().assemble(&source).unwrap();
+        let expected = Program::new(&[
+            "nop +0", "acc +1", "jmp +4", "acc +3", "jmp -3", "acc -99", "acc +1", "jmp -4",
+            "acc +6",
+        ])
+        .unwrap();
+        assert_eq!(program, expected);
+    }
+
+    #[test]
+    fn registers_and_the_end() {
+        let program = Assembler::new()
+            .assemble(&[
+                "mov a +3",
+                "loop: acc a",
+                "subr a +1",
+                "jz a done",
+                "jmp loop",
+                "done:",
+            ])
+            .unwrap();
+        assert_eq!(
+            Assembler::new().disassemble(&program).unwrap(),
+            "    mov a +3\nl0:\n    acc a\n    subr a +1\n    jz a l1\n    jmp l0\nl1:\n"
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let program = Program::new(&[
+            "nop +0", "acc +1", "jmp +4", "acc +3", "jmp -3", "acc -99", "acc +1", "jmp -4",
+            "acc +6", "jmp +5",
+        ])
+        .unwrap();
+        let text = Assembler::new().disassemble(&program).unwrap();
+        assert_eq!(
+            text,
+            "    nop +0\n\
+             l0:\n    acc +1\n    jmp l2\n\
+             l1:\n    acc +3\n    jmp l0\n    acc -99\n\
+             l2:\n    acc +1\n    jmp l1\n    acc +6\n    jmp +5\n"
+        );
+
+        let lines = text.lines().collect::<Vec<&str>>();
+        let assembled = Assembler::new().assemble(&lines).unwrap();
+        assert_eq!(assembled, program);
+        assert_eq!(Assembler::new().disassemble(&assembled).unwrap(), text);
+    }
+
+    #[test]
+    fn errors() {
+        let assembler = Assembler::new();
+
+        let err = assembler.assemble(&["top: acc +1", "jmp tpo"]).unwrap_err();
+        assert!(err.to_string().contains("Unknown label: 'tpo'"));
+        assert_eq!(err.span().unwrap(), &Span::new(2, 5, 3, "jmp tpo"));
+
+        let err = assembler
+            .assemble(&["a: nop +0", "a: acc +x ; oops"])
+            .unwrap_err();
+        assert!(err.to_string().contains("Duplicate label: 'a'"));
+
+        let err = assembler
+            .assemble(&["nop +0", "x: acc +y ; oops"])
+            .unwrap_err();
+        assert_eq!(err.span().unwrap(), &Span::new(2, 8, 2, "x: acc +y ; oops"));
+    }
+}
@@ -0,0 +1,46 @@
+use std::process;
+use std::time::Duration;
+
+use aoc::server::Server;
+use structopt::StructOpt;
+
+/// Answer puzzle inputs over HTTP. POST an input to /day/<day> or
+/// /day/<day>/part/<part> to get the answers, timings or error as JSON, or
+/// GET /days for the days there are.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "aoc-server")]
+struct Options {
+    /// The address to listen on
+    #[structopt(short, long, default_value = "127.0.0.1:8020")]
+    addr: String,
+
+    /// Give up on a request after this many seconds, with a 503
+    #[structopt(long, parse(try_from_str = seconds))]
+    timeout: Option<Duration>,
+}
+
+fn seconds(s: &str) -> Result<Duration, String> {
+    s.parse::<f64>()
+        .ok()
+        .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+        .map(Duration::from_secs_f64)
+        .ok_or_else(|| format!("not a number of seconds: '{}'", s))
+}
+
+fn main() {
+    let options = Options::from_args();
+
+    let server = match Server::bind(&options.addr) {
+        Ok(server) => match options.timeout {
+            Some(timeout) => server.with_timeout(timeout),
+            None => server,
+        },
+        Err(e) => {
+            eprintln!("error: {}", e.report());
+            process::exit(1);
+        }
+    };
+
+    eprintln!("listening on http://{}", server.addr());
+    server.run();
+}
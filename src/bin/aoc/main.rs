@@ -0,0 +1,401 @@
+use std::process;
+use std::time::{Duration, Instant};
+
+use aoc::budget::Budget;
+
+use aoc::crosscheck;
+#[cfg(feature = "serde")]
+use aoc::dump;
+use aoc::error::Result;
+#[cfg(feature = "fetch")]
+use aoc::fetch::Fetcher;
+use aoc::gen;
+use aoc::input::{Input, Source};
+use aoc::runner;
+use aoc::solution::{self, Part, Solver};
+use aoc::timing::{Format, Report};
+use aoc::trace;
+use aoc::util::load_example;
+use aoc::verify::{self, Answers, Summary};
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "aoc", about = "Advent of Code 2020 solutions")]
+enum Command {
+    /// Run the solution for a single day
+    Run {
+        /// The day to run, e.g. 16 or 016
+        day: usize,
+
+        /// Only run the given part (1 or 2)
+        #[structopt(short, long)]
+        part: Option<Part>,
+
+        /// Read the puzzle input from this file, or from stdin if '-', instead
+        /// of examples/<day>_<name>/input. The examples directory can be moved
+        /// with AOC_EXAMPLES_DIR
+        #[structopt(short, long)]
+        input: Option<Source>,
+
+        /// Give up with a timeout error after this many seconds
+        #[structopt(long, parse(try_from_str = seconds))]
+        timeout: Option<Duration>,
+    },
+
+    /// Run the solutions for every day
+    All {
+        /// Run the days on a thread pool. A day that fails or panics is
+        /// reported without stopping the others
+        #[structopt(short = "j", long)]
+        parallel: bool,
+
+        /// Give up on a day after this many seconds. In parallel the other
+        /// days carry on, otherwise the run stops there
+        #[structopt(long, parse(try_from_str = seconds))]
+        timeout: Option<Duration>,
+    },
+
+    /// List the alternate implementations registered for each day
+    Variants {
+        /// Only list the given day, otherwise list every day
+        day: Option<usize>,
+    },
+
+    /// Run every alternate implementation and report any that disagree,
+    /// exiting non-zero if one does
+    CrossCheck {
+        /// Only check the given day, otherwise check every day
+        day: Option<usize>,
+
+        /// Also run variants too slow for a full puzzle input
+        #[structopt(long)]
+        slow: bool,
+
+        /// Read the puzzle input from this file, or from stdin if '-'. Needs
+        /// a day, as no one input suits every day
+        #[structopt(short, long, requires = "day")]
+        input: Option<Source>,
+    },
+
+    /// Time parsing and each part, for one day or every day
+    Time {
+        /// Only time the given day, otherwise time every day
+        day: Option<usize>,
+
+        /// How to print the report: table, json or markdown
+        #[structopt(short, long, default_value = "table")]
+        format: Format,
+    },
+
+    /// Check answers, including those of README examples, against
+    /// examples/<day>_<name>/answers, exiting non-zero on any mismatch
+    Verify {
+        /// Only verify the given day, otherwise verify every day
+        day: Option<usize>,
+    },
+
+    /// Print a random but valid puzzle input, the same for the same seed
+    Gen {
+        /// The day to generate an input for
+        day: usize,
+
+        /// The seed for the random number generator
+        #[structopt(long, default_value = "0")]
+        seed: u64,
+
+        /// How big an input to make, usually in lines or records. Each day
+        /// clamps it to what its puzzle can handle
+        #[structopt(long, default_value = "100")]
+        size: usize,
+    },
+
+    /// Print a day's parsed input and answers as JSON
+    #[cfg(feature = "serde")]
+    Dump {
+        /// The day to dump
+        day: usize,
+
+        /// Read the puzzle input from this file, or from stdin if '-'
+        #[structopt(short, long)]
+        input: Option<Source>,
+    },
+
+    /// Download a day's input and README into examples/<day>_<name>, using
+    /// the session cookie in AOC_SESSION. Files already there are kept
+    #[cfg(feature = "fetch")]
+    Fetch {
+        /// The day to fetch
+        day: usize,
+
+        /// Fetch from here instead of AOC_BASE_URL or adventofcode.com
+        #[structopt(long)]
+        base_url: Option<String>,
+    },
+}
+
+fn seconds(s: &str) -> std::result::Result<Duration, String> {
+    s.parse::<f64>()
+        .ok()
+        .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+        .map(Duration::from_secs_f64)
+        .ok_or_else(|| format!("not a number of seconds: '{}'", s))
+}
+
+fn budget(timeout: Option<Duration>) -> Budget {
+    timeout.map(Budget::limit).unwrap_or_default()
+}
+
+fn source(day: usize, input: Option<&Source>) -> Source {
+    match input {
+        Some(source) => source.clone(),
+        None => Source::day(day),
+    }
+}
+
+fn run(day: usize, part: Option<Part>, input: Option<&Source>) -> Result<()> {
+    let solution = solution::get(day)?;
+    let parts = match part {
+        Some(part) => vec![part],
+        None => solution.parts().to_vec(),
+    };
+
+    let source = source(day, input);
+    let input = source.load()?;
+    let parsed = solution
+        .parse_input(&input)
+        .map_err(|e| e.in_file(&source.name()))?;
+
+    for part in parts {
+        println!("{}: {}", part, solution.solve_part(parsed.as_ref(), part)?);
+    }
+
+    Ok(())
+}
+
+fn run_parallel(timeout: Option<Duration>) -> bool {
+    let start = Instant::now();
+    let runs = match timeout {
+        Some(limit) => runner::run_all_within(solution::solutions(), limit),
+        None => runner::run_all(solution::solutions()),
+    };
+    let elapsed = start.elapsed();
+
+    let mut failed = 0;
+    for run in &runs {
+        println!("day {:03}: {}", run.day, run.name);
+        match run.result {
+            Ok(ref solved) => {
+                for (part, answer) in &solved.answers {
+                    println!("{}: {}", part, answer);
+                }
+            }
+            Err(ref e) => {
+                println!("error: {}", e.report());
+                failed += 1;
+            }
+        }
+    }
+
+    println!(
+        "{} days in {:.3} ms, {} failed",
+        runs.len(),
+        elapsed.as_secs_f64() * 1000.0,
+        failed
+    );
+
+    failed == 0
+}
+
+fn days(day: Option<usize>) -> Result<Vec<&'static dyn Solver>> {
+    match day {
+        Some(day) => Ok(vec![solution::get(day)?]),
+        None => Ok(solution::solutions().to_vec()),
+    }
+}
+
+fn variants(day: Option<usize>) -> Result<()> {
+    for solution in days(day)? {
+        for part in solution.parts() {
+            let names = solution
+                .variants()
+                .iter()
+                .filter(|variant| variant.part == *part)
+                .map(|variant| {
+                    if variant.slow {
+                        format!("{} (slow)", variant.name)
+                    } else {
+                        variant.name.to_string()
+                    }
+                })
+                .collect::<Vec<String>>();
+
+            if !names.is_empty() {
+                println!("day {:03} {}: {}", solution.day(), part, names.join(", "));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn cross_check(day: Option<usize>, slow: bool, input: Option<&Source>) -> Result<bool> {
+    let mut agreed = true;
+
+    for solution in days(day)? {
+        let source = source(solution.day(), input);
+        let input = source.load()?;
+        let checks = crosscheck::cross_check(solution, &input, slow)
+            .map_err(|e| e.in_file(&source.name()))?;
+
+        for check in checks {
+            println!("day {:03} {}: {}", solution.day(), check.part, check);
+            agreed &= check.agrees();
+        }
+    }
+
+    Ok(agreed)
+}
+
+fn time(day: Option<usize>, format: Format) -> Result<()> {
+    print!("{}", Report::run(&days(day)?)?.render(format));
+
+    Ok(())
+}
+
+fn verify(day: Option<usize>) -> Result<bool> {
+    let mut summary = Summary::default();
+
+    for solution in days(day)? {
+        let input = Source::day(solution.day()).load()?;
+        let answers = Answers::load(solution.day())?;
+
+        for (part, outcome) in verify::verify(solution, &input, &answers) {
+            println!("day {:03} {}: {}", solution.day(), part, outcome);
+            summary.record(&outcome);
+        }
+
+        for (index, answers) in answers.examples() {
+            let input = Input::from_lines(&load_example(&format!("{:03}", solution.day()), index)?);
+
+            for (part, outcome) in verify::verify(solution, &input, answers) {
+                println!(
+                    "day {:03} example {} {}: {}",
+                    solution.day(),
+                    index,
+                    part,
+                    outcome
+                );
+                summary.record(&outcome);
+            }
+        }
+    }
+
+    println!("{}", summary);
+
+    Ok(summary.is_success())
+}
+
+fn generate(day: usize, seed: u64, size: usize) -> Result<()> {
+    for line in gen::generate(day, seed, size)?.lines() {
+        println!("{}", line);
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+fn dump(day: usize, input: Option<&Source>) -> Result<()> {
+    let source = source(day, input);
+    let input = source.load()?;
+    println!(
+        "{}",
+        dump::solve(solution::get(day)?, &input).map_err(|e| e.in_file(&source.name()))?
+    );
+
+    Ok(())
+}
+
+#[cfg(feature = "fetch")]
+fn fetch(day: usize, base_url: Option<&str>) -> Result<()> {
+    let mut fetcher = Fetcher::from_env();
+    if let Some(url) = base_url {
+        fetcher = fetcher.base_url(url);
+    }
+
+    for fetched in fetcher.fetch(day)? {
+        println!("{}", fetched);
+    }
+
+    Ok(())
+}
+
+fn main() {
+    if let Err(e) = trace::install_from_env() {
+        eprintln!("error: {}", e.report());
+        process::exit(1);
+    }
+
+    let res = match Command::from_args() {
+        Command::Run {
+            day,
+            part,
+            input,
+            timeout,
+        } => budget(timeout).enter(|| run(day, part, input.as_ref())),
+        Command::All {
+            parallel: false,
+            timeout,
+        } => solution::solutions().iter().try_for_each(|solution| {
+            println!("day {:03}: {}", solution.day(), solution.name());
+            budget(timeout).enter(|| run(solution.day(), None, None))
+        }),
+        Command::All {
+            parallel: true,
+            timeout,
+        } => {
+            if !run_parallel(timeout) {
+                process::exit(1);
+            }
+            Ok(())
+        }
+        Command::Variants { day } => variants(day),
+        Command::CrossCheck { day, slow, input } => match cross_check(day, slow, input.as_ref()) {
+            Ok(true) => Ok(()),
+            Ok(false) => process::exit(1),
+            Err(e) => Err(e),
+        },
+        Command::Time { day, format } => time(day, format),
+        Command::Verify { day } => match verify(day) {
+            Ok(true) => Ok(()),
+            Ok(false) => process::exit(1),
+            Err(e) => Err(e),
+        },
+        Command::Gen { day, seed, size } => generate(day, seed, size),
+        #[cfg(feature = "serde")]
+        Command::Dump { day, input } => dump(day, input.as_ref()),
+        #[cfg(feature = "fetch")]
+        Command::Fetch { day, base_url } => fetch(day, base_url.as_deref()),
+    };
+
+    if let Err(e) = res {
+        eprintln!("error: {}", e.report());
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cross_check_input_needs_a_day() {
+        let args = ["aoc", "cross-check", "--input", "input.txt"];
+        assert!(Command::from_iter_safe(&args).is_err());
+
+        let args = ["aoc", "cross-check", "8", "--input", "input.txt"];
+        assert!(matches!(
+            Command::from_iter_safe(&args),
+            Ok(Command::CrossCheck { day: Some(8), .. })
+        ));
+    }
+}
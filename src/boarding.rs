@@ -1,7 +1,12 @@
 use crate::error::{AocError, Result};
+use crate::input::Input;
+use crate::solution::Solution;
+use crate::trace;
+use crate::util::parse_lines;
 use rayon::prelude::*;
 
 #[derive(Eq, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Seat {
     row: usize,
     col: usize,
@@ -22,6 +27,7 @@ impl Seat {
 }
 
 #[derive(Eq, PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Pass {
     locator: String,
     row_locator: String,
@@ -79,10 +85,11 @@ impl Pass {
 
 // the following four functions are just playing around with performance of
 // result handling and whatnot
-pub fn find_highest_id(locators: &[String]) -> Result<usize> {
+pub fn find_highest_id<S: AsRef<str>>(locators: &[S]) -> Result<usize> {
+    let _span = trace::span!("find_highest_id");
     locators
         .iter()
-        .map(|line| Pass::new(&line))
+        .map(|line| Pass::new(line.as_ref()))
         .map(|pass| pass.map_or_else(Err, |p| p.seat()))
         .map(|seat| seat.map_or_else(Err, |s| Ok(s.id())))
         .collect::<Result<Vec<usize>>>()?
@@ -91,30 +98,31 @@ pub fn find_highest_id(locators: &[String]) -> Result<usize> {
         .ok_or_else(|| AocError::InvalidLocator("No locators".to_string()))
 }
 
-pub fn find_highest_id_bad_errors(locators: &[String]) -> Result<usize> {
+pub fn find_highest_id_bad_errors<S: AsRef<str>>(locators: &[S]) -> Result<usize> {
     locators
         .iter()
-        .map(|line| Pass::new(&line).unwrap())
+        .map(|line| Pass::new(line.as_ref()).unwrap())
         .map(|pass| pass.seat().unwrap())
         .map(|seat| seat.id())
         .max()
         .ok_or_else(|| AocError::InvalidLocator("No locators".to_string()))
 }
 
-pub fn find_highest_id_par_bad_errors(locators: &[String]) -> Result<usize> {
+pub fn find_highest_id_par_bad_errors<S: AsRef<str> + Sync>(locators: &[S]) -> Result<usize> {
     locators
         .par_iter()
-        .map(|line| Pass::new(&line).unwrap())
+        .map(|line| Pass::new(line.as_ref()).unwrap())
         .map(|pass| pass.seat().unwrap())
         .map(|seat| seat.id())
         .max()
         .ok_or_else(|| AocError::InvalidLocator("No locators".to_string()))
 }
 
-pub fn find_highest_id_par(locators: &[String]) -> Result<usize> {
+pub fn find_highest_id_par<S: AsRef<str> + Sync>(locators: &[S]) -> Result<usize> {
+    let _span = trace::span!("find_highest_id_par");
     locators
         .par_iter()
-        .map(|line| Pass::new(&line))
+        .map(|line| Pass::new(line.as_ref()))
         .map(|pass| pass.map_or_else(Err, |p| p.seat()))
         .map(|seat| seat.map_or_else(Err, |s| Ok(s.id())))
         .collect::<Result<Vec<usize>>>()?
@@ -123,6 +131,39 @@ pub fn find_highest_id_par(locators: &[String]) -> Result<usize> {
         .ok_or_else(|| AocError::InvalidLocator("No locators".to_string()))
 }
 
+pub struct BinaryBoarding;
+
+impl Solution for BinaryBoarding {
+    const DAY: usize = 5;
+    const NAME: &'static str = "Binary Boarding";
+
+    // sorted seat ids
+    type Parsed = Vec<usize>;
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed> {
+        let mut ids = parse_lines(&input.lines(), |line| {
+            Pass::new(line).and_then(|pass| pass.seat()).map(|s| s.id())
+        })?;
+        ids.sort_unstable();
+        Ok(ids)
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String> {
+        parsed
+            .last()
+            .map(|id| id.to_string())
+            .ok_or_else(|| AocError::InvalidLocator("No locators".to_string()))
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String> {
+        parsed
+            .windows(2)
+            .find(|pair| pair[1] - pair[0] == 2)
+            .map(|pair| (pair[0] + 1).to_string())
+            .ok_or_else(|| AocError::SeatNotFound("No gap between seat ids".to_string()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
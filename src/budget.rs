@@ -0,0 +1,174 @@
+//! Lets the longest loops give up rather than hang: day 13's bus scans,
+//! day 15's 30 million turns, day 20's tile search and day 23's 10 million
+//! moves.
+//!
+//! `Budget::enter` makes a budget current for the code run on that thread,
+//! where `Budget::current` picks it up, and a few methods such as
+//! `Game::play_until` take one directly. The `--timeout` option of `aoc run`
+//! and `aoc all` sets one, as does `AOC_TIMEOUT` for the examples of days
+//! 13, 15, 20 and 23.
+
+use std::cell::RefCell;
+use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::error::{AocError, Result};
+
+/// Set this to a number of seconds to stop the examples after that long.
+pub const TIMEOUT_VAR: &str = "AOC_TIMEOUT";
+
+/// How many steps of a tight loop `check_at` lets pass between checks, so
+/// checking the clock does not show up in the timings. A power of two.
+pub const CHECK_EVERY: usize = 4096;
+
+thread_local! {
+    static CURRENT: RefCell<Budget> = RefCell::new(Budget::unlimited());
+}
+
+/// A cancellation token with an optional time limit, for stopping long
+/// loops and searches. Clones share the token, so cancelling one cancels
+/// them all.
+///
+/// Solutions check the budget current on their thread, which is unlimited
+/// unless a caller has entered another, so `Solution` parts need no extra
+/// argument. Work handed to other threads is not covered.
+#[derive(Debug, Clone, Default)]
+pub struct Budget {
+    cancelled: Arc<AtomicBool>,
+    limit: Option<(Instant, Duration)>,
+}
+
+impl Budget {
+    /// A budget that only runs out if cancelled.
+    pub fn unlimited() -> Self {
+        Budget::default()
+    }
+
+    /// A budget that runs out `limit` from now.
+    pub fn limit(limit: Duration) -> Self {
+        Budget {
+            cancelled: Arc::default(),
+            limit: Some((Instant::now(), limit)),
+        }
+    }
+
+    /// A budget limited to the seconds in `AOC_TIMEOUT`, if it is set.
+    pub fn from_env() -> Result<Self> {
+        match env::var(TIMEOUT_VAR) {
+            Ok(seconds) => seconds
+                .parse::<f64>()
+                .ok()
+                .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+                .map(|seconds| Budget::limit(Duration::from_secs_f64(seconds)))
+                .ok_or_else(|| {
+                    AocError::InvalidInput(format!(
+                        "{} must be a number of seconds: '{}'",
+                        TIMEOUT_VAR, seconds
+                    ))
+                }),
+            Err(_) => Ok(Budget::unlimited()),
+        }
+    }
+
+    /// The budget current on this thread.
+    pub fn current() -> Self {
+        CURRENT.with(|current| current.borrow().clone())
+    }
+
+    /// Run `f` with this as the current budget of this thread, restoring the
+    /// previous one afterwards, even if `f` panics.
+    pub fn enter<T, F: FnOnce() -> T>(&self, f: F) -> T {
+        struct Restore(Option<Budget>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                if let Some(previous) = self.0.take() {
+                    CURRENT.with(|current| *current.borrow_mut() = previous);
+                }
+            }
+        }
+
+        let previous = CURRENT.with(|current| current.replace(self.clone()));
+        let _restore = Restore(Some(previous));
+        f()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.check().is_err()
+    }
+
+    /// A `Timeout` error if the budget has been cancelled or has run out.
+    pub fn check(&self) -> Result<()> {
+        if self.cancelled.load(Ordering::Relaxed) {
+            return Err(AocError::Timeout("cancelled".to_string()));
+        }
+
+        match self.limit {
+            Some((start, limit)) if start.elapsed() > limit => Err(AocError::Timeout(format!(
+                "took longer than {:.3} s",
+                limit.as_secs_f64()
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// As `check`, but only on every `CHECK_EVERY`th step of a loop.
+    pub fn check_at(&self, step: usize) -> Result<()> {
+        if step & (CHECK_EVERY - 1) == 0 {
+            self.check()
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn limiting() {
+        assert!(Budget::unlimited().check().is_ok());
+        assert!(Budget::limit(Duration::from_secs(60)).check().is_ok());
+
+        let budget = Budget::unlimited();
+        budget.cancel();
+        assert!(budget.check_at(1).is_ok());
+        assert!(budget.check_at(CHECK_EVERY * 3).is_err());
+
+        let budget = Budget::limit(Duration::from_millis(1));
+        thread::sleep(Duration::from_millis(5));
+        match budget.check() {
+            Err(AocError::Timeout(message)) => assert_eq!(message, "took longer than 0.001 s"),
+            other => panic!("expected a timeout, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn cancelling() {
+        let budget = Budget::unlimited();
+        let shared = budget.clone();
+        thread::spawn(move || shared.cancel()).join().unwrap();
+        assert!(budget.is_exhausted());
+    }
+
+    #[test]
+    fn entering() {
+        let budget = Budget::unlimited();
+        budget.cancel();
+
+        assert!(!Budget::current().is_exhausted());
+        assert!(budget.enter(|| Budget::current().is_exhausted()));
+        assert!(!Budget::current().is_exhausted());
+
+        let _ = std::panic::catch_unwind(|| budget.enter(|| panic!("inside")));
+        assert!(!Budget::current().is_exhausted());
+    }
+}
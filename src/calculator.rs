@@ -1,10 +1,17 @@
+use crate::error::Result;
+use crate::input::Input;
+use crate::solution::Solution;
+use crate::trace;
+
 #[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Token {
     Op(Op),
     Val(i64),
 }
 
 #[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Op {
     Add,
     Multiply,
@@ -34,6 +41,7 @@ pub trait StrParser {
     fn calculate(&self, tokens: &[Token]) -> i64;
 
     fn eval(&self, raw: &str) -> i64 {
+        let _span = trace::span!("StrParser::eval");
         let mut index = 0;
         let chars = raw.chars().collect::<Vec<char>>();
         self.recur(&mut index, &chars)
@@ -165,6 +173,39 @@ impl StrParser for AdvancedParser {
     }
 }
 
+pub struct OperationOrder;
+
+impl Solution for OperationOrder {
+    const DAY: usize = 18;
+    const NAME: &'static str = "Operation Order";
+
+    type Parsed = Input;
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed> {
+        Ok(input.clone())
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String> {
+        let parser = Parser {};
+        Ok(parsed
+            .lines()
+            .iter()
+            .map(|line| parser.eval(line))
+            .sum::<i64>()
+            .to_string())
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String> {
+        let parser = AdvancedParser {};
+        Ok(parsed
+            .lines()
+            .iter()
+            .map(|line| parser.eval(line))
+            .sum::<i64>()
+            .to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
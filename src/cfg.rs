@@ -0,0 +1,417 @@
+//! Control flow graphs of console programs, built from where each `Opcode`
+//! says control can go.
+//!
+//! Knowing which instructions can reach the end, day 8's flip can be found in
+//! one run of the program, which is the `correct_cfg` variant. A `Report`
+//! reads:
+//!
+//! ```text
+//! 6 basic blocks
+//! 2 unreachable instructions
+//! loops through 6 instructions from 1
+//! repaired with nop -4 at 7, acc=8
+//! ```
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::console::{Instruction, Program};
+use crate::error::Result;
+use crate::trace;
+use crate::vm::{Exit, Flow, InstructionSet, Vm};
+
+/// A run of instructions only ever entered at the first and left at the last.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Block {
+    pub start: usize,
+    /// One past the last instruction.
+    pub end: usize,
+    /// Where control may go after the block, with the program's length
+    /// standing for termination.
+    pub successors: Vec<usize>,
+}
+
+/// The flip of a `jmp` or `nop` that makes a looping program terminate.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Repair {
+    pub pc: usize,
+    /// The instruction as it is after the flip.
+    pub instruction: Instruction,
+    /// The accumulator once the repaired program has terminated.
+    pub acc: i64,
+}
+
+/// What `Cfg::report` found out about a program.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Report {
+    pub blocks: Vec<Block>,
+    pub unreachable: Vec<usize>,
+    /// The instructions of the loop the program runs forever in, in the order
+    /// they run, starting with the first to run twice.
+    pub detected_loop: Option<Vec<usize>>,
+    pub repair: Option<Repair>,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} basic blocks", self.blocks.len())?;
+        writeln!(f, "{} unreachable instructions", self.unreachable.len())?;
+        match self.detected_loop {
+            Some(ref pcs) => writeln!(
+                f,
+                "loops through {} instructions from {}",
+                pcs.len(),
+                pcs[0]
+            )?,
+            None => writeln!(f, "terminates")?,
+        }
+        match self.repair {
+            Some(ref repair) => writeln!(
+                f,
+                "repaired with {} at {}, acc={}",
+                repair.instruction, repair.pc, repair.acc
+            ),
+            None => writeln!(f, "no single flip repairs it"),
+        }
+    }
+}
+
+/// The control flow graph of a program, with an edge from each instruction
+/// to everywhere control may go after it. Address `len` stands for
+/// termination, and jumps out of the program have no edge at all.
+#[derive(Debug, Clone)]
+pub struct Cfg {
+    set: InstructionSet,
+    program: Program,
+    successors: Vec<Vec<usize>>,
+}
+
+impl Cfg {
+    pub fn new(program: &Program) -> Result<Self> {
+        Cfg::with_set(program, InstructionSet::standard())
+    }
+
+    pub fn with_set(program: &Program, set: InstructionSet) -> Result<Self> {
+        let _span = trace::span!("Cfg::new");
+        let successors = program
+            .instructions()
+            .iter()
+            .enumerate()
+            .map(|(pc, instruction)| successors(&set, program, pc, instruction))
+            .collect::<Result<Vec<Vec<usize>>>>()?;
+
+        Ok(Cfg {
+            set,
+            program: program.clone(),
+            successors,
+        })
+    }
+
+    /// The number of instructions, which is also the address of termination.
+    pub fn len(&self) -> usize {
+        self.successors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.successors.is_empty()
+    }
+
+    pub fn successors(&self, pc: usize) -> &[usize] {
+        &self.successors[pc]
+    }
+
+    /// Whether each instruction can be reached from the first.
+    pub fn reachable(&self) -> Vec<bool> {
+        let mut reached = vec![false; self.len() + 1];
+        let mut queue = VecDeque::new();
+        if !self.is_empty() {
+            reached[0] = true;
+            queue.push_back(0);
+        }
+
+        while let Some(pc) = queue.pop_front() {
+            for &next in self.successors.get(pc).map_or(&[][..], Vec::as_slice) {
+                if !reached[next] {
+                    reached[next] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        reached.truncate(self.len());
+        reached
+    }
+
+    pub fn unreachable(&self) -> Vec<usize> {
+        self.reachable()
+            .into_iter()
+            .enumerate()
+            .filter(|(_, reached)| !reached)
+            .map(|(pc, _)| pc)
+            .collect()
+    }
+
+    /// Whether each instruction can lead to termination, found by walking
+    /// the edges backwards from the end.
+    pub fn reaches_end(&self) -> Vec<bool> {
+        let mut predecessors = vec![Vec::new(); self.len() + 1];
+        for (pc, successors) in self.successors.iter().enumerate() {
+            for &next in successors {
+                predecessors[next].push(pc);
+            }
+        }
+
+        let mut reaches = vec![false; self.len() + 1];
+        reaches[self.len()] = true;
+        let mut queue = VecDeque::from(vec![self.len()]);
+        while let Some(pc) = queue.pop_front() {
+            for &previous in &predecessors[pc] {
+                if !reaches[previous] {
+                    reaches[previous] = true;
+                    queue.push_back(previous);
+                }
+            }
+        }
+
+        reaches.truncate(self.len());
+        reaches
+    }
+
+    pub fn blocks(&self) -> Vec<Block> {
+        let mut leaders = vec![false; self.len() + 1];
+        leaders[0] = true;
+        leaders[self.len()] = true;
+        for (pc, successors) in self.successors.iter().enumerate() {
+            if successors.as_slice() != [pc + 1] {
+                leaders[pc + 1] = true;
+                successors.iter().for_each(|&next| leaders[next] = true);
+            }
+        }
+
+        let mut blocks = Vec::new();
+        let mut start = 0;
+        for (pc, _) in leaders
+            .iter()
+            .enumerate()
+            .skip(1)
+            .filter(|(_, &leader)| leader)
+        {
+            blocks.push(Block {
+                start,
+                end: pc,
+                successors: self.successors[pc - 1].clone(),
+            });
+            start = pc;
+        }
+        blocks
+    }
+
+    /// The loop the program runs forever in, if it does not terminate, found
+    /// by running it until an instruction is about to run twice.
+    pub fn detected_loop(&self) -> Result<Option<Vec<usize>>> {
+        let (mut path, repeated) = self.path()?;
+        Ok(repeated.map(|pc| {
+            let start = path.iter().position(|&p| p == pc).unwrap_or_default();
+            path.split_off(start)
+        }))
+    }
+
+    /// Find the `jmp` or `nop` whose flip makes the program terminate.
+    ///
+    /// Only the instructions run before the program loops can matter, and
+    /// only a flip sending control to an instruction that reaches the end
+    /// can help, so this takes one run and one walk of the graph rather than
+    /// a run per instruction. Where conditional jumps make reaching the end
+    /// a maybe, each candidate is run to check it.
+    pub fn repair(&self) -> Result<Option<Repair>> {
+        let _span = trace::span!("Cfg::repair");
+        let reaches = self.reaches_end();
+        let len = self.len();
+
+        let path = match self.path()? {
+            (_, None) => return Ok(None),
+            (path, Some(_)) => path,
+        };
+
+        for pc in path {
+            let mut flipped = self.program.instructions()[pc].clone();
+            if !flipped.flip() {
+                continue;
+            }
+
+            let helps = successors(&self.set, &self.program, pc, &flipped)?
+                .into_iter()
+                .any(|next| next == len || reaches[next]);
+            if !helps {
+                continue;
+            }
+
+            let mut instructions = self.program.instructions().to_vec();
+            instructions[pc] = flipped.clone();
+            let mut vm = Vm::with_set(&Program::from_instructions(instructions), self.set.clone())?;
+            if let Ok(Exit::Halted) = vm.run_until_repeat() {
+                return Ok(Some(Repair {
+                    pc,
+                    instruction: flipped,
+                    acc: vm.acc(),
+                }));
+            }
+        }
+
+        Ok(None)
+    }
+
+    pub fn report(&self) -> Result<Report> {
+        Ok(Report {
+            blocks: self.blocks(),
+            unreachable: self.unreachable(),
+            detected_loop: self.detected_loop()?,
+            repair: self.repair()?,
+        })
+    }
+
+    /// The instructions run before the program halts or first repeats one,
+    /// in order, and the one it repeats.
+    fn path(&self) -> Result<(Vec<usize>, Option<usize>)> {
+        let mut vm = Vm::with_set(&self.program, self.set.clone())?;
+        let mut path = Vec::new();
+        let mut seen = vec![false; self.len()];
+
+        while !vm.is_halted() {
+            let pc = vm.pc() as usize;
+            if seen[pc] {
+                return Ok((path, Some(pc)));
+            }
+            seen[pc] = true;
+            path.push(pc);
+            vm.step()?;
+        }
+
+        Ok((path, None))
+    }
+}
+
+/// Where control may go after `instruction` if it were at `pc`.
+fn successors(
+    set: &InstructionSet,
+    program: &Program,
+    pc: usize,
+    instruction: &Instruction,
+) -> Result<Vec<usize>> {
+    let len = program.instructions().len() as i64;
+    let mut successors = set
+        .targets(instruction)?
+        .into_iter()
+        .map(|flow| match flow {
+            Flow::Next => pc as i64 + 1,
+            Flow::Jump(offset) => pc as i64 + offset,
+            Flow::Halt => len,
+        })
+        .filter(|next| (0..=len).contains(next))
+        .map(|next| next as usize)
+        .collect::<Vec<usize>>();
+    successors.dedup();
+    Ok(successors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_input;
+
+    fn program() -> Program {
+        Program::new(&test_input(
+            "
+            nop +0
+            acc +1
+            jmp +4
+            acc +3
+            jmp -3
+            acc -99
+            acc +1
+            jmp -4
+            acc +6
+            ",
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn graph() {
+        let cfg = Cfg::new(&program()).unwrap();
+        assert_eq!(cfg.successors(2), &[6]);
+        assert_eq!(cfg.successors(8), &[9]);
+        assert_eq!(cfg.unreachable(), vec![5, 8]);
+        assert_eq!(
+            cfg.reaches_end(),
+            vec![false, false, false, false, false, false, false, false, true]
+        );
+
+        let blocks = cfg
+            .blocks()
+            .into_iter()
+            .map(|block| (block.start, block.end, block.successors))
+            .collect::<Vec<(usize, usize, Vec<usize>)>>();
+        assert_eq!(
+            blocks,
+            vec![
+                (0, 1, vec![1]),
+                (1, 3, vec![6]),
+                (3, 5, vec![1]),
+                (5, 6, vec![6]),
+                (6, 8, vec![3]),
+                (8, 9, vec![9]),
+            ]
+        );
+    }
+
+    #[test]
+    fn reporting() {
+        let report = Cfg::new(&program()).unwrap().report().unwrap();
+        assert_eq!(report.detected_loop, Some(vec![1, 2, 6, 7, 3, 4]));
+        assert_eq!(
+            report.repair,
+            Some(Repair {
+                pc: 7,
+                instruction: Instruction::new("nop -4").unwrap(),
+                acc: 8,
+            })
+        );
+        assert_eq!(
+            report.to_string(),
+            "6 basic blocks\n\
+             2 unreachable instructions\n\
+             loops through 6 instructions from 1\n\
+             repaired with nop -4 at 7, acc=8\n"
+        );
+
+        let set = InstructionSet::standard();
+        let fixed = set
+            .program(&["nop +0", "acc +1", "halt", "jmp -1"])
+            .unwrap();
+        let report = Cfg::new(&fixed).unwrap().report().unwrap();
+        assert_eq!(report.detected_loop, None);
+        assert_eq!(report.repair, None);
+        assert_eq!(report.unreachable, vec![3]);
+    }
+
+    #[test]
+    fn branching() {
+        // the jz makes flipping the nop look like it might help, but only
+        // flipping the jmp does
+        let set = InstructionSet::standard();
+        let program = set
+            .program(&["acc +1", "jz acc +3", "nop +0", "jmp -2", "acc +5"])
+            .unwrap();
+        let cfg = Cfg::new(&program).unwrap();
+        assert_eq!(cfg.successors(1), &[2, 4]);
+        assert_eq!(cfg.reaches_end(), vec![true; 5]);
+        assert_eq!(
+            cfg.repair().unwrap().map(|repair| (repair.pc, repair.acc)),
+            Some((3, 6))
+        );
+
+        let program = set.program(&["acc +1", "jnz acc +0"]).unwrap();
+        assert!(program.correct_cfg().is_err());
+    }
+}
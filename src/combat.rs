@@ -1,9 +1,14 @@
 use crate::error::{AocError, Result};
+use crate::input::{Input, Section};
+use crate::solution::{Part, Solution, Variant};
+use crate::trace;
+use crate::util::parse_lines;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::iter::FromIterator;
 use std::str::FromStr;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Card(usize);
 
 impl FromStr for Card {
@@ -15,6 +20,7 @@ impl FromStr for Card {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Hand {
     winner: usize,
     cards: Vec<Card>,
@@ -53,26 +59,18 @@ impl FromIterator<(usize, Option<Card>)> for Hand {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Player {
     name: String,
     deck: VecDeque<Card>,
 }
 
 impl Player {
-    pub fn from_input(input: &[String]) -> Result<Self> {
-        let mut parts = input.iter();
-        if let Some(name) = parts.next() {
-            let cards = parts
-                .map(|c| Card::from_str(c))
-                .collect::<Result<Vec<Card>>>()?;
-
-            return Ok(Self::new(name.clone(), cards));
-        }
+    pub fn from_section(section: &Section) -> Result<Self> {
+        let cards = parse_lines(&section.body, Card::from_str)
+            .map_err(|e| e.locate(&section.body, section.line + 1))?;
 
-        Err(AocError::InvalidInput(format!(
-            "Could not make player from {:#?}",
-            input
-        )))
+        Ok(Self::new(section.header.to_string(), cards))
     }
 
     pub fn new(name: String, cards: Vec<Card>) -> Self {
@@ -115,19 +113,15 @@ impl Player {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Game {
     players: Vec<Player>,
     round: usize,
 }
 
 impl Game {
-    pub fn from_input(input: &[String]) -> Result<Self> {
-        let players = input
-            .split(|line| line.is_empty())
-            .map(|player_input| Player::from_input(player_input))
-            .collect::<Result<Vec<Player>>>()?;
-
-        Ok(Game::new(players))
+    pub fn from_input(input: &Input) -> Result<Self> {
+        Ok(Game::new(players(input)?))
     }
 
     pub fn new(players: Vec<Player>) -> Self {
@@ -138,6 +132,7 @@ impl Game {
     }
 
     pub fn play(&mut self) -> Result<(Player, usize)> {
+        let _span = trace::span!("Game::play");
         loop {
             let remaining = self
                 .players
@@ -179,19 +174,15 @@ impl Game {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RecursiveGame {
     players: Vec<Player>,
     rounds: HashSet<Vec<usize>>,
 }
 
 impl RecursiveGame {
-    pub fn from_input(input: &[String]) -> Result<Self> {
-        let players = input
-            .split(|line| line.is_empty())
-            .map(|player_input| Player::from_input(player_input))
-            .collect::<Result<Vec<Player>>>()?;
-
-        Ok(RecursiveGame::new(players))
+    pub fn from_input(input: &Input) -> Result<Self> {
+        Ok(RecursiveGame::new(players(input)?))
     }
 
     pub fn new(players: Vec<Player>) -> Self {
@@ -202,6 +193,7 @@ impl RecursiveGame {
     }
 
     pub fn play(&mut self) -> Result<(Player, usize)> {
+        let _span = trace::span!("RecursiveGame::play");
         loop {
             // check if ever a round like this one -> player 1 wins
             let scores = self.players.iter().map(|p| p.score()).collect();
@@ -299,6 +291,7 @@ impl RecursiveGame {
     }
 
     pub fn play_cached(&mut self) -> Result<(Player, usize)> {
+        let _span = trace::span!("RecursiveGame::play_cached");
         let mut cache = HashMap::new();
         self.play_c(&mut cache)
     }
@@ -415,10 +408,48 @@ impl RecursiveGame {
     }
 }
 
+/// A player for each section of the input, headed by the player's name.
+fn players(input: &Input) -> Result<Vec<Player>> {
+    input.sections().iter().map(Player::from_section).collect()
+}
+
+pub struct CrabCombat;
+
+impl Solution for CrabCombat {
+    const DAY: usize = 22;
+    const NAME: &'static str = "Crab Combat";
+
+    type Parsed = Vec<Player>;
+
+    const VARIANTS: &'static [Variant<Self::Parsed>] = &[Variant {
+        part: Part::Two,
+        name: "play",
+        slow: false,
+        solve: |players| {
+            let winner = RecursiveGame::new(players.clone()).play()?;
+            Ok(winner.0.score().to_string())
+        },
+    }];
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed> {
+        players(input)
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String> {
+        let winner = Game::new(parsed.clone()).play()?;
+        Ok(winner.0.score().to_string())
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String> {
+        let winner = RecursiveGame::new(parsed.clone()).play_cached()?;
+        Ok(winner.0.score().to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::util::test_input;
+    use crate::util::load_example;
 
     mod player {
         use super::*;
@@ -499,23 +530,7 @@ mod tests {
 
         #[test]
         fn play() {
-            let input = test_input(
-                "
-                Player 1:
-                9
-                2
-                6
-                3
-                1
-
-                Player 2:
-                5
-                8
-                4
-                7
-                10
-            ",
-            );
+            let input = Input::from_lines(&load_example("022", 0).unwrap());
 
             let mut g = Game::from_input(&input).unwrap();
             let winner = g.play().unwrap();
@@ -529,23 +544,7 @@ mod tests {
 
         #[test]
         fn play() {
-            let input = test_input(
-                "
-                Player 1:
-                9
-                2
-                6
-                3
-                1
-
-                Player 2:
-                5
-                8
-                4
-                7
-                10
-            ",
-            );
+            let input = Input::from_lines(&load_example("022", 0).unwrap());
 
             let mut g = RecursiveGame::from_input(&input).unwrap();
             let winner = g.play().unwrap();
@@ -555,23 +554,7 @@ mod tests {
 
         #[test]
         fn play_cached() {
-            let input = test_input(
-                "
-                Player 1:
-                9
-                2
-                6
-                3
-                1
-
-                Player 2:
-                5
-                8
-                4
-                7
-                10
-            ",
-            );
+            let input = Input::from_lines(&load_example("022", 0).unwrap());
 
             let mut g = RecursiveGame::from_input(&input).unwrap();
             let winner = g.play_cached().unwrap();
@@ -579,4 +562,12 @@ mod tests {
             assert_eq!(winner.0.score(), 291);
         }
     }
+
+    #[test]
+    fn readme_example() {
+        let input = load_example("022", 0).unwrap();
+        let parsed = CrabCombat.parse(&Input::from_lines(&input)).unwrap();
+        assert_eq!(CrabCombat.part_one(&parsed).unwrap(), "306");
+        assert_eq!(CrabCombat.part_two(&parsed).unwrap(), "291");
+    }
 }
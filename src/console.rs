@@ -1,77 +1,181 @@
+use crate::cfg::Cfg;
 use crate::error::{AocError, Result};
+use crate::input::Input;
+use crate::repair::{Goal, Search};
+use crate::solution::{Part, Solution, Variant};
+use crate::trace;
+use crate::util::Collected;
+use crate::vm::{self, Exit, Flow, InstructionSet, Registers, Vm};
 use rayon::prelude::*;
+use std::borrow::Cow;
 use std::collections::HashSet;
+use std::fmt;
 
-#[derive(Eq, PartialEq, Clone, Debug)]
-pub enum Op {
-    Acc,
-    Jmp,
-    Nop,
-}
+/// The mnemonic of an instruction. The handheld's own are kept as
+/// constants; which others exist depends on the `InstructionSet` in use.
+#[derive(Eq, PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Op(Cow<'static, str>);
 
 impl Op {
+    pub const ACC: Op = Op(Cow::Borrowed("acc"));
+    pub const JMP: Op = Op(Cow::Borrowed("jmp"));
+    pub const NOP: Op = Op(Cow::Borrowed("nop"));
+    pub const HALT: Op = Op(Cow::Borrowed("halt"));
+
+    pub fn new(name: &str) -> Self {
+        Op(Cow::Owned(name.to_string()))
+    }
+
+    /// One of the handheld's own opcodes.
     pub fn from_str(input: &str) -> Result<Op> {
-        match input {
-            "acc" => Ok(Op::Acc),
-            "jmp" => Ok(Op::Jmp),
-            "nop" => Ok(Op::Nop),
-            _ => Err(AocError::UnknownOperation(input.to_string())),
+        match InstructionSet::handheld().get(input) {
+            Some(_) => Ok(Op::new(input)),
+            None => Err(AocError::UnknownOperation(input.to_string())),
         }
     }
+
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
 }
 
 #[derive(Eq, PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Operand {
+    Value(i64),
+    Register(String),
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Operand::Value(value) => write!(f, "{:+}", value),
+            Operand::Register(ref name) => write!(f, "{}", name),
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Instruction {
     op: Op,
-    val: i64,
+    args: Vec<Operand>,
 }
 
 impl Instruction {
+    /// Parse one of the handheld's own instructions. Others are parsed by
+    /// the `InstructionSet` they belong to.
     pub fn new(input: &str) -> Result<Self> {
-        let mut parts = input.split(' ');
+        InstructionSet::handheld().instruction(input)
+    }
 
-        if let Some(op_str) = parts.next() {
-            if let Some(val_str) = parts.next() {
-                if let Some(_) = parts.next() {
-                    return Err(AocError::InvalidInstruction(input.to_string()));
-                }
+    pub fn from_parts(op: Op, args: Vec<Operand>) -> Self {
+        Instruction { op, args }
+    }
 
-                return Ok(Instruction {
-                    op: Op::from_str(op_str)?,
-                    val: val_str.parse::<i64>()?,
-                });
-            }
+    pub fn op(&self) -> &Op {
+        &self.op
+    }
+
+    pub fn args(&self) -> &[Operand] {
+        &self.args
+    }
+
+    pub fn with_args(mut self, args: Vec<Operand>) -> Self {
+        self.args = args;
+        self
+    }
+
+    /// Swap a `jmp` for a `nop` or the other way round, returning whether
+    /// there was anything to swap.
+    pub fn flip(&mut self) -> bool {
+        if self.op == Op::JMP {
+            self.op = Op::NOP;
+        } else if self.op == Op::NOP {
+            self.op = Op::JMP;
+        } else {
+            return false;
         }
+        true
+    }
+}
 
-        Err(AocError::InvalidInstruction(input.to_string()))
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.op)?;
+        for arg in &self.args {
+            write!(f, " {}", arg)?;
+        }
+        Ok(())
     }
 }
 
 #[derive(PartialEq, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Program {
     instructions: Vec<Instruction>,
 }
 
 impl Program {
-    pub fn new(input: &[String]) -> Result<Self> {
-        Ok(Program {
-            instructions: input
-                .iter()
-                .map(|line| Instruction::new(line))
-                .collect::<Result<Vec<Instruction>>>()?,
-        })
+    /// Parse the handheld's boot code, which only has `acc`, `jmp` and `nop`
+    /// with numbers. Programs using more of the VM are parsed with
+    /// `InstructionSet::program`.
+    pub fn new<S: AsRef<str>>(input: &[S]) -> Result<Self> {
+        let _span = trace::span!("Program::new");
+        InstructionSet::handheld().program(input)
     }
 
+    /// A program of every instruction that parsed, and the errors of those
+    /// that did not.
+    pub fn new_lenient<S: AsRef<str>>(input: &[S]) -> Collected<Self> {
+        InstructionSet::handheld().program_lenient(input)
+    }
+
+    pub fn from_instructions(instructions: Vec<Instruction>) -> Self {
+        Program { instructions }
+    }
+
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+
+    /// Run the instruction at `ptr` with the accumulator at `accumulator`,
+    /// giving the next pointer and accumulator. A `halt` jumps to the end.
     pub fn step(&self, ptr: i64, accumulator: i64) -> Result<(i64, i64)> {
         if let Some(cur) = self.instructions.get(ptr as usize) {
-            let mut new_acc = accumulator;
-            let new_ptr = match cur.op {
-                Op::Acc => {
-                    new_acc += cur.val;
-                    ptr + 1
+            // the handheld's own instructions are run here, as setting up
+            // registers for each would cost more than running them
+            let (flow, new_acc) = match (cur.op(), cur.args()) {
+                (op, &[Operand::Value(value)]) if *op == Op::ACC => (
+                    Flow::Next,
+                    accumulator.checked_add(value).ok_or_else(|| {
+                        AocError::InvalidProgram(format!("acc overflowed at {}", ptr))
+                    })?,
+                ),
+                (op, &[Operand::Value(offset)]) if *op == Op::JMP => {
+                    (Flow::Jump(offset), accumulator)
                 }
-                Op::Jmp => ptr + cur.val,
-                Op::Nop => ptr + 1,
+                (op, &[Operand::Value(_)]) if *op == Op::NOP => (Flow::Next, accumulator),
+                _ => {
+                    let mut registers = Registers::new();
+                    registers.set(vm::ACC, accumulator);
+                    let flow = InstructionSet::standard().execute(cur, &mut registers)?;
+                    (flow, registers.acc())
+                }
+            };
+            let new_ptr = match flow {
+                Flow::Next => ptr + 1,
+                Flow::Jump(offset) => ptr.checked_add(offset).ok_or_else(|| {
+                    AocError::InvalidProgram(format!("jump overflowed at {}", ptr))
+                })?,
+                Flow::Halt => self.instructions.len() as i64,
             };
             Ok((new_ptr, new_acc))
         } else {
@@ -83,59 +187,26 @@ impl Program {
     }
 
     pub fn execute(&self) -> Result<(i64, bool)> {
-        let mut seen: HashSet<i64> = HashSet::new();
-
         if self.instructions.is_empty() {
             return Err(AocError::InvalidProgram("No instructions".to_string()));
         }
 
-        let mut ptr: i64 = 0;
-        let mut accumulator = 0;
-        let eof = self.instructions.len() as i64;
-        loop {
-            seen.insert(ptr);
-
-            let res = self.step(ptr, accumulator)?;
-            ptr = res.0;
-            accumulator = res.1;
-
-            if ptr == eof {
-                break;
-            }
-
-            if ptr < 0 || ptr > eof {
-                return Err(AocError::InvalidProgram(format!(
-                    "Attempted to access instruction location out of bounds {} of {}",
-                    ptr, eof
-                )));
-            }
-
-            if seen.contains(&ptr) {
-                return Ok((accumulator, false));
-            }
-        }
-
-        Ok((accumulator, true))
+        let mut vm = Vm::new(self)?;
+        let exit = vm.run_until_repeat()?;
+        Ok((vm.acc(), exit == Exit::Halted))
     }
 
     pub fn correct(&mut self) -> Result<i64> {
+        let _span = trace::span!("Program::correct");
         for i in 0..self.instructions.len() {
             if let Some(ins) = self.instructions.get_mut(i) {
-                match ins.op {
-                    Op::Jmp => ins.op = Op::Nop,
-                    Op::Nop => ins.op = Op::Jmp,
-                    _ => {}
-                }
+                ins.flip();
             }
 
             let res = self.execute();
 
             if let Some(ins) = self.instructions.get_mut(i) {
-                match ins.op {
-                    Op::Jmp => ins.op = Op::Nop,
-                    Op::Nop => ins.op = Op::Jmp,
-                    _ => {}
-                }
+                ins.flip();
             }
 
             // we could have done this before we put it back, but making
@@ -151,21 +222,18 @@ impl Program {
     }
 
     pub fn correct_parallel(&self) -> Result<i64> {
+        let _span = trace::span!("Program::correct_parallel");
         let res = (0..self.instructions.len())
             .collect::<Vec<usize>>()
             .par_iter()
             .filter(|i| match self.instructions.get(**i) {
-                Some(ins) => ins.op != Op::Nop,
+                Some(ins) => ins.op != Op::NOP,
                 None => false,
             })
             .map(|i| {
                 let mut copy = self.clone();
                 if let Some(ins) = copy.instructions.get_mut(*i) {
-                    match ins.op {
-                        Op::Jmp => ins.op = Op::Nop,
-                        Op::Nop => ins.op = Op::Jmp,
-                        _ => {}
-                    }
+                    ins.flip();
                     if let Ok((val, normal)) = copy.execute() {
                         if normal {
                             return Some(val);
@@ -189,7 +257,16 @@ impl Program {
         ))
     }
 
+    /// `correct` in linear time, by way of the control flow graph.
+    pub fn correct_cfg(&self) -> Result<i64> {
+        Cfg::new(self)?
+            .repair()?
+            .map(|repair| repair.acc)
+            .ok_or_else(|| AocError::InvalidProgram("Could not fix program".to_string()))
+    }
+
     pub fn correct_recursive(&mut self) -> Result<i64> {
+        let _span = trace::span!("Program::correct_recursive");
         let mut seen = HashSet::new();
         let mut final_accumulator = 0;
         if self.execute_r(0, 0, false, &mut seen, &mut final_accumulator)? {
@@ -230,25 +307,17 @@ impl Program {
         seen.insert(ptr);
 
         if !changed {
-            let mut did_swap = true;
-            if let Some(ins) = self.instructions.get_mut(ptr as usize) {
-                match ins.op {
-                    Op::Jmp => ins.op = Op::Nop,
-                    Op::Nop => ins.op = Op::Jmp,
-                    _ => did_swap = false,
-                }
-            }
+            let did_swap = match self.instructions.get_mut(ptr as usize) {
+                Some(ins) => ins.flip(),
+                None => true,
+            };
 
             if did_swap {
                 let res = self.step(ptr, accumulator)?;
                 let execution_res = self.execute_r(res.0, res.1, true, seen, final_accumulator)?;
 
                 if let Some(ins) = self.instructions.get_mut(ptr as usize) {
-                    match ins.op {
-                        Op::Jmp => ins.op = Op::Nop,
-                        Op::Nop => ins.op = Op::Jmp,
-                        _ => {}
-                    }
+                    ins.flip();
                 }
 
                 if execution_res {
@@ -267,6 +336,59 @@ impl Program {
     }
 }
 
+pub struct HandheldHalting;
+
+impl Solution for HandheldHalting {
+    const DAY: usize = 8;
+    const NAME: &'static str = "Handheld Halting";
+
+    type Parsed = Program;
+
+    const VARIANTS: &'static [Variant<Self::Parsed>] = &[
+        Variant {
+            part: Part::Two,
+            name: "correct",
+            slow: false,
+            solve: |program| Ok(program.clone().correct()?.to_string()),
+        },
+        Variant {
+            part: Part::Two,
+            name: "correct_recursive",
+            slow: false,
+            solve: |program| Ok(program.clone().correct_recursive()?.to_string()),
+        },
+        Variant {
+            part: Part::Two,
+            name: "correct_cfg",
+            slow: false,
+            solve: |program| Ok(program.correct_cfg()?.to_string()),
+        },
+        Variant {
+            part: Part::Two,
+            name: "search",
+            slow: false,
+            solve: |program| match Search::new(Goal::Terminate).run(program)?.first() {
+                Some(fix) => Ok(fix.acc.to_string()),
+                None => Err(AocError::InvalidProgram(
+                    "Could not fix program".to_string(),
+                )),
+            },
+        },
+    ];
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed> {
+        Program::new(&input.lines())
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String> {
+        Ok(parsed.execute()?.0.to_string())
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String> {
+        Ok(parsed.correct_parallel()?.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     mod op {
@@ -274,9 +396,10 @@ mod tests {
 
         #[test]
         fn from_str() {
-            assert_eq!(Op::from_str("acc").unwrap(), Op::Acc);
-            assert_eq!(Op::from_str("jmp").unwrap(), Op::Jmp);
-            assert_eq!(Op::from_str("nop").unwrap(), Op::Nop);
+            assert_eq!(Op::from_str("acc").unwrap(), Op::ACC);
+            assert_eq!(Op::from_str("jmp").unwrap(), Op::JMP);
+            assert_eq!(Op::from_str("nop").unwrap(), Op::NOP);
+            assert!(Op::from_str("halt").is_err());
             assert!(Op::from_str("foo").is_err());
             assert!(Op::from_str("bar").is_err());
         }
@@ -294,17 +417,19 @@ mod tests {
             assert!(Instruction::new("bar +1").is_err());
 
             let i = Instruction::new("acc +1").unwrap();
-            assert_eq!(i.op, Op::Acc);
-            assert_eq!(i.val, 1);
+            assert_eq!(i.op, Op::ACC);
+            assert_eq!(i.args, vec![Operand::Value(1)]);
 
             let i = Instruction::new("nop -1333").unwrap();
-            assert_eq!(i.op, Op::Nop);
-            assert_eq!(i.val, -1333);
+            assert_eq!(i.op, Op::NOP);
+            assert_eq!(i.args, vec![Operand::Value(-1333)]);
+            assert_eq!(i.to_string(), "nop -1333");
         }
     }
 
     mod program {
         use super::super::*;
+        use crate::span::Span;
 
         fn input() -> Vec<String> {
             vec![
@@ -320,6 +445,36 @@ mod tests {
             ]
         }
 
+        #[test]
+        fn locating_errors() {
+            let mut lines = input();
+            lines[3] = "acc +x".to_string();
+            let err = Program::new(&lines).unwrap_err();
+            assert_eq!(err.span().unwrap(), &Span::new(4, 5, 2, "acc +x"));
+
+            lines[3] = "add +3".to_string();
+            let err = Program::new(&lines).unwrap_err();
+            assert_eq!(err.span().unwrap(), &Span::new(4, 1, 3, "add +3"));
+        }
+
+        #[test]
+        fn new_lenient() {
+            let mut lines = input();
+            lines[3] = "acc +x".to_string();
+            lines[6] = "add +1".to_string();
+
+            let collected = Program::new_lenient(&lines);
+            assert_eq!(collected.error_lines(), vec![4, 7]);
+            assert_eq!(collected.parsed.instructions.len(), 7);
+
+            let collected = Program::new_lenient(&input());
+            assert!(collected.is_clean());
+            assert_eq!(
+                collected.into_result().unwrap(),
+                Program::new(&input()).unwrap()
+            );
+        }
+
         fn correct_input() -> Vec<String> {
             vec![
                 "nop +0".to_string(),
@@ -340,6 +495,17 @@ mod tests {
             assert_eq!(p.instructions.len(), 9);
         }
 
+        #[test]
+        fn only_the_handhelds_instructions() {
+            for line in &["jz acc +1", "halt", "mov a +1", "acc a", "jmp a"] {
+                assert!(Program::new(&[line]).is_err(), "{}", line);
+            }
+
+            let lines = ["mov a +2", "acc a", "halt"];
+            let p = InstructionSet::standard().program(&lines).unwrap();
+            assert_eq!(p.execute().unwrap(), (2, true));
+        }
+
         #[test]
         fn execute() {
             let p = Program::new(&input()).unwrap();
@@ -347,6 +513,13 @@ mod tests {
 
             let p = Program::new(&correct_input()).unwrap();
             assert_eq!(p.execute().unwrap(), (8, true));
+
+            let p = Program::new(&["acc +9223372036854775807", "acc +1"]).unwrap();
+            assert!(matches!(
+                p.step(1, i64::MAX),
+                Err(AocError::InvalidProgram(_))
+            ));
+            assert!(matches!(p.execute(), Err(AocError::InvalidProgram(_))));
         }
 
         #[test]
@@ -359,6 +532,17 @@ mod tests {
 
             let mut p = Program::new(&input()).unwrap();
             assert_eq!(p.correct_recursive().unwrap(), 8);
+
+            let p = Program::new(&input()).unwrap();
+            assert_eq!(p.correct_cfg().unwrap(), 8);
+        }
+
+        #[cfg(feature = "serde")]
+        #[test]
+        fn serde() {
+            let p = Program::new(&input()).unwrap();
+            let json = serde_json::to_string(&p).unwrap();
+            assert_eq!(serde_json::from_str::<Program>(&json).unwrap(), p);
         }
     }
 }
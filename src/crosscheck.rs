@@ -0,0 +1,165 @@
+//! Runs every variant of a part next to the default on the same parsed
+//! input, so a disagreement between implementations shows up at once. It
+//! is worth pairing with `aoc gen` to try inputs the puzzle's own miss.
+
+use std::fmt;
+
+use crate::error::Result;
+use crate::input::Input;
+use crate::solution::{Part, Solver};
+
+/// The name the answer of `part_one` or `part_two` goes by in a cross-check.
+pub const DEFAULT_VARIANT: &str = "default";
+
+/// The answers every implementation of one part gave on the same input.
+#[derive(Debug)]
+pub struct CrossCheck {
+    pub part: Part,
+    pub answers: Vec<(&'static str, Result<String>)>,
+}
+
+impl CrossCheck {
+    /// Whether every implementation succeeded with the same answer.
+    pub fn agrees(&self) -> bool {
+        let mut answers = self.answers.iter().map(|(_, answer)| answer.as_ref().ok());
+        match answers.next() {
+            Some(Some(first)) => answers.all(|answer| answer == Some(first)),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for CrossCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.agrees() {
+            if let Some((_, Ok(answer))) = self.answers.first() {
+                return write!(
+                    f,
+                    "ok, {} implementations agree on {}",
+                    self.answers.len(),
+                    answer
+                );
+            }
+        }
+
+        let answers = self
+            .answers
+            .iter()
+            .map(|(name, answer)| match answer {
+                Ok(answer) => format!("{} = {}", name, answer),
+                Err(e) => format!("{} failed: {}", name, e),
+            })
+            .collect::<Vec<String>>();
+
+        write!(f, "DISAGREE {}", answers.join(", "))
+    }
+}
+
+/// Run every registered variant of a day alongside the part it stands in
+/// for, parsing the input only once. Parts without variants are left out,
+/// and so are slow variants unless `include_slow` is set.
+pub fn cross_check(
+    solution: &dyn Solver,
+    input: &Input,
+    include_slow: bool,
+) -> Result<Vec<CrossCheck>> {
+    let parsed = solution.parse_input(input)?;
+    let variants = solution.variants();
+
+    Ok(solution
+        .parts()
+        .iter()
+        .filter_map(|part| {
+            let names = variants
+                .iter()
+                .filter(|variant| variant.part == *part && (include_slow || !variant.slow))
+                .map(|variant| variant.name)
+                .collect::<Vec<&'static str>>();
+
+            if names.is_empty() {
+                return None;
+            }
+
+            let mut answers = vec![(DEFAULT_VARIANT, solution.solve_part(parsed.as_ref(), *part))];
+            answers.extend(
+                names
+                    .into_iter()
+                    .map(|name| (name, solution.solve_variant(parsed.as_ref(), name))),
+            );
+
+            Some(CrossCheck {
+                part: *part,
+                answers,
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::AocError;
+    use crate::solution;
+    use crate::util::load_example;
+
+    #[test]
+    fn agreeing() {
+        let check = CrossCheck {
+            part: Part::Two,
+            answers: vec![("default", Ok("8".to_string())), ("a", Ok("8".to_string()))],
+        };
+        assert!(check.agrees());
+        assert_eq!(check.to_string(), "ok, 2 implementations agree on 8");
+
+        let check = CrossCheck {
+            part: Part::Two,
+            answers: vec![
+                ("default", Ok("8".to_string())),
+                ("a", Ok("9".to_string())),
+                ("b", Err(AocError::NoOutlier)),
+            ],
+        };
+        assert!(!check.agrees());
+        assert!(check
+            .to_string()
+            .starts_with("DISAGREE default = 8, a = 9, b failed: "));
+    }
+
+    #[test]
+    fn cross_checking() {
+        let input = Input::from_lines(&load_example("010", 0).unwrap());
+        let solution = solution::get(10).unwrap();
+
+        let checks = cross_check(solution, &input, false).unwrap();
+        assert_eq!(checks.len(), 1);
+        assert_eq!(checks[0].part, Part::Two);
+        assert_eq!(checks[0].answers.len(), 2);
+        assert!(checks[0].agrees());
+
+        let checks = cross_check(solution, &input, true).unwrap();
+        assert_eq!(checks[0].answers.len(), 3);
+        assert!(checks[0].agrees());
+
+        // days without variants have nothing to check
+        let input = Input::from("5764801\n17807724\n");
+        assert!(cross_check(solution::get(25).unwrap(), &input, true)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn variant_names_are_unique() {
+        for solution in solution::solutions() {
+            let mut names = solution
+                .variants()
+                .iter()
+                .map(|variant| variant.name)
+                .collect::<Vec<&str>>();
+            names.push(DEFAULT_VARIANT);
+            let count = names.len();
+            names.sort_unstable();
+            names.dedup();
+            assert_eq!(names.len(), count, "day {}", solution.day());
+        }
+    }
+}
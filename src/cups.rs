@@ -1,11 +1,19 @@
+use crate::budget::Budget;
 use crate::error::{AocError, Result};
+use crate::input::Input;
+use crate::progress::Progress;
+use crate::render::Render;
+use crate::solution::Solution;
+use crate::trace;
 use std::fmt;
 use std::str::FromStr;
 
 #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Cup(usize);
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Game {
     cups: NodeList,
 }
@@ -18,6 +26,7 @@ impl Game {
     }
 
     pub fn from_str_with_len(s: &str, desired_len: usize) -> Result<Self> {
+        let _span = trace::span!("Game::from_str_with_len");
         let cups = s
             .chars()
             .map(|ch| {
@@ -47,6 +56,28 @@ impl Game {
         }
     }
 
+    /// As `simulate`, but giving up with a `Timeout` error if the budget runs
+    /// out first.
+    pub fn simulate_within(&mut self, n: usize, budget: &Budget) -> Result<()> {
+        self.simulate_with(n, budget, |_, _| {})
+    }
+
+    /// As `simulate_within`, showing `observe` the game after each round.
+    pub fn simulate_with<F: FnMut(Progress, &Self)>(
+        &mut self,
+        n: usize,
+        budget: &Budget,
+        mut observe: F,
+    ) -> Result<()> {
+        let _span = trace::span!("Game::simulate_with");
+        for step in 1..=n {
+            budget.check_at(step)?;
+            self.round();
+            observe(Progress::new(step, n), self);
+        }
+        Ok(())
+    }
+
     pub fn order(&self) -> Vec<Cup> {
         if let Some(one) = self.cups.get_node(&Cup(1)) {
             self.cups.cups(one.next, one.prev)
@@ -95,6 +126,7 @@ impl FromStr for Game {
 }
 
 #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Node {
     prev: usize,
     next: usize,
@@ -108,6 +140,7 @@ impl Node {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NodeList {
     nodes: Vec<Node>,
     cup_map: Vec<usize>,
@@ -281,6 +314,65 @@ impl fmt::Display for NodeList {
     }
 }
 
+impl Render for Game {
+    fn render(&self) -> String {
+        self.cups.render()
+    }
+}
+
+/// The cups clockwise from the current one, which is in parentheses as in
+/// the puzzle text. Only the first few of a long list are shown.
+impl Render for NodeList {
+    fn render(&self) -> String {
+        const SHOWN: usize = 30;
+
+        let mut labels = vec![format!("({})", self.nodes[self.current].val.0)];
+        let mut index = self.nodes[self.current].next;
+        while index != self.current && labels.len() < SHOWN {
+            labels.push(self.nodes[index].val.0.to_string());
+            index = self.nodes[index].next;
+        }
+        if index != self.current {
+            labels.push("...".to_string());
+        }
+
+        format!("cups: {}", labels.join(" "))
+    }
+}
+
+pub struct CrabCups;
+
+impl Solution for CrabCups {
+    const DAY: usize = 23;
+    const NAME: &'static str = "Crab Cups";
+
+    // the cup labels, since the parts play with different numbers of cups
+    type Parsed = String;
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed> {
+        let lines = input.lines();
+        let labels = lines
+            .first()
+            .ok_or_else(|| AocError::InvalidInput("No cup labels".to_string()))?;
+
+        // validate up front so bad input is reported as a parse failure
+        Game::from_str(labels)?;
+        Ok(labels.to_string())
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String> {
+        let mut game = Game::from_str(parsed)?;
+        game.simulate(100);
+        Ok(game.order_string())
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String> {
+        let mut game = Game::from_str_with_len(parsed, 1_000_000)?;
+        game.simulate_within(10_000_000, &Budget::current())?;
+        Ok(game.crappy_checksum().to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -331,6 +423,18 @@ mod tests {
     mod game {
         use super::*;
 
+        #[test]
+        fn render() {
+            let mut g = Game::from_str("389125467").unwrap();
+            assert_eq!(g.render(), "cups: (3) 8 9 1 2 5 4 6 7");
+
+            g.round();
+            assert_eq!(g.render(), "cups: (2) 8 9 1 5 4 6 7 3");
+
+            let g = Game::from_str_with_len("389125467", 100).unwrap();
+            assert!(g.render().ends_with(" 29 30 ..."));
+        }
+
         #[test]
         fn round() {
             let mut g = Game::from_str("389125467").unwrap();
@@ -572,6 +676,41 @@ mod tests {
             assert_eq!(g.order_string().as_str(), "67384529");
         }
 
+        #[test]
+        fn simulate_with() {
+            let mut g = Game::from_str("389125467").unwrap();
+            let mut seen = Vec::new();
+
+            g.simulate_with(10, &Budget::unlimited(), |progress, g| {
+                if progress.step <= 2 || progress.is_done() {
+                    seen.push((progress, g.order_string()));
+                }
+            })
+            .unwrap();
+            assert_eq!(
+                seen,
+                vec![
+                    (Progress::new(1, 10), "54673289".to_string()),
+                    (Progress::new(2, 10), "32546789".to_string()),
+                    (Progress::new(10, 10), "92658374".to_string()),
+                ]
+            );
+        }
+
+        #[test]
+        fn simulate_within() {
+            let mut g = Game::from_str("389125467").unwrap();
+            g.simulate_within(10, &Budget::unlimited()).unwrap();
+            assert_eq!(g.order_string().as_str(), "92658374");
+
+            let budget = Budget::unlimited();
+            budget.cancel();
+            assert!(matches!(
+                g.simulate_within(10_000_000, &budget),
+                Err(AocError::Timeout(_))
+            ));
+        }
+
         // #[test]
         // fn simulate_large() {
         //     let mut g = Game::from_str_with_len("389125467", 1_000_000).unwrap();
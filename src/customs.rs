@@ -1,24 +1,28 @@
 use crate::error::{AocError, Result};
+use crate::input::Input;
+use crate::solution::Solution;
+use crate::trace;
+use crate::util::parse_blocks;
 use std::collections::HashMap;
+use std::fmt::Debug;
 
 #[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Group {
     size: usize,
     answers: HashMap<char, usize>,
 }
 
 impl Group {
-    pub fn from_input(input: &[String]) -> Result<Vec<Self>> {
-        input
-            .split(|line| line.is_empty())
-            .map(|group| Self::new(group))
-            .collect::<Result<Vec<Self>>>()
+    pub fn from_input<S: AsRef<str> + Debug>(input: &[S]) -> Result<Vec<Self>> {
+        let _span = trace::span!("Group::from_input");
+        parse_blocks(input, Self::new)
     }
 
-    pub fn new(data: &[String]) -> Result<Self> {
+    pub fn new<S: AsRef<str> + Debug>(data: &[S]) -> Result<Self> {
         let mut answers = HashMap::new();
         for line in data {
-            for ch in line.to_lowercase().chars() {
+            for ch in line.as_ref().to_lowercase().chars() {
                 if ch.is_alphabetic() {
                     if let Some(count) = answers.get_mut(&ch) {
                         *count += 1;
@@ -46,6 +50,35 @@ impl Group {
     }
 }
 
+pub struct CustomCustoms;
+
+impl Solution for CustomCustoms {
+    const DAY: usize = 6;
+    const NAME: &'static str = "Custom Customs";
+
+    type Parsed = Vec<Group>;
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed> {
+        Group::from_input(&input.lines())
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String> {
+        Ok(parsed
+            .iter()
+            .map(|g| g.unique_answers())
+            .sum::<usize>()
+            .to_string())
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String> {
+        Ok(parsed
+            .iter()
+            .map(|g| g.collective_answers())
+            .sum::<usize>()
+            .to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,351 @@
+//! Steps through a program for when `println!` is not enough. The
+//! `008_debug` example is a REPL over a `Debugger`:
+//!
+//! ```text
+//! $ cargo run --example 008_debug
+//! 654 instructions, type help for commands
+//! 0> watch acc
+//! 0> c
+//! acc changed from 0 to 45
+//!    0  acc +45      acc=45
+//! ```
+
+use std::collections::BTreeSet;
+use std::fmt;
+
+use crate::budget::Budget;
+use crate::console::{Instruction, Program};
+use crate::error::Result;
+use crate::vm::{InstructionSet, Vm};
+
+/// One instruction run: where it was, what it was and the accumulator once it
+/// had run.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Traced {
+    pub pc: usize,
+    pub instruction: Instruction,
+    pub acc: i64,
+}
+
+impl fmt::Display for Traced {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:>4}  {:<12} acc={}",
+            self.pc,
+            self.instruction.to_string(),
+            self.acc
+        )
+    }
+}
+
+/// Why the debugger handed control back.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Stop {
+    /// A single step finished with nothing else to report.
+    Stepped,
+    /// The next instruction to run has a breakpoint on it.
+    Breakpoint(usize),
+    /// A watched register changed.
+    Changed {
+        register: String,
+        from: i64,
+        to: i64,
+    },
+    /// The next instruction to run has already run since resuming, which for
+    /// the handheld's boot code means it loops forever.
+    Repeat(usize),
+    Halted,
+}
+
+impl fmt::Display for Stop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Stop::Stepped => write!(f, "stepped"),
+            Stop::Breakpoint(pc) => write!(f, "breakpoint at {}", pc),
+            Stop::Changed {
+                ref register,
+                from,
+                to,
+            } => write!(f, "{} changed from {} to {}", register, from, to),
+            Stop::Repeat(pc) => write!(f, "about to run {} a second time", pc),
+            Stop::Halted => write!(f, "halted"),
+        }
+    }
+}
+
+/// Runs a program on a `Vm` an instruction or a breakpoint at a time,
+/// keeping a trace of every instruction run.
+#[derive(Debug, Clone)]
+pub struct Debugger {
+    program: Program,
+    start: Vm,
+    vm: Vm,
+    breakpoints: BTreeSet<usize>,
+    watched: Vec<String>,
+    stop_on_repeat: bool,
+    runs: Vec<usize>,
+    trace: Vec<Traced>,
+}
+
+impl Debugger {
+    pub fn new(program: &Program) -> Result<Self> {
+        Debugger::with_set(program, InstructionSet::standard())
+    }
+
+    pub fn with_set(program: &Program, set: InstructionSet) -> Result<Self> {
+        let vm = Vm::with_set(program, set)?;
+        Ok(Debugger {
+            program: program.clone(),
+            start: vm.clone(),
+            runs: vec![0; program.instructions().len()],
+            vm,
+            breakpoints: BTreeSet::new(),
+            watched: Vec::new(),
+            stop_on_repeat: true,
+            trace: Vec::new(),
+        })
+    }
+
+    /// Whether `resume` stops before an instruction runs twice, which
+    /// it does unless told otherwise. Programs with conditional jumps loop on
+    /// purpose, so for them it gets in the way.
+    pub fn stop_on_repeat(mut self, stop: bool) -> Self {
+        self.stop_on_repeat = stop;
+        self
+    }
+
+    pub fn program(&self) -> &Program {
+        &self.program
+    }
+
+    pub fn vm(&self) -> &Vm {
+        &self.vm
+    }
+
+    pub fn trace(&self) -> &[Traced] {
+        &self.trace
+    }
+
+    /// How many times the instruction at `pc` has run.
+    pub fn runs(&self, pc: usize) -> usize {
+        self.runs.get(pc).cloned().unwrap_or_default()
+    }
+
+    pub fn breakpoints(&self) -> impl Iterator<Item = usize> + '_ {
+        self.breakpoints.iter().cloned()
+    }
+
+    /// Stop before the instruction at `pc` runs.
+    pub fn break_at(&mut self, pc: usize) {
+        self.breakpoints.insert(pc);
+    }
+
+    /// Remove a breakpoint, returning whether there was one.
+    pub fn clear(&mut self, pc: usize) -> bool {
+        self.breakpoints.remove(&pc)
+    }
+
+    /// Stop whenever a register changes, e.g. `acc`.
+    pub fn watch(&mut self, register: &str) {
+        if !self.watched.iter().any(|r| r == register) {
+            self.watched.push(register.to_string());
+        }
+    }
+
+    pub fn unwatch(&mut self, register: &str) -> bool {
+        let before = self.watched.len();
+        self.watched.retain(|r| r != register);
+        self.watched.len() != before
+    }
+
+    pub fn watched(&self) -> &[String] {
+        &self.watched
+    }
+
+    /// Start again from the first instruction, keeping breakpoints and
+    /// watches.
+    pub fn reset(&mut self) {
+        self.vm = self.start.clone();
+        self.runs.iter_mut().for_each(|runs| *runs = 0);
+        self.trace.clear();
+    }
+
+    /// Run one instruction, whatever breakpoint is on it.
+    pub fn step(&mut self) -> Result<Stop> {
+        if self.vm.is_halted() {
+            return Ok(Stop::Halted);
+        }
+
+        let pc = self.vm.pc() as usize;
+        let instruction = self.program.instructions()[pc].clone();
+        let before = self.watched_values();
+
+        self.vm.step()?;
+        self.runs[pc] += 1;
+        self.trace.push(Traced {
+            pc,
+            instruction,
+            acc: self.vm.acc(),
+        });
+
+        let changed = self
+            .watched
+            .iter()
+            .zip(before)
+            .find_map(|(register, from)| {
+                let to = self.vm.registers().get(register);
+                match (from, to) {
+                    (from, Some(to)) if from != Some(to) => Some(Stop::Changed {
+                        register: register.clone(),
+                        from: from.unwrap_or_default(),
+                        to,
+                    }),
+                    _ => None,
+                }
+            });
+
+        Ok(match changed {
+            Some(stop) => stop,
+            None if self.vm.is_halted() => Stop::Halted,
+            None => Stop::Stepped,
+        })
+    }
+
+    /// Run until the program halts, reaches a breakpoint, changes a watched
+    /// register or is about to run an instruction it has already run. The
+    /// instruction it starts on always runs, so resuming from a breakpoint
+    /// moves on. Gives up with a `Timeout` if the current budget runs out.
+    pub fn resume(&mut self) -> Result<Stop> {
+        let budget = Budget::current();
+        let mut seen = vec![false; self.runs.len()];
+        let mut first = true;
+
+        loop {
+            if self.vm.is_halted() {
+                return Ok(Stop::Halted);
+            }
+
+            let pc = self.vm.pc() as usize;
+            if !first {
+                if self.breakpoints.contains(&pc) {
+                    return Ok(Stop::Breakpoint(pc));
+                }
+                if self.stop_on_repeat && seen[pc] {
+                    return Ok(Stop::Repeat(pc));
+                }
+            }
+            first = false;
+            seen[pc] = true;
+
+            match self.step()? {
+                Stop::Stepped => budget.check_at(self.vm.cycles())?,
+                stop => return Ok(stop),
+            }
+        }
+    }
+
+    fn watched_values(&self) -> Vec<Option<i64>> {
+        self.watched
+            .iter()
+            .map(|register| self.vm.registers().get(register))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_input;
+
+    fn debugger() -> Debugger {
+        let input = test_input(
+            "
+            nop +0
+            acc +1
+            jmp +4
+            acc +3
+            jmp -3
+            acc -99
+            acc +1
+            jmp -4
+            acc +6
+            ",
+        );
+        Debugger::new(&Program::new(&input).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn stepping() {
+        let mut debugger = debugger();
+        assert_eq!(debugger.step().unwrap(), Stop::Stepped);
+        assert_eq!(debugger.step().unwrap(), Stop::Stepped);
+        assert_eq!(debugger.step().unwrap(), Stop::Stepped);
+
+        assert_eq!(
+            debugger
+                .trace()
+                .iter()
+                .map(Traced::to_string)
+                .collect::<Vec<String>>(),
+            vec![
+                "   0  nop +0       acc=0",
+                "   1  acc +1       acc=1",
+                "   2  jmp +4       acc=1",
+            ]
+        );
+        assert_eq!(debugger.vm().pc(), 6);
+    }
+
+    #[test]
+    fn resuming() {
+        let mut debugger = debugger();
+        assert_eq!(debugger.resume().unwrap(), Stop::Repeat(1));
+        assert_eq!(debugger.vm().acc(), 5);
+        assert_eq!(debugger.trace().len(), 7);
+
+        // resuming runs the repeated instruction and carries on to the
+        // breakpoint
+        debugger.break_at(3);
+        assert_eq!(debugger.resume().unwrap(), Stop::Breakpoint(3));
+        assert_eq!(debugger.runs(1), 2);
+        assert_eq!(debugger.resume().unwrap(), Stop::Breakpoint(3));
+
+        assert!(debugger.clear(3));
+        assert!(!debugger.clear(3));
+        debugger.reset();
+        assert!(debugger.trace().is_empty());
+        assert_eq!(debugger.resume().unwrap(), Stop::Repeat(1));
+    }
+
+    #[test]
+    fn watching() {
+        let mut debugger = debugger();
+        debugger.watch("acc");
+        assert_eq!(
+            debugger.resume().unwrap(),
+            Stop::Changed {
+                register: "acc".to_string(),
+                from: 0,
+                to: 1
+            }
+        );
+        assert_eq!(debugger.vm().pc(), 2);
+        assert!(debugger.unwatch("acc"));
+        assert_eq!(debugger.resume().unwrap(), Stop::Repeat(2));
+    }
+
+    #[test]
+    fn halting() {
+        let program = InstructionSet::standard()
+            .program(&["mov a +3", "acc a", "subr a +1", "jnz a -2"])
+            .unwrap();
+        let mut debugger = Debugger::new(&program).unwrap();
+        assert_eq!(debugger.resume().unwrap(), Stop::Repeat(1));
+
+        let mut debugger = Debugger::new(&program).unwrap().stop_on_repeat(false);
+        assert_eq!(debugger.resume().unwrap(), Stop::Halted);
+        assert_eq!(debugger.vm().acc(), 6);
+        assert_eq!(debugger.step().unwrap(), Stop::Halted);
+    }
+}
@@ -1,8 +1,13 @@
 use crate::error::{AocError, Result};
+use crate::input::Input;
+use crate::solution::{Part, Solution, Variant};
+use crate::trace;
+use crate::util::parse_lines;
 use std::collections::HashMap;
 use std::str::FromStr;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Program {
     memory: HashMap<usize, u64>,
 }
@@ -140,7 +145,8 @@ impl Mask {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
-enum Instruction {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Instruction {
     Mask(String),
     Assign(usize, u64),
 }
@@ -186,16 +192,16 @@ impl FromStr for Instruction {
 pub struct Initializer;
 
 impl Initializer {
-    pub fn initialize(input: &[String]) -> Result<Program> {
+    pub fn initialize<S: AsRef<str>>(input: &[S]) -> Result<Program> {
+        Initializer::run(&parse_lines(input, Instruction::from_str)?)
+    }
+
+    pub fn run(instructions: &[Instruction]) -> Result<Program> {
+        let _span = trace::span!("Initializer::run");
         let mut program = Program::new();
         let mut mask = None;
 
-        let instructions = input
-            .iter()
-            .map(|line| Instruction::from_str(line))
-            .collect::<Result<Vec<Instruction>>>()?;
-
-        for instruction in &instructions {
+        for instruction in instructions {
             match instruction {
                 Instruction::Mask(m) => {
                     mask = Some(Mask::new(m)?);
@@ -214,16 +220,16 @@ impl Initializer {
         Ok(program)
     }
 
-    pub fn initialize_v2(input: &[String]) -> Result<Program> {
+    pub fn initialize_v2<S: AsRef<str>>(input: &[S]) -> Result<Program> {
+        Initializer::run_v2(&parse_lines(input, Instruction::from_str)?)
+    }
+
+    pub fn run_v2(instructions: &[Instruction]) -> Result<Program> {
+        let _span = trace::span!("Initializer::run_v2");
         let mut program = Program::new();
         let mut mask = None;
 
-        let instructions = input
-            .iter()
-            .map(|line| Instruction::from_str(line))
-            .collect::<Result<Vec<Instruction>>>()?;
-
-        for instruction in &instructions {
+        for instruction in instructions {
             match instruction {
                 Instruction::Mask(m) => {
                     mask = Some(Mask::new(m)?);
@@ -244,16 +250,16 @@ impl Initializer {
         Ok(program)
     }
 
-    pub fn initialize_v2_memoized(input: &[String]) -> Result<Program> {
+    pub fn initialize_v2_memoized<S: AsRef<str>>(input: &[S]) -> Result<Program> {
+        Initializer::run_v2_memoized(&parse_lines(input, Instruction::from_str)?)
+    }
+
+    pub fn run_v2_memoized(instructions: &[Instruction]) -> Result<Program> {
+        let _span = trace::span!("Initializer::run_v2_memoized");
         let mut program = Program::new();
         let mut mask = None;
 
-        let instructions = input
-            .iter()
-            .map(|line| Instruction::from_str(line))
-            .collect::<Result<Vec<Instruction>>>()?;
-
-        for instruction in &instructions {
+        for instruction in instructions {
             match instruction {
                 Instruction::Mask(m) => {
                     mask = Some(Mask::new(m)?);
@@ -275,6 +281,36 @@ impl Initializer {
     }
 }
 
+pub struct DockingData;
+
+impl Solution for DockingData {
+    const DAY: usize = 14;
+    const NAME: &'static str = "Docking Data";
+
+    type Parsed = Vec<Instruction>;
+
+    const VARIANTS: &'static [Variant<Self::Parsed>] = &[Variant {
+        part: Part::Two,
+        name: "initialize_v2",
+        slow: false,
+        solve: |input| Ok(Initializer::run_v2(input)?.memory_sum().to_string()),
+    }];
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed> {
+        parse_lines(&input.lines(), Instruction::from_str)
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String> {
+        Ok(Initializer::run(parsed)?.memory_sum().to_string())
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String> {
+        Ok(Initializer::run_v2_memoized(parsed)?
+            .memory_sum()
+            .to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,159 @@
+//! Parsed models and answers as JSON, with the `serde` feature:
+//!
+//! ```text
+//! AOC_DUMP=model.json cargo run --features serde --example 008_handheld-halting
+//! cargo run --features serde -- dump 24 > lobby.json
+//! ```
+//!
+//! Without the feature, setting `AOC_DUMP` is an error rather than ignored.
+
+use std::env;
+#[cfg(feature = "serde")]
+use std::fs;
+
+use crate::error::{AocError, Result};
+#[cfg(feature = "serde")]
+use crate::input::{Input, Source};
+#[cfg(feature = "serde")]
+use crate::solution::{self, Part, Solver};
+
+/// Set this to a path to have the examples write their parsed model and
+/// answers there as JSON. Needs the `serde` feature.
+pub const DUMP_VAR: &str = "AOC_DUMP";
+
+/// Where to write a day's parsed model and answers as JSON, if anywhere.
+#[derive(Debug, Default)]
+pub struct Dump {
+    path: Option<String>,
+}
+
+impl Dump {
+    /// A dump that writes nothing.
+    pub fn new() -> Self {
+        Dump::default()
+    }
+
+    /// Write to the file at `path`. Fails without the `serde` feature.
+    pub fn to(path: &str) -> Result<Self> {
+        if !cfg!(feature = "serde") {
+            return Err(AocError::Serialization(format!(
+                "cannot dump to '{}', aoc was built without the serde feature",
+                path
+            )));
+        }
+
+        Ok(Dump {
+            path: Some(path.to_string()),
+        })
+    }
+
+    /// Write to the file named by `AOC_DUMP`, if it is set.
+    pub fn from_env() -> Result<Self> {
+        match env::var(DUMP_VAR) {
+            Ok(path) => Dump::to(&path),
+            Err(_) => Ok(Dump::new()),
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.path.is_some()
+    }
+}
+
+/// Solve a day against its own input and dump it to the file named by
+/// `AOC_DUMP`, or do nothing if that is not set. The examples call this once
+/// they have printed their answers.
+pub fn example(day: usize) -> Result<()> {
+    match Dump::from_env()?.path {
+        #[cfg(feature = "serde")]
+        Some(ref path) => {
+            let json = solve(solution::get(day)?, &Source::day(day).load()?)?;
+            fs::write(path, json + "\n")?;
+            Ok(())
+        }
+        _ => {
+            let _ = day;
+            Ok(())
+        }
+    }
+}
+
+/// A day's model and answers as a JSON object with `day`, `name`, `model`
+/// and `answers` keys.
+#[cfg(feature = "serde")]
+pub fn to_json(day: usize, model: serde_json::Value, answers: &[(Part, String)]) -> Result<String> {
+    let answers = answers
+        .iter()
+        .map(|(part, answer)| (part.to_string(), serde_json::Value::from(answer.as_str())))
+        .collect::<serde_json::Map<String, serde_json::Value>>();
+
+    Ok(serde_json::to_string_pretty(&serde_json::json!({
+        "day": day,
+        "name": solution::get(day)?.name(),
+        "model": model,
+        "answers": answers,
+    }))?)
+}
+
+/// Parse and solve every part of a day through the registry and dump the
+/// result.
+#[cfg(feature = "serde")]
+pub fn solve(solution: &dyn Solver, input: &Input) -> Result<String> {
+    let parsed = solution.parse_input(input)?;
+
+    let mut answers = Vec::new();
+    for part in solution.parts() {
+        answers.push((*part, solution.solve_part(parsed.as_ref(), *part)?));
+    }
+
+    to_json(solution.day(), solution.model(parsed.as_ref())?, &answers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inactive() {
+        assert!(!Dump::new().is_active());
+        example(1).unwrap();
+    }
+
+    #[cfg(not(feature = "serde"))]
+    #[test]
+    fn unsupported() {
+        assert!(Dump::to("model.json").is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn dumping() {
+        let input = Input::from("1721\n979\n366\n299\n675\n1456\n");
+        let json: serde_json::Value =
+            serde_json::from_str(&solve(solution::get(1).unwrap(), &input).unwrap()).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "day": 1,
+                "name": "Report Repair",
+                "model": [1721, 979, 366, 299, 675, 1456],
+                "answers": {"part 1": "514579", "part 2": "241861950"},
+            })
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn dumping_every_day() {
+        for solution in solution::solutions() {
+            let input = Source::day(solution.day()).load().unwrap();
+            let parsed = solution.parse_input(&input).unwrap();
+            assert!(
+                solution.model(parsed.as_ref()).is_ok(),
+                "day {}",
+                solution.day()
+            );
+        }
+    }
+}
@@ -1,6 +1,13 @@
+use crate::error::{AocError, Result};
+use crate::input::Input;
+use crate::solution::{Part, Solution};
+use crate::trace;
+use crate::util::parse_lines;
+
 const DIVISOR: usize = 20201227;
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Key(pub usize);
 
 impl Key {
@@ -16,6 +23,7 @@ impl Key {
     }
 
     pub fn loop_size(&self, subject_number: usize) -> usize {
+        let _span = trace::span!("Key::loop_size");
         let mut value = 1;
         let mut size = 0;
         while value != self.0 {
@@ -28,6 +36,7 @@ impl Key {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Device {
     pub public_key: Key,
     loop_size: usize,
@@ -50,10 +59,48 @@ impl Device {
     }
 
     pub fn encryption_key(&self, other: &Key) -> Key {
+        let _span = trace::span!("Device::encryption_key");
         Key::new(self.loop_size, other.0)
     }
 }
 
+pub struct ComboBreaker;
+
+impl Solution for ComboBreaker {
+    const DAY: usize = 25;
+    const NAME: &'static str = "Combo Breaker";
+    const PARTS: &'static [Part] = &[Part::One];
+
+    // (card device, door device)
+    type Parsed = (Device, Device);
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed> {
+        let mut devices = parse_lines(&input.lines(), |line| {
+            Ok(Device::from_key(Key(line.parse::<usize>()?), 7))
+        })?;
+
+        if devices.len() != 2 {
+            return Err(AocError::InvalidInput(
+                "Expected exactly two public keys".to_string(),
+            ));
+        }
+
+        let door = devices.pop().unwrap();
+        let card = devices.pop().unwrap();
+        Ok((card, door))
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String> {
+        Ok(parsed.0.encryption_key(&parsed.1.public_key).0.to_string())
+    }
+
+    fn part_two(&self, _parsed: &Self::Parsed) -> Result<String> {
+        Err(AocError::UnknownSolution(
+            "day 25 part 2, there is only one puzzle".to_string(),
+        ))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,5 +1,7 @@
 use std::num::ParseIntError;
 
+use crate::span::Span;
+
 pub type Result<T> = std::result::Result<T, AocError>;
 
 /// AocError enumerates all possible errors returned by this library
@@ -25,6 +27,8 @@ pub enum AocError {
     InvalidInstruction(String),
     InvalidProgram(String),
     UnknownOperation(String),
+    UnknownLabel(String),
+    DuplicateLabel(String),
 
     /// XMAS
     NoOutlier,
@@ -45,6 +49,27 @@ pub enum AocError {
     /// tiles
     ArrangementNotFound,
 
+    /// Solution registry
+    UnknownSolution(String),
+
+    /// Answer verification
+    AnswerFileError(String),
+
+    /// Parse errors with the place in the input they came from
+    Located(Span, Box<AocError>),
+
+    /// A solution that panicked while running in isolation
+    Panicked(String),
+
+    /// Dumping parsed models as JSON
+    Serialization(String),
+
+    /// Downloading puzzle inputs
+    FetchError(String),
+
+    /// Solves that ran out of time or were cancelled
+    Timeout(String),
+
     /// Represents all other cases of
     IOError(std::io::Error),
 
@@ -55,6 +80,129 @@ pub enum AocError {
     ParseIntError(ParseIntError),
 }
 
+impl AocError {
+    /// Attach a span to the error, unless it already has one.
+    pub fn at(self, span: Span) -> Self {
+        match self {
+            AocError::Located(..) => self,
+            err => AocError::Located(span, Box::new(err)),
+        }
+    }
+
+    /// Attach a span found by looking for the bad text of this error in the
+    /// given lines, the first of which is line `first_line`. Errors that
+    /// already have a span relative to these lines are moved down to
+    /// `first_line`.
+    pub fn locate<S: AsRef<str>>(self, lines: &[S], first_line: usize) -> Self {
+        match self {
+            AocError::Located(mut span, err) => {
+                span.line += first_line - 1;
+                AocError::Located(span, err)
+            }
+            err => {
+                let span = Span::locate(lines, first_line, err.fragment());
+                err.at(span)
+            }
+        }
+    }
+
+    /// Record which file the input came from.
+    pub fn in_file(self, file: &str) -> Self {
+        match self {
+            AocError::Located(mut span, err) => {
+                span.file = Some(file.to_string());
+                AocError::Located(span, err)
+            }
+            err => err,
+        }
+    }
+
+    pub fn span(&self) -> Option<&Span> {
+        match *self {
+            AocError::Located(ref span, _) => Some(span),
+            _ => None,
+        }
+    }
+
+    /// The error message followed by a snippet of the offending line, if the
+    /// error has a span.
+    pub fn report(&self) -> String {
+        match *self {
+            AocError::Located(ref span, ref err) => format!("{}\n{}", err, span.snippet()),
+            ref err => err.to_string(),
+        }
+    }
+
+    /// The name of the variant, for reporting errors to other programs. A
+    /// located error has the kind of the error it wraps.
+    pub fn kind(&self) -> &'static str {
+        match *self {
+            AocError::ForestDefinitionError(_) => "ForestDefinitionError",
+            AocError::InvalidAnswers(_) => "InvalidAnswers",
+            AocError::InvalidLocator(_) => "InvalidLocator",
+            AocError::InvalidRule(_) => "InvalidRule",
+            AocError::SeatNotFound(_) => "SeatNotFound",
+            AocError::PassportInfoError(_) => "PassportInfoError",
+            AocError::PassportInvalid(_) => "PassportInvalid",
+            AocError::PasswordDefinitionError(_) => "PasswordDefinitionError",
+            AocError::PolicyDefinitionError(_) => "PolicyDefinitionError",
+            AocError::UnknownBag(_) => "UnknownBag",
+            AocError::InputError(_) => "InputError",
+            AocError::InvalidInput(_) => "InvalidInput",
+            AocError::InvalidInstruction(_) => "InvalidInstruction",
+            AocError::InvalidProgram(_) => "InvalidProgram",
+            AocError::UnknownOperation(_) => "UnknownOperation",
+            AocError::UnknownLabel(_) => "UnknownLabel",
+            AocError::DuplicateLabel(_) => "DuplicateLabel",
+            AocError::NoOutlier => "NoOutlier",
+            AocError::NoWeakness => "NoWeakness",
+            AocError::NoAdapterChain => "NoAdapterChain",
+            AocError::SeatDoesNotExist(_) => "SeatDoesNotExist",
+            AocError::GameError(_) => "GameError",
+            AocError::NoValidRuleOrder => "NoValidRuleOrder",
+            AocError::ArrangementNotFound => "ArrangementNotFound",
+            AocError::UnknownSolution(_) => "UnknownSolution",
+            AocError::AnswerFileError(_) => "AnswerFileError",
+            AocError::Located(_, ref err) => err.kind(),
+            AocError::Panicked(_) => "Panicked",
+            AocError::Serialization(_) => "Serialization",
+            AocError::FetchError(_) => "FetchError",
+            AocError::Timeout(_) => "Timeout",
+            AocError::IOError(_) => "IOError",
+            AocError::OsStringErr(_) => "OsStringErr",
+            AocError::ParseIntError(_) => "ParseIntError",
+        }
+    }
+
+    /// The piece of input an error complains about. Messages that wrap it in
+    /// other words quote it, so prefer the quoted part when there is one.
+    fn fragment(&self) -> Option<&str> {
+        let text = match *self {
+            AocError::ForestDefinitionError(ref s)
+            | AocError::InvalidAnswers(ref s)
+            | AocError::InvalidLocator(ref s)
+            | AocError::InvalidRule(ref s)
+            | AocError::PassportInfoError(ref s)
+            | AocError::PassportInvalid(ref s)
+            | AocError::PasswordDefinitionError(ref s)
+            | AocError::PolicyDefinitionError(ref s)
+            | AocError::UnknownBag(ref s)
+            | AocError::InvalidInput(ref s)
+            | AocError::InvalidInstruction(ref s)
+            | AocError::UnknownOperation(ref s)
+            | AocError::UnknownLabel(ref s)
+            | AocError::DuplicateLabel(ref s) => s.as_str(),
+            _ => return None,
+        };
+
+        let mut quoted = text.splitn(3, '\'');
+        match (quoted.next(), quoted.next(), quoted.next()) {
+            (Some(_), Some(inner), Some(_)) if !inner.is_empty() => Some(inner),
+            _ => Some(text),
+        }
+    }
+}
+
 impl std::error::Error for AocError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match *self {
@@ -76,6 +224,8 @@ impl std::error::Error for AocError {
             AocError::InvalidInstruction(_) => None,
             AocError::InvalidProgram(_) => None,
             AocError::UnknownOperation(_) => None,
+            AocError::UnknownLabel(_) => None,
+            AocError::DuplicateLabel(_) => None,
             AocError::NoOutlier => None,
             AocError::NoWeakness => None,
             AocError::NoAdapterChain => None,
@@ -83,6 +233,13 @@ impl std::error::Error for AocError {
             AocError::GameError(_) => None,
             AocError::NoValidRuleOrder => None,
             AocError::ArrangementNotFound => None,
+            AocError::UnknownSolution(_) => None,
+            AocError::AnswerFileError(_) => None,
+            AocError::Located(_, ref err) => Some(err.as_ref()),
+            AocError::Panicked(_) => None,
+            AocError::Serialization(_) => None,
+            AocError::FetchError(_) => None,
+            AocError::Timeout(_) => None,
         }
     }
 }
@@ -115,6 +272,8 @@ impl std::fmt::Display for AocError {
             AocError::InvalidInstruction(ref def) => write!(f, "Invalid instruction: '{}'", def),
             AocError::InvalidProgram(ref def) => write!(f, "Invalid program: '{}'", def),
             AocError::UnknownOperation(ref def) => write!(f, "Unknown operation: '{}'", def),
+            AocError::UnknownLabel(ref def) => write!(f, "Unknown label: '{}'", def),
+            AocError::DuplicateLabel(ref def) => write!(f, "Duplicate label: '{}'", def),
             AocError::IOError(ref err) => err.fmt(f),
             AocError::OsStringErr(ref err) => write!(f, "OsString error: {:?}", err),
             AocError::ParseIntError(ref err) => err.fmt(f),
@@ -125,6 +284,13 @@ impl std::fmt::Display for AocError {
             AocError::GameError(ref msg) => write!(f, "Memory game error: '{}'", msg),
             AocError::NoValidRuleOrder => write!(f, "No rule order could be determined"),
             AocError::ArrangementNotFound => write!(f, "Arrangement has not been found yet"),
+            AocError::UnknownSolution(ref def) => write!(f, "No solution for {}", def),
+            AocError::AnswerFileError(ref def) => write!(f, "Invalid answers file: '{}'", def),
+            AocError::Located(ref span, ref err) => write!(f, "{}: {}", span, err),
+            AocError::Panicked(ref def) => write!(f, "Solution panicked: {}", def),
+            AocError::Serialization(ref def) => write!(f, "Could not serialize: {}", def),
+            AocError::FetchError(ref def) => write!(f, "Could not fetch: {}", def),
+            AocError::Timeout(ref def) => write!(f, "Timed out: {}", def),
         }
     }
 }
@@ -146,3 +312,10 @@ impl From<std::ffi::OsString> for AocError {
         AocError::OsStringErr(err)
     }
 }
+
+#[cfg(feature = "serde")]
+impl From<serde_json::Error> for AocError {
+    fn from(err: serde_json::Error) -> AocError {
+        AocError::Serialization(err.to_string())
+    }
+}
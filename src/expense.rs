@@ -1,5 +1,11 @@
 use std::collections::HashMap;
 
+use crate::error::{AocError, Result};
+use crate::input::Input;
+use crate::solution::{Part, Solution, Variant};
+use crate::trace;
+use crate::util::parse_lines;
+
 pub fn expense_report_slow(nums: &[i32], target: i32) -> Option<i32> {
     for i in nums {
         for j in nums {
@@ -12,6 +18,7 @@ pub fn expense_report_slow(nums: &[i32], target: i32) -> Option<i32> {
 }
 
 pub fn expense_report(nums: &[i32], target: i32) -> Option<i32> {
+    let _span = trace::span!("expense_report");
     let mut hash: HashMap<i32, i32> = HashMap::with_capacity(nums.len());
     for i in nums {
         if let Some(j) = hash.get(i) {
@@ -27,7 +34,7 @@ pub fn triple_expense_slow(nums: &[i32], target: i32) -> Option<i32> {
     for i in nums {
         for j in nums {
             for k in nums {
-                if i + j + k == target {
+                if i != j && j != k && i != k && i + j + k == target {
                     return Some(i * j * k);
                 }
             }
@@ -37,6 +44,7 @@ pub fn triple_expense_slow(nums: &[i32], target: i32) -> Option<i32> {
 }
 
 pub fn triple_expense(nums: &[i32], target: i32) -> Option<i32> {
+    let _span = trace::span!("triple_expense");
     for i in nums {
         let mut hash: HashMap<i32, i32> = HashMap::with_capacity(nums.len());
         for j in nums {
@@ -57,25 +65,96 @@ pub fn triple_expense(nums: &[i32], target: i32) -> Option<i32> {
     None
 }
 
+pub struct ReportRepair;
+
+impl Solution for ReportRepair {
+    const DAY: usize = 1;
+    const NAME: &'static str = "Report Repair";
+
+    type Parsed = Vec<i32>;
+
+    const VARIANTS: &'static [Variant<Self::Parsed>] = &[
+        Variant {
+            part: Part::One,
+            name: "expense_report_slow",
+            slow: false,
+            solve: |nums| {
+                expense_report_slow(nums, 2020)
+                    .map(|v| v.to_string())
+                    .ok_or_else(|| AocError::InvalidInput("No two entries sum to 2020".to_string()))
+            },
+        },
+        Variant {
+            part: Part::Two,
+            name: "triple_expense_slow",
+            slow: false,
+            solve: |nums| {
+                triple_expense_slow(nums, 2020)
+                    .map(|v| v.to_string())
+                    .ok_or_else(|| {
+                        AocError::InvalidInput("No three entries sum to 2020".to_string())
+                    })
+            },
+        },
+    ];
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed> {
+        parse_lines(&input.lines(), |line| Ok(line.parse::<i32>()?))
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String> {
+        expense_report(parsed, 2020)
+            .map(|v| v.to_string())
+            .ok_or_else(|| AocError::InvalidInput("No two entries sum to 2020".to_string()))
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String> {
+        triple_expense(parsed, 2020)
+            .map(|v| v.to_string())
+            .ok_or_else(|| AocError::InvalidInput("No three entries sum to 2020".to_string()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::util::load_example;
+
+    fn example() -> Vec<i32> {
+        load_example("001", 0)
+            .unwrap()
+            .iter()
+            .map(|line| line.parse().unwrap())
+            .collect()
+    }
 
     #[test]
     fn expense_report_satisfies_example() {
-        let res = expense_report(&vec![1721, 979, 366, 299, 675, 1456], 2020);
+        let res = expense_report(&example(), 2020);
         assert_eq!(res, Some(514579));
 
-        let res = expense_report_slow(&vec![1721, 979, 366, 299, 675, 1456], 2020);
+        let res = expense_report_slow(&example(), 2020);
         assert_eq!(res, Some(514579));
     }
 
     #[test]
     fn expense_report_out_of_3_values() {
-        let res = triple_expense(&vec![1721, 979, 366, 299, 675, 1456], 2020);
+        let res = triple_expense(&example(), 2020);
         assert_eq!(res, Some(241861950));
 
-        let res = triple_expense_slow(&vec![1721, 979, 366, 299, 675, 1456], 2020);
+        let res = triple_expense_slow(&example(), 2020);
         assert_eq!(res, Some(241861950));
+
+        // an entry may only be used once
+        let res = triple_expense_slow(&vec![1000, 20, 5, 2000, 15], 2020);
+        assert_eq!(res, Some(150000));
+    }
+
+    #[test]
+    fn readme_example() {
+        let input = load_example("001", 0).unwrap();
+        let parsed = ReportRepair.parse(&Input::from_lines(&input)).unwrap();
+        assert_eq!(ReportRepair.part_one(&parsed).unwrap(), "514579");
+        assert_eq!(ReportRepair.part_two(&parsed).unwrap(), "241861950");
     }
 }
@@ -0,0 +1,378 @@
+//! Downloads a day's input and puzzle description into its examples
+//! directory, with the session cookie of a logged in browser:
+//!
+//! ```text
+//! AOC_SESSION=53616c7465645f5f... cargo run --features fetch -- fetch 7
+//! ```
+//!
+//! Files already there are kept, so delete one to fetch it again.
+
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{AocError, Result};
+use crate::solution;
+use crate::util::examples_dir;
+
+/// The session cookie of a logged in Advent of Code account.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+/// Set this to fetch from somewhere other than adventofcode.com, such as a
+/// local stub server.
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com/2020";
+
+/// What became of one of the files of a day.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Fetched {
+    Downloaded(PathBuf),
+    /// The file was already there, so nothing was requested.
+    Cached(PathBuf),
+}
+
+impl fmt::Display for Fetched {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Fetched::Downloaded(ref path) => write!(f, "downloaded {}", path.display()),
+            Fetched::Cached(ref path) => write!(f, "cached {}", path.display()),
+        }
+    }
+}
+
+/// Downloads the input and puzzle description of a day into its directory
+/// under the examples directory, as `input` and `README.md`. Files that
+/// already exist are never fetched again.
+#[derive(Debug)]
+pub struct Fetcher {
+    base_url: String,
+    session: Option<String>,
+    dir: PathBuf,
+    agent: ureq::Agent,
+}
+
+impl Fetcher {
+    /// Fetch from adventofcode.com into the examples directory. Without a
+    /// session only cached files can be had.
+    pub fn new(session: Option<&str>) -> Self {
+        Fetcher {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            session: session.map(str::to_string),
+            dir: examples_dir(),
+            agent: ureq::AgentBuilder::new()
+                .user_agent(concat!("aoc/", env!("CARGO_PKG_VERSION"), " fetch"))
+                .build(),
+        }
+    }
+
+    /// Use the session in `AOC_SESSION` and the base URL in `AOC_BASE_URL`,
+    /// when they are set.
+    pub fn from_env() -> Self {
+        let session = env::var(SESSION_VAR).ok().filter(|s| !s.is_empty());
+        let fetcher = Fetcher::new(session.as_deref());
+
+        match env::var(BASE_URL_VAR) {
+            Ok(url) if !url.is_empty() => fetcher.base_url(&url),
+            _ => fetcher,
+        }
+    }
+
+    pub fn base_url(mut self, url: &str) -> Self {
+        self.base_url = url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Put day directories under `dir` instead of the examples directory.
+    pub fn dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.dir = dir.as_ref().to_path_buf();
+        self
+    }
+
+    /// Fetch the input and then the README of a day.
+    pub fn fetch(&self, day: usize) -> Result<Vec<Fetched>> {
+        let dir = self.day_dir(day)?;
+
+        let input =
+            self.fetch_file(&dir.join("input"), &format!("/day/{}/input", day), |body| {
+                body.to_string()
+            })?;
+        let readme = self.fetch_file(&dir.join("README.md"), &format!("/day/{}", day), readme)?;
+
+        Ok(vec![input, readme])
+    }
+
+    /// The existing directory of a day, e.g. `examples/019_monster-message`,
+    /// or a new one named after the solution.
+    fn day_dir(&self, day: usize) -> Result<PathBuf> {
+        let prefix = format!("{:03}_", day);
+
+        if self.dir.is_dir() {
+            for entry in fs::read_dir(&self.dir)? {
+                let entry = entry?;
+                if entry.path().is_dir() && entry.file_name().into_string()?.starts_with(&prefix) {
+                    return Ok(entry.path());
+                }
+            }
+        }
+
+        let slug = solution::get(day)?
+            .name()
+            .to_lowercase()
+            .split_whitespace()
+            .collect::<Vec<&str>>()
+            .join("-");
+
+        Ok(self.dir.join(format!("{}{}", prefix, slug)))
+    }
+
+    fn fetch_file<F: Fn(&str) -> String>(
+        &self,
+        path: &Path,
+        url_path: &str,
+        convert: F,
+    ) -> Result<Fetched> {
+        if path.exists() {
+            return Ok(Fetched::Cached(path.to_path_buf()));
+        }
+
+        let session = self.session.as_ref().ok_or_else(|| {
+            AocError::FetchError(format!(
+                "{} is missing and {} is not set",
+                path.display(),
+                SESSION_VAR
+            ))
+        })?;
+
+        let url = format!("{}{}", self.base_url, url_path);
+        let body = self
+            .agent
+            .get(&url)
+            .set("Cookie", &format!("session={}", session))
+            .call()
+            .map_err(|err| match err {
+                ureq::Error::Status(code, _) => {
+                    AocError::FetchError(format!("{} returned {}", url, code))
+                }
+                err => AocError::FetchError(format!("{}: {}", url, err)),
+            })?
+            .into_string()?;
+
+        // write then rename, so an interrupted download is not cached
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let partial = path.with_extension("partial");
+        fs::write(&partial, convert(&body))?;
+        fs::rename(&partial, path)?;
+
+        Ok(Fetched::Downloaded(path.to_path_buf()))
+    }
+}
+
+/// Turn the HTML of a puzzle page into the plain text READMEs are kept in,
+/// with the examples in fenced blocks so `util::load_example` finds them.
+pub fn readme(html: &str) -> String {
+    let body = match (html.find("<main>"), html.find("</main>")) {
+        (Some(start), Some(end)) if start < end => &html[start + "<main>".len()..end],
+        _ => html,
+    };
+
+    let mut out = String::new();
+    let mut rest = body;
+    while let Some(start) = rest.find('<') {
+        out.push_str(&unescape(&rest[..start]));
+
+        let end = match rest[start..].find('>') {
+            Some(end) => start + end,
+            None => break,
+        };
+        let tag = rest[start + 1..end]
+            .split_whitespace()
+            .next()
+            .unwrap_or_default();
+        rest = &rest[end + 1..];
+
+        match tag {
+            "pre" => {
+                if !out.is_empty() && !out.ends_with('\n') {
+                    out.push('\n');
+                }
+                out.push_str("```\n");
+            }
+            "/pre" => {
+                if !out.ends_with('\n') {
+                    out.push('\n');
+                }
+                out.push_str("```\n");
+            }
+            "/h2" => out.push('\n'),
+            "/p" => out.push_str("\n\n"),
+            "li" => out.push_str("- "),
+            "/li" => out.push('\n'),
+            _ => {}
+        }
+    }
+    out.push_str(&unescape(rest));
+
+    let lines = out.lines().map(str::trim_end).collect::<Vec<&str>>();
+    format!("{}\n", lines.join("\n").trim())
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    const PAGE: &str = "<html><body><header>Advent of Code</header><main>\
+        <article class=\"day-desc\"><h2>--- Day 1: Report Repair ---</h2>\
+        <p>Find the <em>two entries</em> that sum to 2020 &amp; multiply them.</p>\
+        <pre><code>1721\n979\n</code></pre>\
+        <p>What do you get?</p></article></main></body></html>";
+
+    /// Answer `count` requests with a body chosen by path, recording the
+    /// request line and cookie of each.
+    fn stub(count: usize) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}/2020", listener.local_addr().unwrap());
+        let seen = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = Arc::clone(&seen);
+        thread::spawn(move || {
+            for stream in listener.incoming().take(count) {
+                let mut stream = stream.unwrap();
+                let mut request = Vec::new();
+                for line in BufReader::new(&stream).lines() {
+                    let line = line.unwrap();
+                    if line.is_empty() {
+                        break;
+                    }
+                    if line.starts_with("GET") || line.starts_with("Cookie") {
+                        request.push(line);
+                    }
+                }
+
+                let first = request[0].clone();
+                recorded.lock().unwrap().push(request.join(" | "));
+
+                let (status, body) = match first.as_str() {
+                    "GET /2020/day/1/input HTTP/1.1" => ("200 OK", "1721\n979\n"),
+                    "GET /2020/day/1 HTTP/1.1" => ("200 OK", PAGE),
+                    _ => ("404 Not Found", "nope"),
+                };
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+
+        (base_url, seen)
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-fetch-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn converting_readmes() {
+        assert_eq!(
+            readme(PAGE),
+            "\
+--- Day 1: Report Repair ---
+Find the two entries that sum to 2020 & multiply them.
+
+```
+1721
+979
+```
+What do you get?
+"
+        );
+    }
+
+    #[test]
+    fn fetching() {
+        let (base_url, seen) = stub(2);
+        let dir = temp_dir("fresh");
+        let fetcher = Fetcher::new(Some("cookie"))
+            .base_url(&format!("{}/", base_url))
+            .dir(&dir);
+
+        let day = dir.join("001_report-repair");
+        assert_eq!(
+            fetcher.fetch(1).unwrap(),
+            vec![
+                Fetched::Downloaded(day.join("input")),
+                Fetched::Downloaded(day.join("README.md"))
+            ]
+        );
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![
+                "GET /2020/day/1/input HTTP/1.1 | Cookie: session=cookie",
+                "GET /2020/day/1 HTTP/1.1 | Cookie: session=cookie",
+            ]
+        );
+        assert_eq!(
+            fs::read_to_string(day.join("input")).unwrap(),
+            "1721\n979\n"
+        );
+        assert_eq!(
+            crate::util::example_blocks(
+                &crate::util::load_lines(day.join("README.md").to_str().unwrap()).unwrap()
+            ),
+            vec![vec!["1721".to_string(), "979".to_string()]]
+        );
+
+        // the stub only answers twice, so this would fail if it asked again
+        assert_eq!(
+            fetcher.fetch(1).unwrap(),
+            vec![
+                Fetched::Cached(day.join("input")),
+                Fetched::Cached(day.join("README.md"))
+            ]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn failing() {
+        let (base_url, _) = stub(1);
+        let dir = temp_dir("failing");
+
+        // an existing directory is reused, whatever its name
+        fs::create_dir_all(dir.join("002_passwords")).unwrap();
+        let fetcher = Fetcher::new(Some("cookie")).base_url(&base_url).dir(&dir);
+        match fetcher.fetch(2) {
+            Err(AocError::FetchError(message)) => assert!(message.ends_with("returned 404")),
+            other => panic!("expected a fetch error, got {:?}", other),
+        }
+        assert!(!dir.join("002_passwords/input").exists());
+        assert!(!dir.join("002_passwords/input.partial").exists());
+
+        let fetcher = Fetcher::new(None).dir(&dir);
+        assert!(matches!(fetcher.fetch(2), Err(AocError::FetchError(_))));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,13 +1,19 @@
 use crate::error::{AocError, Result};
+use crate::input::Input;
+use crate::solution::Solution;
+use crate::trace;
 use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Allergen(pub String);
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ingredient(pub String);
 
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Food {
     id: usize,
     ingredients: HashSet<Ingredient>,
@@ -41,6 +47,7 @@ impl Food {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShoppingList {
     food: Vec<Food>,
     allergens: HashSet<Allergen>,
@@ -56,11 +63,12 @@ impl ShoppingList {
         }
     }
 
-    pub fn from_input(input: &[String]) -> Result<Self> {
+    pub fn from_input<S: AsRef<str>>(input: &[S]) -> Result<Self> {
+        let _span = trace::span!("ShoppingList::from_input");
         let mut list = Self::new();
 
         for (index, line) in input.iter().enumerate() {
-            let mut parts = line.split(" (contains ");
+            let mut parts = line.as_ref().split(" (contains ");
 
             // ingredients
             if let Some(ingredients_str) = parts.next() {
@@ -96,6 +104,7 @@ impl ShoppingList {
     }
 
     pub fn match_allergens(&self) -> HashMap<Ingredient, Option<Allergen>> {
+        let _span = trace::span!("ShoppingList::match_allergens");
         let mut candidate_map = self.make_candidate_map();
         let mut ingredient_map: HashMap<Ingredient, Option<Allergen>> = HashMap::new();
         self.ingredients
@@ -178,6 +187,45 @@ impl ShoppingList {
     }
 }
 
+pub struct AllergenAssessment;
+
+impl Solution for AllergenAssessment {
+    const DAY: usize = 21;
+    const NAME: &'static str = "Allergen Assessment";
+
+    type Parsed = ShoppingList;
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed> {
+        ShoppingList::from_input(&input.lines())
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String> {
+        Ok(parsed
+            .match_allergens()
+            .iter()
+            .filter(|(_, v)| v.is_none())
+            .map(|(ingredient, _)| parsed.count_appearance(ingredient))
+            .sum::<usize>()
+            .to_string())
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String> {
+        let mut mapping = parsed
+            .match_allergens()
+            .into_iter()
+            .filter_map(|(ingredient, allergen)| allergen.map(|a| (ingredient, a)))
+            .collect::<Vec<(Ingredient, Allergen)>>();
+
+        mapping.sort_by(|a, b| a.1 .0.cmp(&b.1 .0));
+
+        Ok(mapping
+            .iter()
+            .map(|(i, _)| i.0.as_ref())
+            .collect::<Vec<&str>>()
+            .join(","))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
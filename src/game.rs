@@ -1,9 +1,15 @@
+use crate::budget::Budget;
 use crate::error::{AocError, Result};
+use crate::input::Input;
+use crate::progress::Progress;
+use crate::solution::Solution;
+use crate::trace;
 use std::collections::HashMap;
 use std::fmt;
 use std::str::FromStr;
 
 #[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct History {
     calls: usize,
     prev: usize,
@@ -41,6 +47,7 @@ impl History {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Game {
     numbers: HashMap<usize, History>,
     last_spoken: usize,
@@ -97,6 +104,30 @@ impl Game {
             "Game has not been initialized".to_string(),
         ))
     }
+
+    /// Take turns until `turn` has been played, returning the last number
+    /// spoken, or a `Timeout` error if the budget runs out first.
+    pub fn play_until(&mut self, turn: usize, budget: &Budget) -> Result<usize> {
+        self.play_until_with(turn, budget, |_, _| {})
+    }
+
+    /// As `play_until`, showing `observe` the game after each turn, with the
+    /// turns played so far as the step.
+    pub fn play_until_with<F: FnMut(Progress, &Self)>(
+        &mut self,
+        turn: usize,
+        budget: &Budget,
+        mut observe: F,
+    ) -> Result<usize> {
+        let _span = trace::span!("Game::play_until_with");
+        while self.turn <= turn {
+            budget.check_at(self.turn)?;
+            self.take_turn()?;
+            observe(Progress::new(self.turn - 1, turn), self);
+        }
+
+        Ok(self.last_spoken)
+    }
 }
 
 impl FromStr for Game {
@@ -121,6 +152,34 @@ impl fmt::Display for Game {
     }
 }
 
+pub struct RambunctiousRecitation;
+
+impl Solution for RambunctiousRecitation {
+    const DAY: usize = 15;
+    const NAME: &'static str = "Rambunctious Recitation";
+
+    type Parsed = Game;
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed> {
+        Game::from_str(
+            input
+                .lines()
+                .first()
+                .ok_or_else(|| AocError::InvalidInput("No starting numbers".to_string()))?,
+        )
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String> {
+        let mut game = parsed.clone();
+        Ok(game.play_until(2020, &Budget::current())?.to_string())
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String> {
+        let mut game = parsed.clone();
+        Ok(game.play_until(30_000_000, &Budget::current())?.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -175,6 +234,7 @@ mod tests {
 
     mod game {
         use super::*;
+        use crate::budget::CHECK_EVERY;
 
         #[test]
         fn from_str() {
@@ -264,5 +324,38 @@ mod tests {
 
             assert_eq!(game.get_last_spoken(), 436);
         }
+
+        #[test]
+        fn play_until() {
+            let mut game = Game::from_str("0,3,6").unwrap();
+            assert_eq!(game.play_until(2020, &Budget::unlimited()).unwrap(), 436);
+
+            let budget = Budget::unlimited();
+            budget.cancel();
+            let mut game = Game::from_str("0,3,6").unwrap();
+            assert!(matches!(
+                game.play_until(30_000_000, &budget),
+                Err(AocError::Timeout(_))
+            ));
+            assert!(game.get_turn() <= CHECK_EVERY);
+        }
+
+        #[test]
+        fn play_until_with() {
+            let mut game = Game::from_str("0,3,6").unwrap();
+            let mut seen = Vec::new();
+            let last = game
+                .play_until_with(10, &Budget::unlimited(), |progress, game| {
+                    seen.push((progress.step, game.get_last_spoken()));
+                    assert_eq!(progress.total, 10);
+                })
+                .unwrap();
+
+            assert_eq!(last, 0);
+            assert_eq!(
+                seen,
+                vec![(4, 0), (5, 3), (6, 3), (7, 1), (8, 0), (9, 4), (10, 0)]
+            );
+        }
     }
 }
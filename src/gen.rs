@@ -0,0 +1,1359 @@
+//! Random but valid inputs for every day, the same for the same seed: console
+//! programs fixed by one flip, bag rules without cycles, jigsaw tiles cut
+//! from an image with sea monsters in it and so on. `aoc gen DAY --size N`
+//! prints one, for stress benchmarks or for cross-checking variants.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use crate::error::{AocError, Result};
+use crate::input::Input;
+use crate::jigsaw::SEA_MONSTER;
+use crate::seating::Area;
+
+/// Rng is SplitMix64: tiny, fast and the same on every platform, so a seed
+/// always produces the same input.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`. `n` must not be zero.
+    pub fn below(&mut self, n: usize) -> usize {
+        ((self.next_u64() as u128 * n as u128) >> 64) as usize
+    }
+
+    /// A number in `low..=high`.
+    pub fn range(&mut self, low: usize, high: usize) -> usize {
+        low + self.below(high - low + 1)
+    }
+
+    /// A signed number in `low..=high`.
+    pub fn range_i64(&mut self, low: i64, high: i64) -> i64 {
+        low + self.below((high - low + 1) as usize) as i64
+    }
+
+    /// True with probability `p`.
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// A generator writes the lines of a puzzle input. What `size` means depends
+/// on the day, usually the number of lines or records; each generator clamps
+/// it to what its puzzle can handle.
+pub type Generator = fn(&mut Rng, usize) -> Vec<String>;
+
+static GENERATORS: [Generator; 25] = [
+    expense, password, toboggan, passport, boarding, customs, luggage, console, xmas, adapter,
+    seating, navigation, shuttle, docking, game, ticket, power, calculator, message, jigsaw, food,
+    combat, cups, lobby, encryption,
+];
+
+/// The generator for the given day (1 through 25).
+pub fn generator(day: usize) -> Result<Generator> {
+    day.checked_sub(1)
+        .and_then(|index| GENERATORS.get(index))
+        .copied()
+        .ok_or_else(|| AocError::UnknownSolution(format!("day {} generator", day)))
+}
+
+/// A random but valid input for the day, the same every time for a seed.
+pub fn generate(day: usize, seed: u64, size: usize) -> Result<Input> {
+    let lines = generator(day)?(&mut Rng::new(seed), size);
+    Ok(Input::from_lines(&lines))
+}
+
+fn letters(rng: &mut Rng, alphabet: &[u8], len: usize) -> String {
+    (0..len).map(|_| *rng.choose(alphabet) as char).collect()
+}
+
+fn grid(rng: &mut Rng, rows: usize, cols: usize, p: f64, on: char, off: char) -> Vec<String> {
+    (0..rows)
+        .map(|_| {
+            (0..cols)
+                .map(|_| if rng.chance(p) { on } else { off })
+                .collect()
+        })
+        .collect()
+}
+
+/// Exactly one pair and one triple of entries sum to 2020, whatever the
+/// number of entries.
+pub fn expense(rng: &mut Rng, size: usize) -> Vec<String> {
+    let mut values: Vec<usize>;
+    loop {
+        let a = rng.range(1, 2019);
+        let x = rng.range(1, 1000);
+        let y = rng.range(1, 2019 - x - 1);
+        values = vec![a, 2020 - a, x, y, 2020 - x - y];
+
+        let unique = values.iter().collect::<HashSet<&usize>>().len() == values.len();
+        let pairs = (0..5)
+            .flat_map(|i| (i + 1..5).map(move |j| (i, j)))
+            .filter(|(i, j)| values[*i] + values[*j] == 2020)
+            .count();
+        let triples = (0..5)
+            .flat_map(|i| (i + 1..5).flat_map(move |j| (j + 1..5).map(move |k| (i, j, k))))
+            .filter(|(i, j, k)| values[*i] + values[*j] + values[*k] == 2020)
+            .count();
+
+        if unique && pairs == 1 && triples == 1 {
+            break;
+        }
+    }
+
+    // pair sums below 2020, to reject fillers that would add a triple
+    let mut sums = HashSet::new();
+    for i in 0..values.len() {
+        for j in i + 1..values.len() {
+            sums.insert(values[i] + values[j]);
+        }
+    }
+
+    let mut seen = values.iter().cloned().collect::<HashSet<usize>>();
+    let mut attempts = 0;
+    while values.len() < size.max(5) && attempts < size * 100 {
+        attempts += 1;
+        let v = rng.range(1, 2019);
+        if seen.contains(&v) || seen.contains(&(2020 - v)) || sums.contains(&(2020 - v)) {
+            continue;
+        }
+
+        sums.extend(values.iter().map(|w| v + w).filter(|sum| *sum < 2020));
+        seen.insert(v);
+        values.push(v);
+    }
+
+    rng.shuffle(&mut values);
+    values.iter().map(|v| v.to_string()).collect()
+}
+
+pub fn password(rng: &mut Rng, size: usize) -> Vec<String> {
+    (0..size.max(1))
+        .map(|_| {
+            let len = rng.range(3, 20);
+            let low = rng.range(1, len - 1);
+            let high = rng.range(low + 1, len);
+            let letter = *rng.choose(b"abcdefghijklmnopqrstuvwxyz");
+            let pwd = (0..len)
+                .map(|_| {
+                    if rng.chance(0.4) {
+                        letter as char
+                    } else {
+                        *rng.choose(b"abcdefghijklmnopqrstuvwxyz") as char
+                    }
+                })
+                .collect::<String>();
+            format!("{}-{} {}: {}", low, high, letter as char, pwd)
+        })
+        .collect()
+}
+
+/// `size` rows of the usual 31 columns.
+pub fn toboggan(rng: &mut Rng, size: usize) -> Vec<String> {
+    grid(rng, size.max(1), 31, 0.2, '#', '.')
+}
+
+/// Passports with missing and invalid fields mixed in, split over lines at
+/// random.
+pub fn passport(rng: &mut Rng, size: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for index in 0..size.max(1) {
+        let hgt = match rng.below(3) {
+            0 => format!("{}cm", rng.range(140, 200)),
+            1 => format!("{}in", rng.range(55, 80)),
+            _ => rng.range(50, 190).to_string(),
+        };
+        let hcl = if rng.chance(0.8) {
+            format!("#{}", letters(rng, b"0123456789abcdef", 6))
+        } else {
+            letters(rng, b"0123456789abcdefz", 6)
+        };
+        let ecl = rng
+            .choose(&[
+                "amb", "blu", "brn", "gry", "grn", "hzl", "oth", "xry", "gmt",
+            ])
+            .to_string();
+        let pid_len = if rng.chance(0.85) {
+            9
+        } else {
+            rng.range(8, 10)
+        };
+
+        let mut fields = vec![
+            format!("byr:{}", rng.range(1910, 2010)),
+            format!("iyr:{}", rng.range(2005, 2025)),
+            format!("eyr:{}", rng.range(2015, 2035)),
+            format!("hgt:{}", hgt),
+            format!("hcl:{}", hcl),
+            format!("ecl:{}", ecl),
+            format!("pid:{}", letters(rng, b"0123456789", pid_len)),
+            format!("cid:{}", rng.range(10, 350)),
+        ];
+        fields.retain(|_| rng.chance(0.95));
+        rng.shuffle(&mut fields);
+
+        if index > 0 {
+            lines.push(String::new());
+        }
+        let start = lines.len();
+        let mut line = Vec::new();
+        for field in fields {
+            line.push(field);
+            if rng.chance(0.3) {
+                lines.push(line.join(" "));
+                line.clear();
+            }
+        }
+        if !line.is_empty() || lines.len() == start {
+            lines.push(line.join(" "));
+        }
+    }
+
+    lines
+}
+
+/// A run of consecutive seats with one missing from the middle.
+pub fn boarding(rng: &mut Rng, size: usize) -> Vec<String> {
+    let count = size.clamp(3, 1000);
+    let start = rng.range(0, 1023 - count);
+    let missing = rng.range(start + 1, start + count - 2);
+
+    let mut passes = (start..start + count)
+        .filter(|id| *id != missing)
+        .map(|id| {
+            let row = (0..7).rev().map(|bit| {
+                if (id >> 3) & (1 << bit) == 0 {
+                    'F'
+                } else {
+                    'B'
+                }
+            });
+            let col = (0..3)
+                .rev()
+                .map(|bit| if id & (1 << bit) == 0 { 'L' } else { 'R' });
+            row.chain(col).collect::<String>()
+        })
+        .collect::<Vec<String>>();
+
+    rng.shuffle(&mut passes);
+    passes
+}
+
+pub fn customs(rng: &mut Rng, size: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for index in 0..size.max(1) {
+        if index > 0 {
+            lines.push(String::new());
+        }
+
+        let shared = rng.range(0, 4);
+        let common = letters(rng, b"abcdefghijklmnopqrstuvwxyz", shared);
+        for _ in 0..rng.range(1, 5) {
+            let mut answers = common.bytes().collect::<BTreeSet<u8>>();
+            for _ in 0..rng.range(1, 10) {
+                answers.insert(*rng.choose(b"abcdefghijklmnopqrstuvwxyz"));
+            }
+            let mut answers = answers.into_iter().collect::<Vec<u8>>();
+            rng.shuffle(&mut answers);
+            lines.push(String::from_utf8(answers).unwrap_or_default());
+        }
+    }
+
+    lines
+}
+
+const ADJECTIVES: [&str; 30] = [
+    "bright", "clear", "dark", "dim", "dotted", "drab", "dull", "faded", "light", "mirrored",
+    "muted", "pale", "plaid", "posh", "shiny", "striped", "vibrant", "wavy", "dusty", "mottled",
+    "vivid", "dashed", "dull", "bold", "clear", "wavy", "pale", "drab", "light", "mirrored",
+];
+
+const COLORS: [&str; 30] = [
+    "aqua",
+    "beige",
+    "black",
+    "blue",
+    "bronze",
+    "brown",
+    "chartreuse",
+    "coral",
+    "crimson",
+    "cyan",
+    "fuchsia",
+    "gold",
+    "gray",
+    "green",
+    "indigo",
+    "lavender",
+    "lime",
+    "magenta",
+    "maroon",
+    "olive",
+    "orange",
+    "plum",
+    "purple",
+    "red",
+    "salmon",
+    "silver",
+    "tan",
+    "teal",
+    "tomato",
+    "white",
+];
+
+/// Bag rules without cycles: every bag only holds bags of deeper levels, and
+/// shiny gold sits in the middle so both parts have something to count.
+pub fn luggage(rng: &mut Rng, size: usize) -> Vec<String> {
+    const LEVELS: usize = 7;
+
+    let mut names = BTreeSet::new();
+    names.insert("shiny gold".to_string());
+    while names.len() < size.clamp(2, 600) {
+        names.insert(format!(
+            "{} {}",
+            rng.choose(&ADJECTIVES),
+            rng.choose(&COLORS)
+        ));
+    }
+
+    let mut bags = names
+        .into_iter()
+        .map(|name| {
+            let level = if name == "shiny gold" {
+                LEVELS / 2
+            } else {
+                rng.below(LEVELS)
+            };
+            (level, name)
+        })
+        .collect::<Vec<(usize, String)>>();
+    bags.sort();
+
+    let mut lines = bags
+        .iter()
+        .enumerate()
+        .map(|(index, (level, name))| {
+            let deeper = bags[index..]
+                .iter()
+                .filter(|(other, _)| other > level)
+                .map(|(_, name)| name)
+                .collect::<Vec<&String>>();
+
+            let mut contents = BTreeMap::new();
+            if !deeper.is_empty() {
+                for _ in 0..rng.range(0, 4) {
+                    contents.insert(rng.choose(&deeper).to_string(), rng.range(1, 5));
+                }
+            }
+            // left to chance, hardly any bag would hold shiny gold
+            if *level < LEVELS / 2 && rng.chance(0.1) {
+                contents.insert("shiny gold".to_string(), rng.range(1, 5));
+            }
+
+            if contents.is_empty() {
+                format!("{} bags contain no other bags.", name)
+            } else {
+                let contents = contents
+                    .iter()
+                    .map(|(inner, count)| {
+                        format!(
+                            "{} {} bag{}",
+                            count,
+                            inner,
+                            if *count == 1 { "" } else { "s" }
+                        )
+                    })
+                    .collect::<Vec<String>>();
+                format!("{} bags contain {}.", name, contents.join(", "))
+            }
+        })
+        .collect::<Vec<String>>();
+
+    rng.shuffle(&mut lines);
+    lines
+}
+
+/// A program that loops, and that flipping exactly one `jmp` makes
+/// terminate. Everything before that `jmp` only ever moves forward up to it,
+/// so no other flip can escape the loop or leave the program.
+pub fn console(rng: &mut Rng, size: usize) -> Vec<String> {
+    let len = size.max(2);
+    let broken = rng.range(len / 2, len - 1);
+
+    (0..len)
+        .map(|index| {
+            let limit = if index < broken { broken } else { len };
+            if index == broken {
+                return format!("jmp -{}", rng.range(1, broken));
+            }
+
+            match rng.below(10) {
+                0..=4 => format!("acc {:+}", rng.range_i64(-50, 50)),
+                5 | 6 => format!(
+                    "nop {:+}",
+                    rng.range_i64(-(index as i64), (limit - index) as i64)
+                ),
+                _ => format!("jmp +{}", rng.range(1, limit - index)),
+            }
+        })
+        .collect()
+}
+
+/// Every number past the preamble is a sum of two of the 25 before it,
+/// except one that is instead the sum of a contiguous run of at least three
+/// earlier numbers.
+pub fn xmas(rng: &mut Rng, size: usize) -> Vec<String> {
+    const PREAMBLE: usize = 25;
+
+    let len = size.max(PREAMBLE + 5);
+    let outlier_at = rng.range(PREAMBLE + (len - PREAMBLE) / 2, len - 1);
+
+    let mut data = Vec::new();
+    while data.len() < PREAMBLE {
+        let v = rng.range(1, 60) as u64;
+        if !data.contains(&v) {
+            data.push(v);
+        }
+    }
+
+    while data.len() < len {
+        let window = &data[data.len() - PREAMBLE..];
+        let is_sum = |target: u64| {
+            (0..PREAMBLE).any(|i| (i + 1..PREAMBLE).any(|j| window[i] + window[j] == target))
+        };
+
+        let next = if data.len() == outlier_at {
+            loop {
+                let start = rng.range(0, data.len() - 3);
+                let end = rng.range(start + 2, (start + 17).min(data.len() - 1));
+                let sum = data[start..=end].iter().sum::<u64>();
+                if !is_sum(sum) {
+                    break sum;
+                }
+            }
+        } else {
+            loop {
+                let i = rng.below(PREAMBLE);
+                let j = rng.below(PREAMBLE);
+                if window[i] != window[j] {
+                    break window[i] + window[j];
+                }
+            }
+        };
+        data.push(next);
+    }
+
+    data.iter().map(|v| v.to_string()).collect()
+}
+
+/// Joltage steps of 1 and 3, with runs of 1s kept short and few enough that
+/// the number of arrangements fits in an i64.
+pub fn adapter(rng: &mut Rng, size: usize) -> Vec<String> {
+    const ARRANGEMENTS: [u64; 5] = [1, 1, 2, 4, 7];
+
+    let count = size.max(2);
+    let mut ratings = Vec::new();
+    let mut rating = 0;
+    let mut arrangements = 1u64;
+    let mut three = false;
+
+    ratings.push({
+        rating += 1;
+        rating
+    });
+    while ratings.len() < count {
+        let mut run = rng.range(0, 4);
+        if arrangements.saturating_mul(ARRANGEMENTS[run]) > 1 << 50 {
+            run = run.min(1);
+        }
+        arrangements *= ARRANGEMENTS[run];
+
+        for _ in 0..run {
+            if ratings.len() < count {
+                rating += 1;
+                ratings.push(rating);
+            }
+        }
+        if ratings.len() < count {
+            rating += 3;
+            ratings.push(rating);
+            three = true;
+        }
+    }
+
+    // day 10 needs a step of 3 between adapters, not only up to the device
+    if !three {
+        if let Some(last) = ratings.last_mut() {
+            *last += 2;
+        }
+    }
+
+    rng.shuffle(&mut ratings);
+    ratings.iter().map(|v| v.to_string()).collect()
+}
+
+/// A square seat layout with `size` rows, at most 100. Random layouts often
+/// never settle, flipping back and forth forever, so layouts are drawn until
+/// one settles under the rules of both parts.
+pub fn seating(rng: &mut Rng, size: usize) -> Vec<String> {
+    let side = size.clamp(1, 100);
+    loop {
+        let layout = grid(rng, side, side, 0.8, 'L', '.');
+        if settles(&layout, Some(1), 4, side * 4) && settles(&layout, None, 5, side * 4) {
+            return layout;
+        }
+    }
+}
+
+fn settles(
+    layout: &[String],
+    search_range: Option<usize>,
+    tipping_point: usize,
+    steps: usize,
+) -> bool {
+    let mut area = match Area::new(layout, search_range, tipping_point) {
+        Ok(area) => area,
+        Err(_) => return false,
+    };
+
+    for _ in 0..steps {
+        match area.step() {
+            Ok(next) if next == area => return true,
+            Ok(next) => area = next,
+            Err(_) => return false,
+        }
+    }
+
+    false
+}
+
+pub fn navigation(rng: &mut Rng, size: usize) -> Vec<String> {
+    (0..size.max(1))
+        .map(|_| match rng.below(7) {
+            0 => format!("L{}", rng.choose(&[90, 180, 270])),
+            1 => format!("R{}", rng.choose(&[90, 180, 270])),
+            2 | 3 => format!("F{}", rng.range(1, 100)),
+            _ => format!("{}{}", *rng.choose(b"NSEW") as char, rng.range(1, 10)),
+        })
+        .collect()
+}
+
+/// Up to six buses with distinct prime ids, which keeps the search for the
+/// synchronised departure short.
+pub fn shuttle(rng: &mut Rng, size: usize) -> Vec<String> {
+    let mut primes = vec![7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43];
+    rng.shuffle(&mut primes);
+    let buses = &primes[..size.clamp(2, 6)];
+
+    let mut schedule = Vec::new();
+    for (index, bus) in buses.iter().enumerate() {
+        if index > 0 {
+            for _ in 0..rng.range(0, 5) {
+                schedule.push("x".to_string());
+            }
+        }
+        schedule.push(bus.to_string());
+    }
+
+    vec![rng.range(1000, 1_000_000).to_string(), schedule.join(",")]
+}
+
+/// Masks with at most nine floating bits, each followed by a few writes.
+pub fn docking(rng: &mut Rng, size: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for _ in 0..size.max(1) {
+        let mut mask = (0..36)
+            .map(|_| *rng.choose(b"01") as char)
+            .collect::<Vec<char>>();
+        for _ in 0..rng.range(0, 9) {
+            let bit = rng.below(36);
+            mask[bit] = 'X';
+        }
+        lines.push(format!("mask = {}", mask.into_iter().collect::<String>()));
+
+        for _ in 0..rng.range(1, 6) {
+            lines.push(format!(
+                "mem[{}] = {}",
+                rng.below(65536),
+                rng.range(1, 100_000_000)
+            ));
+        }
+    }
+
+    lines
+}
+
+pub fn game(rng: &mut Rng, size: usize) -> Vec<String> {
+    let mut numbers = (0..20).collect::<Vec<usize>>();
+    rng.shuffle(&mut numbers);
+    let numbers = numbers[..size.clamp(2, 10)]
+        .iter()
+        .map(|n| n.to_string())
+        .collect::<Vec<String>>();
+
+    vec![numbers.join(",")]
+}
+
+const FIELDS: [&str; 20] = [
+    "departure location",
+    "departure station",
+    "departure platform",
+    "departure track",
+    "departure date",
+    "departure time",
+    "arrival location",
+    "arrival station",
+    "arrival platform",
+    "arrival track",
+    "class",
+    "duration",
+    "price",
+    "route",
+    "row",
+    "seat",
+    "train",
+    "type",
+    "wagon",
+    "zone",
+];
+
+/// Ticket notes whose rule order can be worked out by elimination. Every
+/// field gets a rank and a band of values, and the rule of rank `r` accepts
+/// the bands of ranks up to `r`, so the column of the highest rank fits just
+/// one rule, the next one two, and so on. `size` is the number of nearby
+/// tickets.
+pub fn ticket(rng: &mut Rng, size: usize) -> Vec<String> {
+    let fields = FIELDS.len();
+    let band = |rank: usize| 25 + 12 * rank;
+
+    let mut ranks = (0..fields).collect::<Vec<usize>>();
+    rng.shuffle(&mut ranks);
+    let mut columns = (0..fields).collect::<Vec<usize>>();
+    rng.shuffle(&mut columns);
+
+    let mut lines = Vec::new();
+    let mut rules = (0..fields)
+        .map(|field| {
+            // the value band(k) + 5 is never used, so the rule may skip it
+            let rank = ranks[field];
+            let split = rng.range(0, rank);
+            format!(
+                "{}: {}-{} or {}-{}",
+                FIELDS[field],
+                band(0),
+                band(split) + 4,
+                band(split) + 6,
+                band(rank) + 10
+            )
+        })
+        .collect::<Vec<String>>();
+    rng.shuffle(&mut rules);
+    lines.extend(rules);
+
+    let valid_ticket = |rng: &mut Rng| {
+        columns
+            .iter()
+            .map(|field| {
+                let low = band(ranks[*field]);
+                let mut value = rng.range(low, low + 9);
+                if value >= low + 5 {
+                    value += 1;
+                }
+                value
+            })
+            .collect::<Vec<usize>>()
+    };
+
+    lines.push(String::new());
+    lines.push("your ticket:".to_string());
+    let ours = valid_ticket(rng);
+    lines.push(join(&ours));
+
+    lines.push(String::new());
+    lines.push("nearby tickets:".to_string());
+    for index in 0..size.max(1) {
+        let mut values = valid_ticket(rng);
+        if index > 0 && rng.chance(0.25) {
+            let column = rng.below(fields);
+            values[column] = if rng.chance(0.5) {
+                rng.range(0, band(0) - 1)
+            } else {
+                rng.range(band(fields) + 20, 999)
+            };
+        }
+        lines.push(join(&values));
+    }
+
+    lines
+}
+
+fn join(values: &[usize]) -> String {
+    values
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+/// A square starting slice of `size` rows, at most 8 to keep the four
+/// dimensional part quick.
+pub fn power(rng: &mut Rng, size: usize) -> Vec<String> {
+    let side = size.clamp(3, 8);
+    grid(rng, side, side, 0.4, '#', '.')
+}
+
+enum Expr {
+    Num(u64),
+    Group(Vec<Expr>, Vec<char>),
+}
+
+impl Expr {
+    fn random(rng: &mut Rng, depth: usize) -> Self {
+        let len = rng.range(2, 5);
+        let terms = (0..len)
+            .map(|_| {
+                if depth > 0 && rng.chance(0.25) {
+                    Expr::random(rng, depth - 1)
+                } else {
+                    Expr::Num(rng.range(1, 9) as u64)
+                }
+            })
+            .collect();
+        let ops = (1..len).map(|_| *rng.choose(b"+*") as char).collect();
+        Expr::Group(terms, ops)
+    }
+
+    /// The value evaluating strictly left to right, or with addition first.
+    fn eval(&self, addition_first: bool) -> Option<u64> {
+        match self {
+            Expr::Num(n) => Some(*n),
+            Expr::Group(terms, ops) => {
+                let mut products = vec![terms[0].eval(addition_first)?];
+                for (op, term) in ops.iter().zip(&terms[1..]) {
+                    let value = term.eval(addition_first)?;
+                    let last = products.last_mut()?;
+                    match (op, addition_first) {
+                        ('+', _) => *last = last.checked_add(value)?,
+                        (_, false) => *last = last.checked_mul(value)?,
+                        _ => products.push(value),
+                    }
+                }
+                products
+                    .into_iter()
+                    .try_fold(1u64, |acc, v| acc.checked_mul(v))
+                    .filter(|v| *v < 1_000_000_000_000)
+            }
+        }
+    }
+
+    fn render(&self, nested: bool) -> String {
+        match self {
+            Expr::Num(n) => n.to_string(),
+            Expr::Group(terms, ops) => {
+                let mut out = terms[0].render(true);
+                for (op, term) in ops.iter().zip(&terms[1..]) {
+                    out.push_str(&format!(" {} {}", op, term.render(true)));
+                }
+                if nested {
+                    format!("({})", out)
+                } else {
+                    out
+                }
+            }
+        }
+    }
+}
+
+/// Expressions whose values stay well inside a u64 under both sets of
+/// precedence rules.
+pub fn calculator(rng: &mut Rng, size: usize) -> Vec<String> {
+    (0..size.max(1))
+        .map(|_| loop {
+            let expr = Expr::random(rng, 2);
+            if expr.eval(false).is_some() && expr.eval(true).is_some() {
+                break expr.render(false);
+            }
+        })
+        .collect()
+}
+
+enum Grammar {
+    Letter(char),
+    Pairs(Vec<[usize; 2]>),
+}
+
+impl Grammar {
+    fn expand(rules: &BTreeMap<usize, Grammar>, rng: &mut Rng, id: usize, out: &mut String) {
+        match &rules[&id] {
+            Grammar::Letter(ch) => out.push(*ch),
+            Grammar::Pairs(pairs) => {
+                let [left, right] = *rng.choose(pairs);
+                Grammar::expand(rules, rng, left, out);
+                Grammar::expand(rules, rng, right, out);
+            }
+        }
+    }
+}
+
+/// Rules built in levels from the two letters, where each level pairs up the
+/// two rules of the level below: one rule gets the mixed pairs and the other
+/// the matching pairs, so 42 and 31 match disjoint sets of 8 letter messages.
+pub fn message(rng: &mut Rng, size: usize) -> Vec<String> {
+    const LEVELS: usize = 3;
+
+    let mut ids = (1..=130)
+        .filter(|id| ![8, 11, 31, 42].contains(id))
+        .collect::<Vec<usize>>();
+    rng.shuffle(&mut ids);
+
+    let mut grammar = BTreeMap::new();
+    let (first, second) = if rng.chance(0.5) {
+        ('a', 'b')
+    } else {
+        ('b', 'a')
+    };
+    let mut pair = [ids[0], ids[1]];
+    grammar.insert(pair[0], Grammar::Letter(first));
+    grammar.insert(pair[1], Grammar::Letter(second));
+
+    for level in 1..=LEVELS {
+        let [p, q] = pair;
+        let mut mixed = vec![[p, q], [q, p]];
+        let mut matching = vec![[p, p], [q, q]];
+        rng.shuffle(&mut mixed);
+        rng.shuffle(&mut matching);
+
+        pair = if level < LEVELS {
+            [ids[2 * level], ids[2 * level + 1]]
+        } else if rng.chance(0.5) {
+            [42, 31]
+        } else {
+            [31, 42]
+        };
+        grammar.insert(pair[0], Grammar::Pairs(mixed));
+        grammar.insert(pair[1], Grammar::Pairs(matching));
+    }
+
+    let mut rules = vec![
+        "0: 8 11".to_string(),
+        "8: 42".to_string(),
+        "11: 42 31".to_string(),
+    ];
+    rules.extend(grammar.iter().map(|(id, rule)| match rule {
+        Grammar::Letter(ch) => format!("{}: \"{}\"", id, ch),
+        Grammar::Pairs(pairs) => {
+            let pairs = pairs
+                .iter()
+                .map(|[left, right]| format!("{} {}", left, right))
+                .collect::<Vec<String>>();
+            format!("{}: {}", id, pairs.join(" | "))
+        }
+    }));
+    rng.shuffle(&mut rules);
+
+    let mut lines = rules;
+    lines.push(String::new());
+    for _ in 0..size.max(1) {
+        // valid for part one, valid only once the loops are in, or noise
+        let (repeats, closing) = match rng.below(10) {
+            0..=3 => (2, 1),
+            4..=6 => {
+                let closing = rng.range(1, 3);
+                (rng.range(closing + 1, closing + 3), closing)
+            }
+            _ => {
+                let len = rng.range(8, 48);
+                lines.push(letters(rng, b"ab", len));
+                continue;
+            }
+        };
+
+        let mut line = String::new();
+        for _ in 0..repeats {
+            Grammar::expand(&grammar, rng, 42, &mut line);
+        }
+        for _ in 0..closing {
+            Grammar::expand(&grammar, rng, 31, &mut line);
+        }
+        lines.push(line);
+    }
+
+    lines
+}
+
+/// Tiles cut from one random image with sea monsters hidden in it, each
+/// rotated and flipped at random. Every edge is unique so that there is
+/// only one arrangement. `size` is the number of tiles along a side.
+pub fn jigsaw(rng: &mut Rng, size: usize) -> Vec<String> {
+    let n = size.clamp(3, 12);
+    let image_side = 8 * n;
+
+    let monster = SEA_MONSTER
+        .iter()
+        .enumerate()
+        .flat_map(|(r, row)| {
+            row.chars()
+                .enumerate()
+                .filter(|(_, ch)| *ch == '#')
+                .map(move |(c, _)| (r, c))
+        })
+        .collect::<Vec<(usize, usize)>>();
+
+    let mut image = vec![vec![false; image_side]; image_side];
+    let mut taken = vec![vec![false; image_side]; image_side];
+    for _ in 0..n * n / 2 + 1 {
+        let r = rng.range(0, image_side - 3);
+        let c = rng.range(0, image_side - 20);
+        if monster.iter().all(|(dr, dc)| !taken[r + dr][c + dc]) {
+            for (dr, dc) in &monster {
+                taken[r + dr][c + dc] = true;
+                image[r + dr][c + dc] = true;
+            }
+        }
+    }
+    for (r, row) in image.iter_mut().enumerate() {
+        for (c, cell) in row.iter_mut().enumerate() {
+            if !taken[r][c] {
+                *cell = rng.chance(0.2);
+            }
+        }
+    }
+
+    let full = loop {
+        if let Some(full) = jigsaw_borders(rng, n, &image) {
+            break full;
+        }
+    };
+
+    let mut ids = BTreeSet::new();
+    while ids.len() < n * n {
+        ids.insert(rng.range(1000, 9999));
+    }
+    let mut ids = ids.into_iter().collect::<Vec<usize>>();
+    rng.shuffle(&mut ids);
+
+    let mut tiles = Vec::new();
+    for r in 0..n {
+        for c in 0..n {
+            let mut tile = (0..10)
+                .map(|row| full[9 * r + row][9 * c..9 * c + 10].to_vec())
+                .collect::<Vec<Vec<bool>>>();
+            for _ in 0..rng.below(4) {
+                tile = (0..10)
+                    .map(|row| (0..10).map(|col| tile[9 - col][row]).collect())
+                    .collect();
+            }
+            if rng.chance(0.5) {
+                tile.iter_mut().for_each(|row| row.reverse());
+            }
+            tiles.push(tile);
+        }
+    }
+
+    let mut order = (0..tiles.len()).collect::<Vec<usize>>();
+    rng.shuffle(&mut order);
+
+    let mut lines = Vec::new();
+    for (index, tile) in order.into_iter().enumerate() {
+        if index > 0 {
+            lines.push(String::new());
+        }
+        lines.push(format!("Tile {}:", ids[tile]));
+        lines.extend(tiles[tile].iter().map(|row| {
+            row.iter()
+                .map(|on| if *on { '#' } else { '.' })
+                .collect::<String>()
+        }));
+    }
+
+    lines
+}
+
+/// Lay the image out with one shared border row and column between tiles,
+/// filling the borders so that no two edges look alike either way round.
+fn jigsaw_borders(rng: &mut Rng, n: usize, image: &[Vec<bool>]) -> Option<Vec<Vec<bool>>> {
+    let side = 9 * n + 1;
+    let mut full = vec![vec![false; side]; side];
+
+    for r in 0..n {
+        for c in 0..n {
+            for i in 0..8 {
+                for j in 0..8 {
+                    full[9 * r + 1 + i][9 * c + 1 + j] = image[8 * r + i][8 * c + j];
+                }
+            }
+        }
+    }
+    for r in 0..=n {
+        for c in 0..=n {
+            full[9 * r][9 * c] = rng.chance(0.5);
+        }
+    }
+
+    let mut used = HashSet::new();
+    let mut edges = Vec::new();
+    for line in 0..=n {
+        for block in 0..n {
+            edges.push((true, 9 * line, 9 * block));
+            edges.push((false, 9 * block, 9 * line));
+        }
+    }
+
+    for (horizontal, row, col) in edges {
+        let cell = |i: usize| {
+            if horizontal {
+                (row, col + i)
+            } else {
+                (row + i, col)
+            }
+        };
+        let mut placed = false;
+
+        for _ in 0..200 {
+            for i in 1..9 {
+                let (r, c) = cell(i);
+                full[r][c] = rng.chance(0.5);
+            }
+
+            let bits = (0..10).map(|i| {
+                let (r, c) = cell(i);
+                full[r][c]
+            });
+            let forward = bits.clone().fold(0u16, |acc, on| acc << 1 | on as u16);
+            let backward = bits.rev().fold(0u16, |acc, on| acc << 1 | on as u16);
+
+            if forward != backward && used.insert(forward.min(backward)) {
+                placed = true;
+                break;
+            }
+        }
+
+        if !placed {
+            return None;
+        }
+    }
+
+    Some(full)
+}
+
+const ALLERGENS: [&str; 8] = [
+    "dairy",
+    "eggs",
+    "fish",
+    "nuts",
+    "peanuts",
+    "sesame",
+    "shellfish",
+    "soy",
+];
+
+/// Foods whose allergens can each be pinned on one ingredient by
+/// elimination. `size` is the number of foods.
+pub fn food(rng: &mut Rng, size: usize) -> Vec<String> {
+    let count = size.max(8);
+    let allergens = rng.range(2, ALLERGENS.len());
+
+    let mut ingredients = BTreeSet::new();
+    while ingredients.len() < count + allergens + 10 {
+        let len = rng.range(3, 7);
+        ingredients.insert(letters(rng, b"abcdefghijklmnopqrstuvwxyz", len));
+    }
+    let mut ingredients = ingredients.into_iter().collect::<Vec<String>>();
+    rng.shuffle(&mut ingredients);
+    let (dangerous, safe) = ingredients.split_at(allergens);
+
+    loop {
+        let foods = (0..count)
+            .map(|_| {
+                let mut listed = (0..allergens)
+                    .filter(|_| rng.chance(0.35))
+                    .collect::<BTreeSet<usize>>();
+                if listed.is_empty() {
+                    listed.insert(rng.below(allergens));
+                }
+
+                let mut contents = (0..allergens)
+                    .filter(|allergen| listed.contains(allergen) || rng.chance(0.2))
+                    .map(|allergen| dangerous[allergen].clone())
+                    .collect::<Vec<String>>();
+                contents.extend(safe.iter().filter(|_| rng.chance(0.4)).cloned());
+                rng.shuffle(&mut contents);
+
+                (contents, listed)
+            })
+            .collect::<Vec<(Vec<String>, BTreeSet<usize>)>>();
+
+        if food_is_solvable(&foods, allergens) {
+            return foods
+                .into_iter()
+                .map(|(contents, listed)| {
+                    let listed = listed
+                        .iter()
+                        .map(|allergen| ALLERGENS[*allergen])
+                        .collect::<Vec<&str>>();
+                    format!("{} (contains {})", contents.join(" "), listed.join(", "))
+                })
+                .collect();
+        }
+    }
+}
+
+fn food_is_solvable(foods: &[(Vec<String>, BTreeSet<usize>)], allergens: usize) -> bool {
+    let mut candidates = (0..allergens)
+        .map(|allergen| {
+            let mut lists = foods
+                .iter()
+                .filter(|(_, listed)| listed.contains(&allergen))
+                .map(|(contents, _)| contents.iter().collect::<BTreeSet<&String>>());
+            let first = lists.next().unwrap_or_default();
+            lists.fold(first, |acc, list| {
+                acc.intersection(&list).cloned().collect()
+            })
+        })
+        .collect::<Vec<BTreeSet<&String>>>();
+
+    let mut solved = 0;
+    while solved < allergens {
+        let found = candidates
+            .iter()
+            .position(|candidates| candidates.len() == 1);
+        match found {
+            Some(index) => {
+                let ingredient = candidates[index].iter().next().cloned();
+                if let Some(ingredient) = ingredient {
+                    candidates.iter_mut().for_each(|candidates| {
+                        candidates.remove(ingredient);
+                    });
+                }
+                solved += 1;
+            }
+            None => return false,
+        }
+    }
+
+    true
+}
+
+/// Two decks of `size` cards each, dealt from a shuffled deck.
+pub fn combat(rng: &mut Rng, size: usize) -> Vec<String> {
+    let per_player = size.clamp(2, 25);
+    let mut cards = (1..=2 * per_player).collect::<Vec<usize>>();
+    rng.shuffle(&mut cards);
+
+    let mut lines = vec!["Player 1:".to_string()];
+    lines.extend(cards[..per_player].iter().map(|c| c.to_string()));
+    lines.push(String::new());
+    lines.push("Player 2:".to_string());
+    lines.extend(cards[per_player..].iter().map(|c| c.to_string()));
+    lines
+}
+
+/// The nine cup labels in a random order. The puzzle always has nine cups,
+/// so `size` is ignored.
+pub fn cups(rng: &mut Rng, _size: usize) -> Vec<String> {
+    let mut labels = (1..=9).collect::<Vec<usize>>();
+    rng.shuffle(&mut labels);
+    vec![labels.iter().map(|l| l.to_string()).collect()]
+}
+
+pub fn lobby(rng: &mut Rng, size: usize) -> Vec<String> {
+    (0..size.max(1))
+        .map(|_| {
+            (0..rng.range(5, 25))
+                .map(|_| *rng.choose(&["e", "se", "sw", "w", "nw", "ne"]))
+                .collect()
+        })
+        .collect()
+}
+
+/// Public keys from loop sizes of up to `size` thousand.
+pub fn encryption(rng: &mut Rng, size: usize) -> Vec<String> {
+    let limit = size.max(1) * 1000;
+    (0..2)
+        .map(|_| {
+            let loops = rng.range(1, limit);
+            let mut key = 1u64;
+            for _ in 0..loops {
+                key = key * 7 % 20_201_227;
+            }
+            key.to_string()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::console::Program;
+    use crate::crosscheck::cross_check;
+    use crate::solution;
+    use proptest::prelude::{prop_assert, proptest, ProptestConfig};
+
+    // days 15 and 23 always play out millions of turns, whatever the input
+    const FIXED_COST: [usize; 2] = [15, 23];
+
+    #[test]
+    fn rng() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        assert_eq!(
+            (0..10).map(|_| a.next_u64()).collect::<Vec<u64>>(),
+            (0..10).map(|_| b.next_u64()).collect::<Vec<u64>>()
+        );
+        assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+
+        let mut rng = Rng::new(7);
+        for _ in 0..1000 {
+            assert!(rng.below(3) < 3);
+            assert!((5..=9).contains(&rng.range(5, 9)));
+            assert!((-3..=3).contains(&rng.range_i64(-3, 3)));
+        }
+        assert!(!rng.chance(0.0));
+        assert!(rng.chance(1.0));
+
+        let mut items = (0..20).collect::<Vec<usize>>();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..20).collect::<Vec<usize>>());
+        items.sort_unstable();
+        assert_eq!(items, (0..20).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn generators() {
+        assert!(generator(0).is_err());
+        assert!(generator(26).is_err());
+
+        for solution in solution::solutions() {
+            let day = solution.day();
+            let input = generate(day, 3, 12).unwrap();
+            assert!(!input.lines().is_empty(), "day {}", day);
+            assert_eq!(input, generate(day, 3, 12).unwrap(), "day {}", day);
+            assert_ne!(input, generate(day, 4, 12).unwrap(), "day {}", day);
+            assert!(solution.parse_input(&input).is_ok(), "day {}", day);
+        }
+    }
+
+    #[test]
+    fn solving_generated() {
+        for solution in solution::solutions() {
+            if FIXED_COST.contains(&solution.day()) {
+                continue;
+            }
+
+            for seed in 0..2 {
+                let input = generate(solution.day(), seed, 12).unwrap();
+                let parsed = solution.parse_input(&input).unwrap();
+                for part in solution.parts() {
+                    let answer = solution.solve_part(parsed.as_ref(), *part);
+                    assert!(
+                        answer.is_ok(),
+                        "day {} {} seed {}: {:?}",
+                        solution.day(),
+                        part,
+                        seed,
+                        answer
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn solving_tiny() {
+        for solution in solution::solutions() {
+            for (size, seed) in (0..=2).flat_map(|size| (0..2).map(move |seed| (size, seed))) {
+                let input = generate(solution.day(), seed, size).unwrap();
+                let parsed = solution.parse_input(&input);
+                assert!(parsed.is_ok(), "day {} size {}", solution.day(), size);
+                if FIXED_COST.contains(&solution.day()) {
+                    continue;
+                }
+
+                for part in solution.parts() {
+                    let answer = solution.solve_part(parsed.as_ref().unwrap().as_ref(), *part);
+                    assert!(
+                        answer.is_ok(),
+                        "day {} {} size {} seed {}: {:?}",
+                        solution.day(),
+                        part,
+                        size,
+                        seed,
+                        answer
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn cross_checking_generated() {
+        for solution in solution::solutions() {
+            if solution.variants().is_empty() {
+                continue;
+            }
+
+            for seed in 0..2 {
+                let input = generate(solution.day(), seed, 30).unwrap();
+                for check in cross_check(*solution, &input, false).unwrap() {
+                    assert!(
+                        check.agrees(),
+                        "day {} seed {}: {}",
+                        solution.day(),
+                        seed,
+                        check
+                    );
+                }
+            }
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn console_programs_loop_until_corrected(seed: u64, size in 2usize..300) {
+            let program = Program::new(&console(&mut Rng::new(seed), size)).unwrap();
+            prop_assert!(!program.execute().unwrap().1);
+
+            let input = generate(8, seed, size).unwrap();
+            for check in cross_check(solution::get(8).unwrap(), &input, true).unwrap() {
+                prop_assert!(check.agrees(), "{}", check);
+            }
+        }
+
+        #[test]
+        fn expense_reports_have_one_answer(seed: u64, size in 5usize..60) {
+            let input = generate(1, seed, size).unwrap();
+            for check in cross_check(solution::get(1).unwrap(), &input, true).unwrap() {
+                prop_assert!(check.agrees(), "{}", check);
+            }
+        }
+    }
+}
@@ -0,0 +1,259 @@
+//! Puzzle inputs, read once and lent out as lines, blank-line separated
+//! blocks or header-and-body sections. A `Source` names where an input comes
+//! from: a day's `examples/NNN_<name>/input`, a path, stdin or a string.
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::error::{AocError, Result};
+use crate::util::{named_input_path, numbered_blocks};
+
+/// Input owns the text of a puzzle input and hands out borrowed views of it,
+/// so a file is read into memory once and never copied line by line.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Input {
+    text: String,
+}
+
+/// A block of input whose first line names or introduces the rest, like
+/// `Tile 2311:` in day 20 or `Player 1:` in day 22.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Section<'a> {
+    /// The line the header is on, counting the first line as line 1.
+    pub line: usize,
+    pub header: &'a str,
+    pub body: Vec<&'a str>,
+}
+
+/// Where puzzle input is read from. Solvers only ever see the loaded Input,
+/// so they behave the same whichever source was used.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Source {
+    /// The input file of a day in the examples directory, e.g. "003"
+    Day(String),
+    Path(PathBuf),
+    Stdin,
+    Text(String),
+}
+
+impl Source {
+    pub fn day(day: usize) -> Self {
+        Source::Day(format!("{:03}", day))
+    }
+
+    pub fn load(&self) -> Result<Input> {
+        match self {
+            Source::Day(day) => Input::load(day),
+            Source::Path(path) => Input::from_file(path),
+            Source::Stdin => Input::from_reader(io::stdin().lock()),
+            Source::Text(text) => Ok(Input::new(text.clone())),
+        }
+    }
+
+    /// What to call the source in error messages.
+    pub fn name(&self) -> String {
+        match self {
+            Source::Day(day) => named_input_path(day, "input")
+                .map(|path| path.display().to_string())
+                .unwrap_or_else(|_| day.clone()),
+            Source::Path(path) => path.display().to_string(),
+            Source::Stdin => "<stdin>".to_string(),
+            Source::Text(_) => "<text>".to_string(),
+        }
+    }
+}
+
+impl FromStr for Source {
+    type Err = AocError;
+
+    /// "-" means stdin, anything else is a path.
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "" => Err(AocError::InputError("Empty input path".to_string())),
+            "-" => Ok(Source::Stdin),
+            path => Ok(Source::Path(PathBuf::from(path))),
+        }
+    }
+}
+
+impl Input {
+    pub fn new(text: String) -> Self {
+        Input { text }
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        fs::read_to_string(path)
+            .map(Self::new)
+            .map_err(|e| AocError::InputError(format!("Could not read {}: {}", path.display(), e)))
+    }
+
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        Ok(Self::new(text))
+    }
+
+    /// Load examples/NNN_<name>/input for the given day, e.g. "003".
+    pub fn load(day: &str) -> Result<Self> {
+        Self::load_named(day, "input")
+    }
+
+    pub fn load_named(day: &str, name: &str) -> Result<Self> {
+        Self::from_file(named_input_path(day, name)?)
+    }
+
+    pub fn from_lines<S: AsRef<str>>(lines: &[S]) -> Self {
+        Self::new(
+            lines
+                .iter()
+                .map(|line| line.as_ref())
+                .collect::<Vec<&str>>()
+                .join("\n"),
+        )
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// The lines of the input, without line endings. This splits the same
+    /// way `BufRead::lines` does, so there is no trailing empty line.
+    pub fn lines(&self) -> Vec<&str> {
+        self.text.lines().collect()
+    }
+
+    /// Groups of lines separated by blank lines. Runs of blank lines and
+    /// blank lines at either end do not produce empty blocks.
+    pub fn blocks(&self) -> Vec<Vec<&str>> {
+        self.numbered_blocks()
+            .into_iter()
+            .map(|(_, block)| block)
+            .collect()
+    }
+
+    /// `blocks`, each with the line it starts on, so errors in a block can
+    /// be located in the input.
+    pub fn numbered_blocks(&self) -> Vec<(usize, Vec<&str>)> {
+        numbered_blocks(&self.lines())
+            .map(|(first_line, block)| (first_line, block.to_vec()))
+            .collect()
+    }
+
+    /// Blocks split into their first line and the lines that follow it.
+    pub fn sections(&self) -> Vec<Section<'_>> {
+        self.numbered_blocks()
+            .into_iter()
+            .map(|(line, block)| Section {
+                line,
+                header: block[0],
+                body: block[1..].to_vec(),
+            })
+            .collect()
+    }
+}
+
+impl From<String> for Input {
+    fn from(text: String) -> Self {
+        Input::new(text)
+    }
+}
+
+impl From<&str> for Input {
+    fn from(text: &str) -> Self {
+        Input::new(text.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "Player 1:\n9\n2\n\n\nPlayer 2:\n5\r\n8\n\n";
+
+    #[test]
+    fn lines() {
+        let input = Input::from(TEXT);
+        assert_eq!(
+            input.lines(),
+            vec!["Player 1:", "9", "2", "", "", "Player 2:", "5", "8", ""]
+        );
+        assert!(Input::default().lines().is_empty());
+    }
+
+    #[test]
+    fn blocks() {
+        let input = Input::from(TEXT);
+        assert_eq!(
+            input.blocks(),
+            vec![vec!["Player 1:", "9", "2"], vec!["Player 2:", "5", "8"]]
+        );
+        assert_eq!(input.numbered_blocks()[1].0, 6);
+    }
+
+    #[test]
+    fn sections() {
+        let input = Input::from(TEXT);
+        let sections = input.sections();
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[1].line, 6);
+        assert_eq!(sections[1].header, "Player 2:");
+        assert_eq!(sections[1].body, vec!["5", "8"]);
+    }
+
+    #[test]
+    fn loading() {
+        let input = Input::load("001").unwrap();
+        assert_eq!(input.lines(), crate::util::load_input("001").unwrap());
+        assert!(Input::load("001").unwrap().blocks().len() == 1);
+        assert!(Input::load_named("001", "missing").is_err());
+    }
+
+    #[test]
+    fn sources() {
+        assert_eq!(Source::from_str("-").unwrap(), Source::Stdin);
+        assert_eq!(
+            Source::from_str("some/input").unwrap(),
+            Source::Path(PathBuf::from("some/input"))
+        );
+        assert!(Source::from_str("").is_err());
+
+        let text = Source::Text("1721\n979\n".to_string()).load().unwrap();
+        assert_eq!(text.lines(), vec!["1721", "979"]);
+
+        let day = Source::day(1).load().unwrap();
+        assert_eq!(day, Input::load("001").unwrap());
+        assert_eq!(
+            Source::Path(PathBuf::from("examples/001_report-repair/input"))
+                .load()
+                .unwrap(),
+            day
+        );
+        assert!(Source::Path(PathBuf::from("examples/none")).load().is_err());
+
+        assert_eq!(Source::Stdin.name(), "<stdin>");
+        assert_eq!(
+            Source::day(1).name(),
+            PathBuf::from("examples/001_report-repair/input")
+                .display()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn from_reader() {
+        let input = Input::from_reader("a\nb\n".as_bytes()).unwrap();
+        assert_eq!(input.lines(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn from_lines() {
+        let lines = vec!["a".to_string(), "".to_string(), "b".to_string()];
+        let input = Input::from_lines(&lines);
+        assert_eq!(input.as_str(), "a\n\nb");
+        assert_eq!(input.lines(), lines);
+    }
+}
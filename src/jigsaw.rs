@@ -1,9 +1,16 @@
+use crate::budget::Budget;
 use crate::error::{AocError, Result};
+use crate::input::Input;
+use crate::render::Render;
+use crate::solution::Solution;
+use crate::trace;
+use crate::util::parse_blocks;
 use itertools::Itertools;
 use std::collections::HashMap;
 use std::fmt;
 
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Side {
     Top,
     Bot,
@@ -12,15 +19,19 @@ pub enum Side {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Offset(usize, usize);
 
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Dimension(usize, usize);
 
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Edge(u128);
 
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tile {
     pub id: usize,
     data: Vec<Vec<char>>,
@@ -94,10 +105,11 @@ impl Tile {
         Ok((top, bot, left, right))
     }
 
-    pub fn from_input(input: &[String]) -> Result<Self> {
+    pub fn from_input<S: AsRef<str> + fmt::Debug>(input: &[S]) -> Result<Self> {
         let mut parts = input.iter();
         if let Some(title) = parts.next() {
             if let Some(id) = title
+                .as_ref()
                 .strip_suffix(":")
                 .unwrap_or("invalidinput")
                 .split(' ')
@@ -105,7 +117,7 @@ impl Tile {
                 .next()
             {
                 let data = parts
-                    .map(|line| line.chars().collect::<Vec<char>>())
+                    .map(|line| line.as_ref().chars().collect::<Vec<char>>())
                     .collect::<Vec<Vec<char>>>();
 
                 if !data.is_empty() && data.iter().map(|row| row.len()).unique().count() == 1 {
@@ -161,6 +173,7 @@ impl Tile {
     }
 
     pub fn find_shape(&mut self, shape: &Vec<Vec<char>>) -> usize {
+        let _span = trace::span!("Tile::find_shape");
         let mut offsets: Vec<Offset> = Vec::new();
         let mut count = 0;
 
@@ -224,6 +237,7 @@ impl fmt::Display for Tile {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Rotation {
     Normal,
     Ninety,
@@ -232,12 +246,14 @@ pub enum Rotation {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Vertical {
     Normal,
     Flipped,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Horizontal {
     Normal,
     Flipped,
@@ -245,11 +261,14 @@ pub enum Horizontal {
 
 // (tile_id, variant_id)
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Variant(pub usize, pub Rotation, pub Horizontal, pub Vertical);
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Grid {
     tiles: HashMap<usize, Tile>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::util::pairs"))]
     pub variant_map: HashMap<Variant, Tile>,
     pub edge_map: HashMap<Side, HashMap<Edge, Vec<Variant>>>,
     pub arrangement: Vec<Vec<Option<Tile>>>,
@@ -274,12 +293,10 @@ impl Grid {
 
         g
     }
-    pub fn from_input(input: &[String]) -> Result<Self> {
+    pub fn from_input<S: AsRef<str> + fmt::Debug>(input: &[S]) -> Result<Self> {
+        let _span = trace::span!("Grid::from_input");
         let mut tiles = HashMap::new();
-        let _ = input
-            .split(|line| line.is_empty())
-            .map(|lines| Tile::from_input(lines))
-            .collect::<Result<Vec<Tile>>>()?
+        let _ = parse_blocks(input, Tile::from_input)?
             .into_iter()
             .map(|tile| tiles.insert(tile.id, tile))
             .collect::<Vec<Option<Tile>>>();
@@ -342,6 +359,7 @@ impl Grid {
     }
 
     pub fn make_edge_map(&mut self) {
+        let _span = trace::span!("Grid::make_edge_map");
         let sides = vec![Side::Top, Side::Left];
         for (v, t) in &self.variant_map {
             for side in sides.iter() {
@@ -354,6 +372,10 @@ impl Grid {
                     .push(v.clone());
             }
         }
+        trace::event!(
+            edges = self.edge_map.values().map(HashMap::len).sum::<usize>(),
+            "built edge map"
+        );
     }
 
     pub fn dimensions(&self) -> &Dimension {
@@ -395,6 +417,30 @@ impl Grid {
     }
 
     pub fn arrange(&mut self) -> bool {
+        self.arrange_within(&Budget::unlimited()).unwrap_or(false)
+    }
+
+    /// As `arrange`, but giving up with a `Timeout` error if the budget runs
+    /// out before the search is done.
+    pub fn arrange_within(&mut self, budget: &Budget) -> Result<bool> {
+        self.arrange_with(budget, |_| {})
+    }
+
+    /// Arrange the tiles within the budget, showing `watch` the partial
+    /// arrangement every time the search places a tile.
+    pub fn arrange_with<F: FnMut(&[Vec<Option<Tile>>])>(
+        &mut self,
+        budget: &Budget,
+        mut watch: F,
+    ) -> Result<bool> {
+        let _span = trace::span!("Grid::arrange");
+        let mut placed = 0;
+        let mut place = |arrangement: &[Vec<Option<Tile>>]| {
+            watch(arrangement);
+            let checked = budget.check_at(placed);
+            placed += 1;
+            checked
+        };
         let mut available = HashMap::new();
         let mut arrangement = vec![vec![None; self.dimensions.1]; self.dimensions.0];
 
@@ -409,6 +455,7 @@ impl Grid {
             for variant in variants {
                 if let Some(tile) = self.variant_map.get(variant) {
                     arrangement[0][0] = Some(tile.clone());
+                    place(&arrangement)?;
 
                     if self.recur(
                         0,
@@ -416,28 +463,33 @@ impl Grid {
                         Some(tile.get_edge(&Side::Right)),
                         &new_available,
                         &mut arrangement,
-                    ) {
+                        &mut place,
+                    )? {
+                        trace::event!(corner = *id, "arranged");
                         self.arrangement = arrangement;
-                        return true;
+                        return Ok(true);
                     }
 
                     arrangement[0][0] = None;
                 }
             }
         }
-        false
+        Ok(false)
     }
 
-    fn recur(
+    /// Fill the arrangement from `row` and `col` on, calling `place` after
+    /// each tile is placed and giving up on its first error.
+    fn recur<F: FnMut(&[Vec<Option<Tile>>]) -> Result<()>>(
         &self,
         row: usize,
         col: usize,
         right: Option<Edge>,
         available: &HashMap<usize, Vec<Variant>>,
         arrangement: &mut Vec<Vec<Option<Tile>>>,
-    ) -> bool {
+        place: &mut F,
+    ) -> Result<bool> {
         if row == self.dimensions.0 {
-            return true;
+            return Ok(true);
         }
 
         let bot = if row > 0 {
@@ -462,6 +514,7 @@ impl Grid {
                 new_available.remove(&variant.0);
 
                 arrangement[row][col] = Some(tile.clone());
+                place(arrangement)?;
 
                 let mut right = Some(tile.get_edge(&Side::Right));
                 let mut next_row = row;
@@ -473,15 +526,22 @@ impl Grid {
                     right = None;
                 }
 
-                if self.recur(next_row, next_col, right, &new_available, arrangement) {
-                    return true;
+                if self.recur(
+                    next_row,
+                    next_col,
+                    right,
+                    &new_available,
+                    arrangement,
+                    place,
+                )? {
+                    return Ok(true);
                 }
 
                 arrangement[row][col] = None;
             }
         }
 
-        false
+        Ok(false)
     }
 
     fn get_variants(
@@ -513,6 +573,7 @@ impl Grid {
     }
 
     pub fn make_complete_tile(&self) -> Option<Tile> {
+        let _span = trace::span!("Grid::make_complete_tile");
         if let Some(ref first_tile) = self.arrangement[0][0] {
             let rows_per_tile = first_tile.dimensions.0 - 2;
             let cols_per_tile = first_tile.dimensions.1 - 2;
@@ -562,6 +623,122 @@ impl fmt::Display for Grid {
     }
 }
 
+/// The tile contents without the id, with any sea monsters found by
+/// `find_shape` drawn as `O`.
+impl Render for Tile {
+    fn render(&self) -> String {
+        self.data
+            .iter()
+            .map(|row| row.iter().collect::<String>())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+/// A possibly partial arrangement of tiles, as passed to the watcher of
+/// `Grid::arrange_with`.
+pub struct Arrangement<'a>(pub &'a [Vec<Option<Tile>>]);
+
+/// The tiles laid out with a gap between them, and the places still to fill
+/// left blank.
+impl<'a> Render for Arrangement<'a> {
+    fn render(&self) -> String {
+        let size = self
+            .0
+            .iter()
+            .flatten()
+            .flatten()
+            .next()
+            .map(|tile| tile.dimensions.clone())
+            .unwrap_or(Dimension(10, 10));
+
+        self.0
+            .iter()
+            .map(|row| {
+                (0..size.0)
+                    .map(|line| {
+                        row.iter()
+                            .map(|tile| match tile {
+                                Some(tile) => tile.data[line].iter().collect::<String>(),
+                                None => " ".repeat(size.1),
+                            })
+                            .collect::<Vec<String>>()
+                            .join(" ")
+                    })
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            })
+            .collect::<Vec<String>>()
+            .join("\n\n")
+    }
+}
+
+impl Render for Grid {
+    fn render(&self) -> String {
+        Arrangement(&self.arrangement).render()
+    }
+}
+
+pub const SEA_MONSTER: [&str; 3] = [
+    "..................#.",
+    "#....##....##....###",
+    ".#..#..#..#..#..#...",
+];
+
+pub struct JurassicJigsaw;
+
+impl Solution for JurassicJigsaw {
+    const DAY: usize = 20;
+    const NAME: &'static str = "Jurassic Jigsaw";
+
+    type Parsed = Grid;
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed> {
+        Grid::from_input(&input.lines())
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String> {
+        let mut grid = parsed.clone();
+
+        if !grid.arrange_within(&Budget::current())? {
+            return Err(AocError::ArrangementNotFound);
+        }
+
+        Ok(grid.get_corner_product()?.to_string())
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String> {
+        let mut grid = parsed.clone();
+
+        if !grid.arrange_within(&Budget::current())? {
+            return Err(AocError::ArrangementNotFound);
+        }
+
+        let shape = SEA_MONSTER
+            .iter()
+            .map(|line| line.chars().collect::<Vec<char>>())
+            .collect::<Vec<Vec<char>>>();
+
+        let combined = grid
+            .make_complete_tile()
+            .ok_or(AocError::ArrangementNotFound)?;
+
+        // leverage the variants calculated when constructing a new Grid by
+        // making a grid of just the one combined tile
+        let mut tiles: HashMap<usize, Tile> = HashMap::new();
+        tiles.insert(combined.id, combined);
+        let mut combined_grid = Grid::new(&tiles);
+
+        for tile in combined_grid.variant_map.values_mut() {
+            if tile.find_shape(&shape) > 0 {
+                return Ok(tile.count_char('#').to_string());
+            }
+        }
+
+        Err(AocError::ArrangementNotFound)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -926,6 +1103,28 @@ mod tests {
             let mut g = Grid::from_input(&input).unwrap();
             g.arrange();
             assert_eq!(g.get_corner_product().unwrap(), 20899048083289);
+
+            let mut frames = Vec::new();
+            let mut g = Grid::from_input(&input).unwrap();
+            assert!(g
+                .arrange_with(&Budget::unlimited(), |arrangement| frames
+                    .push(Arrangement(arrangement).render()))
+                .unwrap());
+            assert!(frames.len() >= 9);
+            assert_eq!(frames[0].lines().next().unwrap().len(), 32);
+            assert!(frames[0].lines().next().unwrap().ends_with(&" ".repeat(22)));
+
+            let rendered = g.render();
+            assert_eq!(rendered.lines().count(), 32);
+            assert_eq!(rendered, frames.last().unwrap().as_str());
+
+            let budget = Budget::unlimited();
+            budget.cancel();
+            let mut g = Grid::from_input(&input).unwrap();
+            assert!(matches!(
+                g.arrange_within(&budget),
+                Err(AocError::Timeout(_))
+            ));
         }
     }
 }
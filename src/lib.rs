@@ -1,27 +1,50 @@
 pub mod adapter;
+pub mod assembler;
 pub mod boarding;
+pub mod budget;
 pub mod calculator;
+pub mod cfg;
 pub mod combat;
 pub mod console;
+pub mod crosscheck;
 pub mod cups;
 pub mod customs;
+pub mod debugger;
 pub mod docking;
+pub mod dump;
 pub mod encryption;
 pub mod error;
 pub mod expense;
+#[cfg(feature = "fetch")]
+pub mod fetch;
 pub mod food;
 pub mod game;
+pub mod gen;
+pub mod input;
 pub mod jigsaw;
 pub mod lobby;
 pub mod luggage;
+pub mod memory;
 pub mod message;
 pub mod navigation;
 pub mod passport;
 pub mod password;
 pub mod power;
+pub mod progress;
+pub mod render;
+pub mod repair;
+pub mod runner;
 pub mod seating;
+#[cfg(feature = "server")]
+pub mod server;
 pub mod shuttle;
+pub mod solution;
+pub mod span;
 pub mod ticket;
+pub mod timing;
 pub mod toboggan;
+pub mod trace;
 pub mod util;
+pub mod verify;
+pub mod vm;
 pub mod xmas;
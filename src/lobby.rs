@@ -1,19 +1,30 @@
 use crate::error::{AocError, Result};
+use crate::input::Input;
+use crate::progress::Progress;
+use crate::render::Render;
+use crate::solution::Solution;
+use crate::trace;
+use crate::util::{collect_lines, parse_lines, Collected};
 use std::collections::HashMap;
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Address(Vec<Dir>);
 
 impl Address {
-    pub fn from_input(input: &[String]) -> Result<Vec<Address>> {
-        input
-            .iter()
-            .map(|line| Dir::parse_instructions(line))
-            .collect::<Result<Vec<Address>>>()
+    pub fn from_input<S: AsRef<str>>(input: &[S]) -> Result<Vec<Address>> {
+        let _span = trace::span!("Address::from_input");
+        parse_lines(input, Dir::parse_instructions)
+    }
+
+    /// Every address that parsed, and the errors of those that did not.
+    pub fn from_input_lenient<S: AsRef<str>>(input: &[S]) -> Collected<Vec<Address>> {
+        collect_lines(input, Dir::parse_instructions)
     }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Dir {
     East,
     SouthEast,
@@ -65,12 +76,14 @@ impl Dir {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Face {
     White,
     Black,
 }
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Coordinate(i64, i64);
 
 impl Coordinate {
@@ -87,6 +100,7 @@ impl Coordinate {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Tile {
     pub coordinate: Coordinate,
     pub face: Face,
@@ -109,7 +123,9 @@ impl Tile {
 }
 
 #[derive(Debug, Default, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Lobby {
+    #[cfg_attr(feature = "serde", serde(with = "crate::util::pairs"))]
     tiles: HashMap<Coordinate, Tile>,
 }
 
@@ -167,8 +183,16 @@ impl Lobby {
     }
 
     pub fn simulate(&mut self, generations: usize) {
-        for _ in 0..generations {
+        self.simulate_with(generations, |_, _| {});
+    }
+
+    /// Flip tiles for `generations` days, showing `observe` the lobby after
+    /// each one.
+    pub fn simulate_with<F: FnMut(Progress, &Self)>(&mut self, generations: usize, mut observe: F) {
+        let _span = trace::span!("Lobby::simulate_with");
+        for step in 1..=generations {
             self.generation();
+            observe(Progress::new(step, generations), self);
         }
     }
 
@@ -195,6 +219,71 @@ impl Lobby {
     }
 }
 
+/// Black tiles as `#` and white as `.`, north up. Every other column of a
+/// row is blank, which staggers the rows into the hexagonal layout.
+impl Render for Lobby {
+    fn render(&self) -> String {
+        let black = self
+            .tiles
+            .values()
+            .filter(|t| t.face == Face::Black)
+            .map(|t| t.coordinate)
+            .collect::<Vec<Coordinate>>();
+
+        let min_x = black.iter().map(|c| c.0).min().unwrap_or(0);
+        let max_x = black.iter().map(|c| c.0).max().unwrap_or(0);
+        let min_y = black.iter().map(|c| c.1).min().unwrap_or(0);
+        let max_y = black.iter().map(|c| c.1).max().unwrap_or(0);
+
+        (min_y..=max_y)
+            .rev()
+            .map(|y| {
+                (min_x..=max_x)
+                    .map(|x| {
+                        if (x + y) % 2 != 0 {
+                            ' '
+                        } else if black.contains(&Coordinate(x, y)) {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+pub struct LobbyLayout;
+
+impl Solution for LobbyLayout {
+    const DAY: usize = 24;
+    const NAME: &'static str = "Lobby Layout";
+
+    type Parsed = Lobby;
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed> {
+        let mut lobby = Lobby::new();
+        Address::from_input(&input.lines())?
+            .iter()
+            .for_each(|address| lobby.flip(address));
+        Ok(lobby)
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String> {
+        Ok(parsed.count_tiles(&Face::Black).to_string())
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String> {
+        let mut lobby = parsed.clone();
+        lobby.simulate(100);
+        Ok(lobby.count_tiles(&Face::Black).to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -295,6 +384,17 @@ mod tests {
             assert_eq!(lobby.count_tiles(&Face::Black), 10);
         }
 
+        #[test]
+        fn render() {
+            let mut lobby = Lobby::new();
+            assert_eq!(lobby.render(), ".");
+
+            for address in Address::from_input(&["e", "w", "nee"]).unwrap() {
+                lobby.flip(&address);
+            }
+            assert_eq!(lobby.render(), " . . #\n# . #");
+        }
+
         #[test]
         fn generation() {
             let input = input();
@@ -335,5 +435,32 @@ mod tests {
             lobby.simulate(100);
             assert_eq!(lobby.count_tiles(&Face::Black), 2208);
         }
+
+        #[test]
+        fn simulate_with() {
+            let input = input();
+            let addresses = Address::from_input(&input).unwrap();
+            let mut lobby = Lobby::new();
+            addresses.iter().for_each(|address| lobby.flip(address));
+
+            let mut seen = Vec::new();
+            lobby.simulate_with(10, |progress, lobby| {
+                assert_eq!(progress.total, 10);
+                seen.push(lobby.count_tiles(&Face::Black));
+            });
+            assert_eq!(seen, vec![15, 12, 25, 14, 23, 28, 41, 37, 49, 37]);
+        }
+
+        #[cfg(feature = "serde")]
+        #[test]
+        fn serde() {
+            let input = input();
+            let addresses = Address::from_input(&input).unwrap();
+            let mut lobby = Lobby::new();
+            addresses.iter().for_each(|address| lobby.flip(address));
+
+            let json = serde_json::to_string(&lobby).unwrap();
+            assert_eq!(serde_json::from_str::<Lobby>(&json).unwrap(), lobby);
+        }
     }
 }
@@ -1,19 +1,26 @@
 use crate::error::{AocError, Result};
+use crate::input::Input;
+use crate::solution::{Part, Solution, Variant};
+use crate::trace;
+use crate::util::{collect_lines, parse_lines, Collected};
 use rayon::prelude::*;
 use std::collections::HashMap;
 
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rule {
     pub bag_name: String,
     contents: HashMap<String, usize>,
 }
 
 impl Rule {
-    pub fn from_input(input: &[String]) -> Result<Vec<Self>> {
-        input
-            .iter()
-            .map(|spec| Rule::new(spec))
-            .collect::<Result<Vec<Self>>>()
+    pub fn from_input<S: AsRef<str>>(input: &[S]) -> Result<Vec<Self>> {
+        parse_lines(input, Rule::new)
+    }
+
+    /// Every rule that parsed, and the errors of those that did not.
+    pub fn from_input_lenient<S: AsRef<str>>(input: &[S]) -> Collected<Vec<Self>> {
+        collect_lines(input, Rule::new)
     }
 
     pub fn new(spec: &str) -> Result<Self> {
@@ -69,18 +76,30 @@ impl Rule {
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ruleset {
     rules: HashMap<String, Rule>,
 }
 
 impl Ruleset {
-    pub fn from_input(input: &[String]) -> Result<Self> {
-        let rules: HashMap<String, Rule> = Rule::from_input(input)?
+    pub fn from_input<S: AsRef<str>>(input: &[S]) -> Result<Self> {
+        let _span = trace::span!("Ruleset::from_input");
+        Ok(Self::from_rules(Rule::from_input(input)?))
+    }
+
+    /// A ruleset of every rule that parsed, and the errors of those that did
+    /// not.
+    pub fn from_input_lenient<S: AsRef<str>>(input: &[S]) -> Collected<Self> {
+        Rule::from_input_lenient(input).map(Self::from_rules)
+    }
+
+    fn from_rules(rules: Vec<Rule>) -> Self {
+        let rules: HashMap<String, Rule> = rules
             .into_iter()
-            .map(|rule| (rule.bag_name.clone(), rule.clone()))
+            .map(|rule| (rule.bag_name.clone(), rule))
             .collect();
 
-        Ok(Ruleset { rules })
+        Ruleset { rules }
     }
 
     pub fn target_in_bag(
@@ -179,6 +198,7 @@ impl Ruleset {
     }
 
     pub fn get_num_possible_bags_memoized(&self, target: &str) -> Result<usize> {
+        let _span = trace::span!("Ruleset::get_num_possible_bags_memoized");
         if !self.rules.contains_key(target) {
             return Err(AocError::UnknownBag(target.to_string()));
         }
@@ -249,6 +269,7 @@ impl Ruleset {
     }
 
     pub fn count_bags_memoized(&self, target: &str) -> Result<usize> {
+        let _span = trace::span!("Ruleset::count_bags_memoized");
         if let Some(rule) = self.rules.get(target) {
             let mut seen = HashMap::with_capacity(self.rules.len());
             Ok(self.count_r_memoized(rule, &mut seen))
@@ -258,6 +279,54 @@ impl Ruleset {
     }
 }
 
+pub struct HandyHaversacks;
+
+impl Solution for HandyHaversacks {
+    const DAY: usize = 7;
+    const NAME: &'static str = "Handy Haversacks";
+
+    type Parsed = Ruleset;
+
+    const VARIANTS: &'static [Variant<Self::Parsed>] = &[
+        Variant {
+            part: Part::One,
+            name: "get_num_possible_bags",
+            slow: false,
+            solve: |rules| Ok(rules.get_num_possible_bags("shiny gold")?.to_string()),
+        },
+        Variant {
+            part: Part::One,
+            name: "get_num_possible_bags_parallel",
+            slow: false,
+            solve: |rules| {
+                Ok(rules
+                    .get_num_possible_bags_parallel("shiny gold")?
+                    .to_string())
+            },
+        },
+        Variant {
+            part: Part::Two,
+            name: "count_bags",
+            slow: false,
+            solve: |rules| Ok(rules.count_bags("shiny gold")?.to_string()),
+        },
+    ];
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed> {
+        Ruleset::from_input(&input.lines())
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String> {
+        Ok(parsed
+            .get_num_possible_bags_memoized("shiny gold")?
+            .to_string())
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String> {
+        Ok(parsed.count_bags_memoized("shiny gold")?.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -290,6 +359,15 @@ mod tests {
             assert_eq!(res.len(), 9);
         }
 
+        #[test]
+        fn from_input_lenient() {
+            let mut lines = input();
+            lines[2] = "bright white bags hold 1 shiny gold bag.".to_string();
+            let collected = Rule::from_input_lenient(&lines);
+            assert_eq!(collected.parsed.len(), 8);
+            assert_eq!(collected.error_lines(), vec![3]);
+        }
+
         #[test]
         fn quantity_parsing() {
             let res = Rule::parse_quantity("a bright white bag");
@@ -0,0 +1,327 @@
+//! Heap use without heaptrack. With the `count-alloc` feature the memory
+//! example reports the peak heap, what is left allocated and the number of
+//! allocations while parsing and solving each part, and the examples of days
+//! 14, 15 and 23 report each of their own parts:
+//!
+//! ```text
+//! cargo run --release --features count-alloc --example memory 23
+//! cargo run --release --features count-alloc --example 023_crab-cups
+//! ```
+//!
+//! The counters are global, so measure one thing at a time.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt::{self, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::error::Result;
+use crate::input::{Input, Source};
+use crate::solution::{Part, Solver};
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// The system allocator, keeping count of the bytes in use, the most ever in
+/// use and the number of allocations. Building with the `count-alloc`
+/// feature installs it as the global allocator.
+pub struct Counting;
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+            grow(new_size);
+        }
+        new_ptr
+    }
+}
+
+fn grow(size: usize) {
+    let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK.fetch_max(current, Ordering::Relaxed);
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+}
+
+#[cfg(feature = "count-alloc")]
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// Whether the counting allocator is installed.
+pub fn enabled() -> bool {
+    cfg!(feature = "count-alloc")
+}
+
+/// The heap used while running a piece of code, over what was already in
+/// use when it started.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct Usage {
+    /// The most bytes in use at any one time.
+    pub peak: usize,
+    /// Bytes still in use at the end, such as the returned value.
+    pub retained: usize,
+    /// Calls to allocate or reallocate.
+    pub allocations: usize,
+}
+
+impl fmt::Display for Usage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} peak, {} retained, {} allocations",
+            bytes(self.peak),
+            bytes(self.retained),
+            self.allocations
+        )
+    }
+}
+
+/// Run `f` and measure its heap usage, or `None` for the usage if the
+/// counting allocator is not installed. The counters are global, so
+/// anything allocating on other threads at the same time is counted too.
+pub fn measure<T, F: FnOnce() -> T>(f: F) -> (T, Option<Usage>) {
+    let base = CURRENT.load(Ordering::Relaxed);
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    PEAK.store(base, Ordering::Relaxed);
+
+    let value = f();
+
+    let usage = Usage {
+        peak: PEAK.load(Ordering::Relaxed).saturating_sub(base),
+        retained: CURRENT.load(Ordering::Relaxed).saturating_sub(base),
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+    };
+
+    (value, if enabled() { Some(usage) } else { None })
+}
+
+/// Print the heap a step of an example used, if it was measured.
+pub fn report(step: &str, usage: Option<Usage>) {
+    if let Some(usage) = usage {
+        println!("{} heap: {}", step, usage);
+    }
+}
+
+/// The heap usage of parsing a day's input and of each of its parts. A part
+/// does not count the parsed input it starts from.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Profile {
+    pub day: usize,
+    pub name: &'static str,
+    pub parse: Usage,
+    pub parts: Vec<(Part, Usage)>,
+}
+
+/// Measure parsing and every part of a day. Without the counting allocator
+/// every usage is zero.
+pub fn profile(solution: &dyn Solver, input: &Input) -> Result<Profile> {
+    let (parsed, parse) = measure(|| solution.parse_input(input));
+    let parsed = parsed?;
+
+    let mut parts = Vec::new();
+    for part in solution.parts() {
+        let (answer, usage) = measure(|| solution.solve_part(parsed.as_ref(), *part));
+        answer?;
+        parts.push((*part, usage.unwrap_or_default()));
+    }
+
+    Ok(Profile {
+        day: solution.day(),
+        name: solution.name(),
+        parse: parse.unwrap_or_default(),
+        parts,
+    })
+}
+
+/// Profiles for a number of days, rendered as a table.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Report {
+    pub days: Vec<Profile>,
+}
+
+impl Report {
+    /// Profile each solution against its own input in the examples
+    /// directory, one at a time so the counts are not mixed up.
+    pub fn run(solutions: &[&dyn Solver]) -> Result<Self> {
+        let mut days = Vec::new();
+        for solution in solutions {
+            let input = Source::day(solution.day()).load()?;
+            days.push(profile(*solution, &input)?);
+        }
+
+        Ok(Report { days })
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .days
+            .iter()
+            .map(|profile| profile.name.len())
+            .max()
+            .unwrap_or(0)
+            .max("name".len());
+
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "day  {:<width$}  {:<6}  {:>12}  {:>12}  {:>12}",
+            "name",
+            "step",
+            "peak",
+            "retained",
+            "allocations",
+            width = width
+        );
+
+        for profile in &self.days {
+            let steps = Some(("parse".to_string(), profile.parse))
+                .into_iter()
+                .chain(
+                    profile
+                        .parts
+                        .iter()
+                        .map(|(part, usage)| (format!("part {}", part.number()), *usage)),
+                );
+
+            for (step, usage) in steps {
+                let _ = writeln!(
+                    out,
+                    "{:>3}  {:<width$}  {:<6}  {:>12}  {:>12}  {:>12}",
+                    profile.day,
+                    profile.name,
+                    step,
+                    bytes(usage.peak),
+                    bytes(usage.retained),
+                    usage.allocations,
+                    width = width
+                );
+            }
+        }
+
+        write!(f, "{}", out)
+    }
+}
+
+fn bytes(count: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut value = count as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} B", count)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution;
+
+    #[test]
+    fn formatting_bytes() {
+        assert_eq!(bytes(0), "0 B");
+        assert_eq!(bytes(1023), "1023 B");
+        assert_eq!(bytes(1536), "1.5 KiB");
+        assert_eq!(bytes(8 * 1024 * 1024), "8.0 MiB");
+
+        let usage = Usage {
+            peak: 2048,
+            retained: 16,
+            allocations: 3,
+        };
+        assert_eq!(
+            usage.to_string(),
+            "2.0 KiB peak, 16 B retained, 3 allocations"
+        );
+    }
+
+    #[test]
+    fn rendering() {
+        let usage = |peak, allocations| Usage {
+            peak,
+            retained: 0,
+            allocations,
+        };
+        let report = Report {
+            days: vec![Profile {
+                day: 23,
+                name: "Crab Cups",
+                parse: usage(64, 2),
+                parts: vec![(Part::One, usage(2048, 5)), (Part::Two, usage(0, 0))],
+            }],
+        };
+
+        assert_eq!(
+            report.to_string(),
+            "\
+day  name       step            peak      retained   allocations
+ 23  Crab Cups  parse           64 B           0 B             2
+ 23  Crab Cups  part 1       2.0 KiB           0 B             5
+ 23  Crab Cups  part 2           0 B           0 B             0
+"
+        );
+    }
+
+    #[cfg(not(feature = "count-alloc"))]
+    #[test]
+    fn disabled() {
+        assert!(!enabled());
+        assert_eq!(measure(|| vec![0u8; 1024]).1, None);
+    }
+
+    #[cfg(feature = "count-alloc")]
+    #[test]
+    fn measuring() {
+        let (value, usage) = measure(|| vec![0u8; 1 << 20]);
+        let usage = usage.unwrap();
+        assert!(usage.peak >= 1 << 20);
+        assert!(usage.retained >= 1 << 20);
+        assert!(usage.allocations >= 1);
+        drop(value);
+
+        let input = Input::from("5764801\n17807724\n");
+        let profile = profile(solution::get(25).unwrap(), &input).unwrap();
+        assert_eq!(profile.parts.len(), 1);
+    }
+
+    #[test]
+    fn profiling() {
+        let input = Input::from("1721\n979\n366\n299\n675\n1456\n");
+        let profile = profile(solution::get(1).unwrap(), &input).unwrap();
+        assert_eq!(profile.day, 1);
+        assert_eq!(profile.parts.len(), 2);
+
+        assert!(super::profile(solution::get(1).unwrap(), &Input::from("x")).is_err());
+    }
+}
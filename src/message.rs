@@ -1,56 +1,95 @@
 use crate::error::{AocError, Result};
+use crate::input::Input;
+use crate::solution::{Part, Solution, Variant};
+use crate::trace;
 use std::collections::{HashMap, HashSet};
 
-pub fn input_map(input: &[String]) -> Result<HashMap<usize, &str>> {
+pub fn input_map<S: AsRef<str>>(input: &[S]) -> Result<HashMap<usize, &str>> {
     let mut map = HashMap::new();
-    for line in input.iter() {
-        let mut parts = line.split(": ");
+    for (index, line) in input.iter().enumerate() {
+        let mut parts = line.as_ref().split(": ");
         if let Some(id) = parts.next() {
             if let Some(def) = parts.next() {
-                map.insert(id.parse::<usize>()?, def);
+                let id = id
+                    .parse::<usize>()
+                    .map_err(|e| AocError::from(e).locate(&input[index..=index], index + 1))?;
+                map.insert(id, def);
             }
         }
     }
     Ok(map)
 }
 
-pub fn get_matching_messages(input: &[String]) -> Result<HashSet<String>> {
-    let mut parts = input.split(|line| line.is_empty());
-    if let Some(rules) = parts.next() {
-        let map = input_map(rules)?;
-        let ruleset = Ruleset::from_input_map(&map)?;
-        if let Some(messages) = parts.next() {
-            return Ok(messages
-                .iter()
-                .cloned()
-                .filter(|line| ruleset.check(0, line))
-                .collect::<HashSet<String>>());
-        }
-        return Err(AocError::InvalidInput("Input missing messages".to_string()));
-    }
+pub fn get_matching_messages(input: &Input) -> Result<HashSet<String>> {
+    let _span = trace::span!("get_matching_messages");
+    Ok(Messages::from_input(input)?.matching())
+}
 
-    Err(AocError::InvalidInput("Input missing rules".to_string()))
+pub fn get_matching_messages_b(input: &Input) -> Result<HashSet<String>> {
+    let _span = trace::span!("get_matching_messages_b");
+    Ok(Messages::from_input(input)?.matching_b())
 }
 
-pub fn get_matching_messages_b(input: &[String]) -> Result<HashSet<String>> {
-    let mut parts = input.split(|line| line.is_empty());
-    if let Some(rules) = parts.next() {
-        let map = input_map(rules)?;
-        let ruleset = Ruleset::from_input_map(&map)?;
-        if let Some(messages) = parts.next() {
-            return Ok(messages
-                .iter()
-                .cloned()
-                .filter(|line| ruleset.check_b(0, line))
-                .collect::<HashSet<String>>());
-        }
-        return Err(AocError::InvalidInput("Input missing messages".to_string()));
+/// The rules and the received messages, which come in two blocks.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Messages {
+    ruleset: Ruleset,
+    messages: Vec<String>,
+}
+
+impl Messages {
+    pub fn from_input(input: &Input) -> Result<Self> {
+        let mut blocks = input.numbered_blocks().into_iter();
+
+        let (first_line, rules) = blocks
+            .next()
+            .ok_or_else(|| AocError::InvalidInput("Input missing rules".to_string()))?;
+        let ruleset = input_map(&rules)
+            .and_then(|map| Ruleset::from_input_map(&map))
+            .map_err(|e| e.locate(&rules, first_line))?;
+
+        let (_, messages) = blocks
+            .next()
+            .ok_or_else(|| AocError::InvalidInput("Input missing messages".to_string()))?;
+
+        Ok(Messages {
+            ruleset,
+            messages: messages.iter().map(|line| line.to_string()).collect(),
+        })
+    }
+
+    /// Replace rules 8 and 11 with the versions from part two, which loop.
+    pub fn with_loops(mut self) -> Result<Self> {
+        self.ruleset = self
+            .ruleset
+            .with_rule(8, "42 | 42 8")?
+            .with_rule(11, "42 31 | 42 11 31")?;
+        Ok(self)
+    }
+
+    /// The messages matching rule 0, found by backtracking, which gives up
+    /// too early on rules that loop.
+    pub fn matching(&self) -> HashSet<String> {
+        self.filter(|line| self.ruleset.check(0, line))
+    }
+
+    /// The messages matching rule 0, following every way of matching at once.
+    pub fn matching_b(&self) -> HashSet<String> {
+        self.filter(|line| self.ruleset.check_b(0, line))
     }
 
-    Err(AocError::InvalidInput("Input missing rules".to_string()))
+    fn filter<F: Fn(&str) -> bool>(&self, matches: F) -> HashSet<String> {
+        self.messages
+            .iter()
+            .filter(|line| matches(line))
+            .cloned()
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Token {
     Val(char),
     Rule(usize),
@@ -109,6 +148,7 @@ impl Token {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Group(Vec<Token>);
 
 impl Group {
@@ -150,6 +190,7 @@ impl Group {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rule {
     id: usize,
     groups: Vec<Group>,
@@ -213,12 +254,14 @@ impl Rule {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ruleset {
     rules: HashMap<usize, Rule>,
 }
 
 impl Ruleset {
     pub fn from_input_map(map: &HashMap<usize, &str>) -> Result<Self> {
+        let _span = trace::span!("Ruleset::from_input_map");
         let mut rules: HashMap<usize, Rule> = HashMap::new();
 
         for (id, def) in map {
@@ -228,6 +271,12 @@ impl Ruleset {
         Ok(Ruleset { rules })
     }
 
+    /// Add a rule, or replace the one with the same id.
+    pub fn with_rule(mut self, id: usize, def: &str) -> Result<Self> {
+        self.rules.insert(id, Rule::parse(id, def)?);
+        Ok(self)
+    }
+
     pub fn get(&self, id: usize) -> Option<&Rule> {
         self.rules.get(&id)
     }
@@ -257,10 +306,38 @@ impl Ruleset {
     }
 }
 
+pub struct MonsterMessages;
+
+impl Solution for MonsterMessages {
+    const DAY: usize = 19;
+    const NAME: &'static str = "Monster Messages";
+
+    type Parsed = Messages;
+
+    const VARIANTS: &'static [Variant<Self::Parsed>] = &[Variant {
+        part: Part::One,
+        name: "get_matching_messages",
+        slow: false,
+        solve: |messages| Ok(messages.matching().len().to_string()),
+    }];
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed> {
+        Messages::from_input(input)
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String> {
+        Ok(parsed.matching_b().len().to_string())
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String> {
+        Ok(parsed.clone().with_loops()?.matching_b().len().to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::util::test_input;
+    use crate::util::{load_example, test_input};
 
     #[test]
     fn rule_matching_example_1() {
@@ -290,142 +367,30 @@ mod tests {
 
     #[test]
     fn rule_matching_example_2() {
-        let input = test_input(
-            "
-                0: 4 1 5
-                1: 2 3 | 3 2
-                2: 4 4 | 5 5
-                3: 4 5 | 5 4
-                4: \"a\"
-                5: \"b\"
-            ",
-        );
-
-        /*
-         * ababbb
-         * abbbab
-         *
-         * bababa
-         * aaabbb
-         * aaaabbb
-         */
-
-        let ruleset = Ruleset::from_input_map(&input_map(&input).unwrap()).unwrap();
+        let input = Input::from_lines(&load_example("019", 0).unwrap());
+        let messages = Messages::from_input(&input).unwrap();
+
+        let expected = vec!["ababbb".to_string(), "abbbab".to_string()]
+            .into_iter()
+            .collect::<HashSet<String>>();
+        assert_eq!(messages.matching(), expected);
+        assert_eq!(messages.matching_b(), expected);
+    }
 
-        assert!(ruleset.check(0, "ababbb"));
-        assert!(ruleset.check(0, "abbbab"));
-        assert!(!ruleset.check(0, "bababa"));
-        assert!(!ruleset.check(0, "aaabbb"));
-        assert!(!ruleset.check(0, "aaaabbb"));
+    #[test]
+    fn locating_errors() {
+        let input = Input::from("\n0: 1 2\nx: \"a\"\n\nab\n");
+        let err = Messages::from_input(&input).unwrap_err();
+        assert_eq!(err.span().unwrap().line, 3);
     }
 
     #[test]
     fn rules_with_loops() {
-        let input = test_input(
-            "
-            42: 9 14 | 10 1
-            9: 14 27 | 1 26
-            10: 23 14 | 28 1
-            1: \"a\"
-            11: 42 31
-            5: 1 14 | 15 1
-            19: 14 1 | 14 14
-            12: 24 14 | 19 1
-            16: 15 1 | 14 14
-            31: 14 17 | 1 13
-            6: 14 14 | 1 14
-            2: 1 24 | 14 4
-            0: 8 11
-            13: 14 3 | 1 12
-            15: 1 | 14
-            17: 14 2 | 1 7
-            23: 25 1 | 22 14
-            28: 16 1
-            4: 1 1
-            20: 14 14 | 1 15
-            3: 5 14 | 16 1
-            27: 1 6 | 14 18
-            14: \"b\"
-            21: 14 1 | 1 14
-            25: 1 1 | 1 14
-            22: 14 14
-            8: 42
-            26: 14 22 | 1 20
-            18: 15 15
-            7: 14 5 | 1 21
-            24: 14 1
-
-            abbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa
-            bbabbbbaabaabba
-            babbbbaabbbbbabbbbbbaabaaabaaa
-            aaabbbbbbaaaabaababaabababbabaaabbababababaaa
-            bbbbbbbaaaabbbbaaabbabaaa
-            bbbababbbbaaaaaaaabbababaaababaabab
-            ababaaaaaabaaab
-            ababaaaaabbbaba
-            baabbaaaabbaaaababbaababb
-            abbbbabbbbaaaababbbbbbaaaababb
-            aaaaabbaabaaaaababaa
-            aaaabbaaaabbaaa
-            aaaabbaabbaaaaaaabbbabbbaaabbaabaaa
-            babaaabbbaaabaababbaabababaaab
-            aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba
-        ",
-        );
-
+        let input = Input::from_lines(&load_example("019", 1).unwrap());
         assert_eq!(get_matching_messages(&input).unwrap().len(), 3);
 
-        let input = test_input(
-            "
-            42: 9 14 | 10 1
-            9: 14 27 | 1 26
-            10: 23 14 | 28 1
-            1: \"a\"
-            11: 42 31 | 42 11 31
-            5: 1 14 | 15 1
-            19: 14 1 | 14 14
-            12: 24 14 | 19 1
-            16: 15 1 | 14 14
-            31: 14 17 | 1 13
-            6: 14 14 | 1 14
-            2: 1 24 | 14 4
-            0: 8 11
-            13: 14 3 | 1 12
-            15: 1 | 14
-            17: 14 2 | 1 7
-            23: 25 1 | 22 14
-            28: 16 1
-            4: 1 1
-            20: 14 14 | 1 15
-            3: 5 14 | 16 1
-            27: 1 6 | 14 18
-            14: \"b\"
-            21: 14 1 | 1 14
-            25: 1 1 | 1 14
-            22: 14 14
-            8: 42 | 42 8
-            26: 14 22 | 1 20
-            18: 15 15
-            7: 14 5 | 1 21
-            24: 14 1
-
-            abbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa
-            bbabbbbaabaabba
-            babbbbaabbbbbabbbbbbaabaaabaaa
-            aaabbbbbbaaaabaababaabababbabaaabbababababaaa
-            bbbbbbbaaaabbbbaaabbabaaa
-            bbbababbbbaaaaaaaabbababaaababaabab
-            ababaaaaaabaaab
-            ababaaaaabbbaba
-            baabbaaaabbaaaababbaababb
-            abbbbabbbbaaaababbbbbbaaaababb
-            aaaaabbaabaaaaababaa
-            aaaabbaaaabbaaa
-            aaaabbaabbaaaaaaabbbabbbaaabbaabaaa
-            babaaabbbaaabaababbaabababaaab
-            aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba
-        ",
-        );
+        let messages = Messages::from_input(&input).unwrap();
+        assert_eq!(messages.matching_b().len(), 3);
 
         let expected = vec![
             "bbabbbbaabaabba",
@@ -445,11 +410,8 @@ mod tests {
         .map(|s| s.to_string())
         .collect::<HashSet<String>>();
 
-        let matching = get_matching_messages_b(&input).unwrap();
-        println!("{:#?}", expected.difference(&matching));
-        println!("{:#?}", matching);
-
-        assert_eq!(matching.len(), 12);
+        let matching = messages.with_loops().unwrap().matching_b();
+        assert_eq!(matching, expected);
     }
 
     #[test]
@@ -466,7 +428,7 @@ mod tests {
         ",
         );
 
-        let matching = get_matching_messages_b(&input).unwrap();
+        let matching = get_matching_messages_b(&Input::from_lines(&input)).unwrap();
         println!("{:#?}", matching);
 
         assert_eq!(matching.len(), 4);
@@ -482,7 +444,7 @@ mod tests {
         ",
         );
 
-        let matching = get_matching_messages_b(&input).unwrap();
+        let matching = get_matching_messages_b(&Input::from_lines(&input)).unwrap();
         println!("{:#?}", matching);
 
         assert_eq!(matching.len(), 3);
@@ -499,7 +461,7 @@ mod tests {
         ",
         );
 
-        let matching = get_matching_messages_b(&input).unwrap();
+        let matching = get_matching_messages_b(&Input::from_lines(&input)).unwrap();
         println!("{:#?}", matching);
 
         assert_eq!(matching.len(), 4);
@@ -1,7 +1,12 @@
 use crate::error::{AocError, Result};
+use crate::input::Input;
+use crate::solution::Solution;
+use crate::trace;
+use crate::util::{collect_lines, parse_lines, Collected};
 use std::str::FromStr;
 
 #[derive(Debug, Eq, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Dir {
     North = 0,
     South = 180,
@@ -25,6 +30,7 @@ impl Dir {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Action {
     North,
     South,
@@ -54,6 +60,7 @@ impl Action {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Instruction {
     action: Action,
     value: i64,
@@ -87,6 +94,7 @@ pub trait Moveable {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ship {
     x: i64,
     y: i64,
@@ -128,6 +136,7 @@ impl Moveable for Ship {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Waypoint(Ship);
 
 impl Waypoint {
@@ -177,6 +186,7 @@ impl Moveable for Waypoint {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WaypointShip {
     x: i64,
     y: i64,
@@ -221,25 +231,31 @@ impl Moveable for WaypointShip {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Plan {
     instructions: Vec<Instruction>,
 }
 
 impl Plan {
-    pub fn from_input(input: &[String]) -> Result<Self> {
+    pub fn from_input<S: AsRef<str>>(input: &[S]) -> Result<Self> {
+        let _span = trace::span!("Plan::from_input");
         Ok(Plan {
-            instructions: input
-                .iter()
-                .map(|line| Instruction::from_str(line))
-                .collect::<Result<Vec<Instruction>>>()?,
+            instructions: parse_lines(input, Instruction::from_str)?,
         })
     }
 
+    /// A plan of every instruction that parsed, and the errors of those that
+    /// did not.
+    pub fn from_input_lenient<S: AsRef<str>>(input: &[S]) -> Collected<Self> {
+        collect_lines(input, Instruction::from_str).map(|instructions| Plan { instructions })
+    }
+
     pub fn len(&self) -> usize {
         self.instructions.len()
     }
 
     pub fn execute<T: Moveable>(&self, ship: &mut T) -> Result<()> {
+        let _span = trace::span!("Plan::execute");
         self.instructions
             .iter()
             .map(|ins| ship.perform(ins))
@@ -248,6 +264,31 @@ impl Plan {
     }
 }
 
+pub struct RainRisk;
+
+impl Solution for RainRisk {
+    const DAY: usize = 12;
+    const NAME: &'static str = "Rain Risk";
+
+    type Parsed = Plan;
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed> {
+        Plan::from_input(&input.lines())
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String> {
+        let mut ship = Ship::new();
+        parsed.execute(&mut ship)?;
+        Ok(ship.manhattan_distance().to_string())
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String> {
+        let mut ship = WaypointShip::new();
+        parsed.execute(&mut ship)?;
+        Ok(ship.manhattan_distance().to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
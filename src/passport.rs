@@ -1,6 +1,10 @@
 use crate::error::{AocError, Result};
+use crate::input::Input;
+use crate::solution::Solution;
+use crate::trace;
 use std::collections::HashMap;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Passport {
     byr: String,
     iyr: String,
@@ -14,21 +18,24 @@ pub struct Passport {
 }
 
 impl Passport {
-    pub fn from_input(input: &[String]) -> Vec<Result<Self>> {
+    pub fn from_input(input: &Input) -> Vec<Result<Self>> {
         input
-            .split(|line| line.is_empty())
-            .map(|group| Self::new(&group.join(" ")))
+            .numbered_blocks()
+            .into_iter()
+            .map(|(first_line, block)| {
+                Self::new(&block.join(" ")).map_err(|e| e.locate(&block, first_line))
+            })
             .collect::<Vec<Result<Self>>>()
     }
 
     pub fn new(data: &str) -> Result<Self> {
         let mut fields: HashMap<&str, String> = HashMap::new();
 
-        for info in data.split(' ').map(|field| field.split(':')) {
-            let info: Vec<&str> = info.collect();
+        for field in data.split(' ') {
+            let info: Vec<&str> = field.split(':').collect();
 
             if info.len() != 2 {
-                return Err(AocError::PassportInfoError(data.to_string()));
+                return Err(AocError::PassportInfoError(field.to_string()));
             }
 
             fields.insert(info[0], info[1].to_string());
@@ -140,6 +147,7 @@ impl Passport {
     }
 
     pub fn validate(&self) -> Result<()> {
+        let _span = trace::span!("Passport::validate");
         self.validate_birth_year()?;
         self.validate_issue_year()?;
         self.validate_expiration_year()?;
@@ -152,9 +160,40 @@ impl Passport {
     }
 }
 
+pub struct PassportProcessing;
+
+impl Solution for PassportProcessing {
+    const DAY: usize = 4;
+    const NAME: &'static str = "Passport Processing";
+
+    // passports missing fields are not an error for this puzzle, they are
+    // simply not counted
+    type Parsed = Vec<Passport>;
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed> {
+        Ok(Passport::from_input(input)
+            .into_iter()
+            .filter_map(|passport| passport.ok())
+            .collect())
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String> {
+        Ok(parsed.len().to_string())
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String> {
+        Ok(parsed
+            .iter()
+            .filter(|passport| passport.validate().is_ok())
+            .count()
+            .to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::util::load_example;
 
     #[test]
     fn check_missing_fields_on_construction() {
@@ -167,43 +206,14 @@ mod tests {
 
     #[test]
     fn making_passports_from_input() {
-        let input = vec![
-            "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd byr:1937 iyr:2017 cid:147 hgt:183cm"
-                .to_string(),
-            "".to_string(),
-            "iyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884 hcl:#cfa07d byr:1929".to_string(),
-            "".to_string(),
-            "hcl:#ae17e1 iyr:2013".to_string(),
-            "eyr:2024".to_string(),
-            "ecl:brn pid:760753108 byr:1931".to_string(),
-            "hgt:179cm".to_string(),
-            "".to_string(),
-            "hcl:#cfa07d eyr:2025 pid:166559648".to_string(),
-            "iyr:2011 ecl:brn hgt:59in".to_string(),
-        ];
-
+        let input = Input::from_lines(&load_example("004", 0).unwrap());
         let res = Passport::from_input(&input);
         assert_eq!(res.len(), 4);
     }
 
     #[test]
     fn invalid_passports() {
-        let input = vec![
-            "eyr:1972 cid:100".to_string(),
-            "hcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926".to_string(),
-            "".to_string(),
-            "iyr:2019".to_string(),
-            "hcl:#602927 eyr:1967 hgt:170cm".to_string(),
-            "ecl:grn pid:012533040 byr:1946".to_string(),
-            "".to_string(),
-            "hcl:dab227 iyr:2012".to_string(),
-            "ecl:brn hgt:182cm pid:021572410 eyr:2020 byr:1992 cid:277".to_string(),
-            "".to_string(),
-            "hgt:59cm ecl:zzz".to_string(),
-            "eyr:2038 hcl:74454a iyr:2023".to_string(),
-            "pid:3556412378 byr:2007".to_string(),
-        ];
-
+        let input = Input::from_lines(&load_example("004", 1).unwrap());
         let res = Passport::from_input(&input)
             .into_iter()
             .collect::<Result<Vec<Passport>>>()
@@ -218,21 +228,7 @@ mod tests {
 
     #[test]
     fn valid_passports() {
-        let input = vec![
-            "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980".to_string(),
-            "hcl:#623a2f".to_string(),
-            "".to_string(),
-            "eyr:2029 ecl:blu cid:129 byr:1989".to_string(),
-            "iyr:2014 pid:896056539 hcl:#a97842 hgt:165cm".to_string(),
-            "".to_string(),
-            "hcl:#888785".to_string(),
-            "hgt:164cm byr:2001 iyr:2015 cid:88".to_string(),
-            "pid:545766238 ecl:hzl".to_string(),
-            "eyr:2022".to_string(),
-            "".to_string(),
-            "iyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719".to_string(),
-        ];
-
+        let input = Input::from_lines(&load_example("004", 2).unwrap());
         let res = Passport::from_input(&input)
             .into_iter()
             .collect::<Result<Vec<Passport>>>()
@@ -244,4 +240,13 @@ mod tests {
             passport.validate().expect("passport invalid");
         }
     }
+
+    #[test]
+    fn readme_example() {
+        let input = load_example("004", 0).unwrap();
+        let parsed = PassportProcessing
+            .parse(&Input::from_lines(&input))
+            .unwrap();
+        assert_eq!(PassportProcessing.part_one(&parsed).unwrap(), "2");
+    }
 }
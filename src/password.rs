@@ -1,12 +1,17 @@
 use std::collections::HashMap;
 
 use crate::error::{AocError, Result};
+use crate::input::Input;
+use crate::solution::Solution;
+use crate::trace;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PolicyType {
     Count,
     Position,
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Policy {
     min: usize,
     max: usize,
@@ -64,17 +69,25 @@ impl Policy {
     }
 }
 
-pub fn count_valid_passwords(input: &[String], policy_type: &PolicyType) -> Result<usize> {
+pub fn count_valid_passwords<S: AsRef<str>>(
+    input: &[S],
+    policy_type: &PolicyType,
+) -> Result<usize> {
+    let _span = trace::span!("count_valid_passwords");
     let mut policies: HashMap<&str, Policy> = HashMap::new();
     let mut count = 0;
-    for candidate in input {
-        let parts = candidate.split(": ").collect::<Vec<&str>>();
+    for (index, candidate) in input.iter().enumerate() {
+        let locate = |e: AocError| e.locate(&input[index..=index], index + 1);
+
+        let parts = candidate.as_ref().split(": ").collect::<Vec<&str>>();
         if parts.len() != 2 {
-            return Err(AocError::PasswordDefinitionError(candidate.to_string()));
+            return Err(locate(AocError::PasswordDefinitionError(
+                candidate.as_ref().to_string(),
+            )));
         }
 
         if !policies.contains_key(parts[0]) {
-            policies.insert(parts[0], Policy::new(parts[0])?);
+            policies.insert(parts[0], Policy::new(parts[0]).map_err(locate)?);
         }
 
         if let Some(policy) = policies.get(parts[0]) {
@@ -87,6 +100,27 @@ pub fn count_valid_passwords(input: &[String], policy_type: &PolicyType) -> Resu
     Ok(count)
 }
 
+pub struct PasswordPhilosophy;
+
+impl Solution for PasswordPhilosophy {
+    const DAY: usize = 2;
+    const NAME: &'static str = "Password Philosophy";
+
+    type Parsed = Input;
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed> {
+        Ok(input.clone())
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String> {
+        Ok(count_valid_passwords(&parsed.lines(), &PolicyType::Count)?.to_string())
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String> {
+        Ok(count_valid_passwords(&parsed.lines(), &PolicyType::Position)?.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,8 +1,15 @@
 use std::clone::Clone;
 use std::cmp::Eq;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::hash::Hash;
 
+use crate::error::Result;
+use crate::input::Input;
+use crate::progress::Progress;
+use crate::render::{cells, Render};
+use crate::solution::Solution;
+use crate::trace;
+
 pub trait Addressable {
     type Item;
 
@@ -11,6 +18,7 @@ pub trait Addressable {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Coordinate(i64, i64, i64);
 
 impl Addressable for Coordinate {
@@ -40,6 +48,7 @@ impl Addressable for Coordinate {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FourDCoordinate(i64, i64, i64, i64);
 
 impl Addressable for FourDCoordinate {
@@ -76,16 +85,26 @@ impl Addressable for FourDCoordinate {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum State {
     Inactive,
     Active,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "T: serde::Serialize",
+        deserialize = "T: serde::Deserialize<'de>"
+    ))
+)]
 pub struct Grid<T>
 where
     T: Addressable + Eq + Hash + Clone + Addressable<Item = T>,
 {
+    #[cfg_attr(feature = "serde", serde(with = "crate::util::pairs"))]
     coordinates: HashMap<T, State>,
 }
 
@@ -93,15 +112,19 @@ impl<T> Grid<T>
 where
     T: Addressable + Eq + Hash + Clone + Addressable<Item = T>,
 {
-    pub fn from_input(input: &[String]) -> Grid<T> {
+    pub fn from_input<S: AsRef<str>>(input: &[S]) -> Grid<T> {
+        let _span = trace::span!("Grid::from_input");
         let mut g = Grid::new();
 
         input.iter().enumerate().for_each(|(y, line)| {
-            line.chars().enumerate().for_each(|(x, ch)| match ch {
-                '#' => g.insert(T::from_coord(x as i64, y as i64)),
-                '.' => {}
-                _ => {}
-            })
+            line.as_ref()
+                .chars()
+                .enumerate()
+                .for_each(|(x, ch)| match ch {
+                    '#' => g.insert(T::from_coord(x as i64, y as i64)),
+                    '.' => {}
+                    _ => {}
+                })
         });
 
         g
@@ -135,12 +158,20 @@ where
     }
 
     pub fn boot(&mut self, cycles: usize) {
-        for _ in 0..cycles {
+        self.boot_with(cycles, |_, _| {});
+    }
+
+    /// Run `cycles` cycles, showing `observe` the grid after each one.
+    pub fn boot_with<F: FnMut(Progress, &Self)>(&mut self, cycles: usize, mut observe: F) {
+        let _span = trace::span!("Grid::boot_with");
+        for step in 1..=cycles {
             self.cycle();
+            observe(Progress::new(step, cycles), self);
         }
     }
 
     pub fn cycle(&mut self) {
+        let _span = trace::span!("Grid::cycle");
         let mut next_coordinates = self.coordinates.clone();
         for (candidate, count) in self.candidates() {
             match self.coordinates.get(&candidate) {
@@ -162,6 +193,93 @@ where
     }
 }
 
+/// Active cubes as `(x, y, rest)`, drawn as one x/y slice for each value of
+/// the remaining axes, like the puzzle text does.
+fn render_slices<I: Iterator<Item = (i64, i64, Vec<i64>)>>(active: I, axes: &[&str]) -> String {
+    let mut slices: BTreeMap<Vec<i64>, HashSet<(i64, i64)>> = BTreeMap::new();
+    for (x, y, rest) in active {
+        slices.entry(rest).or_default().insert((x, y));
+    }
+
+    let points = slices.values().flatten();
+    let min = (
+        points.clone().map(|p| p.0).min().unwrap_or(0),
+        points.clone().map(|p| p.1).min().unwrap_or(0),
+    );
+    let max = (
+        points.clone().map(|p| p.0).max().unwrap_or(0),
+        points.map(|p| p.1).max().unwrap_or(0),
+    );
+
+    slices
+        .iter()
+        .map(|(rest, slice)| {
+            let label = axes
+                .iter()
+                .zip(rest)
+                .map(|(axis, value)| format!("{}={}", axis, value))
+                .collect::<Vec<String>>()
+                .join(", ");
+            format!(
+                "{}\n{}",
+                label,
+                cells(min, max, |x, y| slice.contains(&(x, y)))
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+impl Render for Grid<Coordinate> {
+    fn render(&self) -> String {
+        render_slices(
+            self.coordinates
+                .iter()
+                .filter(|(_, state)| **state == State::Active)
+                .map(|(c, _)| (c.0, c.1, vec![c.2])),
+            &["z"],
+        )
+    }
+}
+
+impl Render for Grid<FourDCoordinate> {
+    fn render(&self) -> String {
+        render_slices(
+            self.coordinates
+                .iter()
+                .filter(|(_, state)| **state == State::Active)
+                .map(|(c, _)| (c.0, c.1, vec![c.2, c.3])),
+            &["z", "w"],
+        )
+    }
+}
+
+pub struct ConwayCubes;
+
+impl Solution for ConwayCubes {
+    const DAY: usize = 17;
+    const NAME: &'static str = "Conway Cubes";
+
+    // the two parts need grids of different dimensions
+    type Parsed = Input;
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed> {
+        Ok(input.clone())
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String> {
+        let mut grid: Grid<Coordinate> = Grid::from_input(&parsed.lines());
+        grid.boot(6);
+        Ok(grid.active().to_string())
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String> {
+        let mut grid: Grid<FourDCoordinate> = Grid::from_input(&parsed.lines());
+        grid.boot(6);
+        Ok(grid.active().to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -211,6 +329,28 @@ mod tests {
             assert_eq!(g.active(), 11);
         }
 
+        #[test]
+        fn render() {
+            let input = test_input(
+                "
+                .#.
+                ..#
+                ### ",
+            );
+
+            let mut g: Grid<Coordinate> = Grid::from_input(&input);
+            assert_eq!(g.render(), "z=0\n.#.\n..#\n###\n");
+
+            g.cycle();
+            assert_eq!(
+                g.render(),
+                "z=-1\n#..\n..#\n.#.\n\nz=0\n#.#\n.##\n.#.\n\nz=1\n#..\n..#\n.#.\n"
+            );
+
+            let g: Grid<FourDCoordinate> = Grid::from_input(&input);
+            assert_eq!(g.render(), "z=0, w=0\n.#.\n..#\n###\n");
+        }
+
         #[test]
         fn boot() {
             let input = test_input(
@@ -232,5 +372,21 @@ mod tests {
 
             assert_eq!(g.active(), 848);
         }
+
+        #[test]
+        fn boot_with() {
+            let input = test_input(
+                "
+                .#.
+                ..#
+                ### ",
+            );
+
+            let mut g: Grid<Coordinate> = Grid::from_input(&input);
+            let mut seen = Vec::new();
+            g.boot_with(3, |progress, g| seen.push((progress.step, g.active())));
+
+            assert_eq!(seen, vec![(1, 11), (2, 21), (3, 38)]);
+        }
     }
 }
@@ -0,0 +1,135 @@
+//! Progress of the long simulations, passed to the observer of methods such
+//! as `cups::Game::simulate_with` and `power::Grid::boot_with`. Their
+//! examples draw it as a bar on stderr:
+//!
+//! ```text
+//! AOC_PROGRESS=1 cargo run --release --example 023_crab-cups
+//! ```
+
+use std::env;
+use std::io::{self, Write};
+
+/// Set this to draw progress bars for the long simulations of the examples.
+pub const PROGRESS_VAR: &str = "AOC_PROGRESS";
+
+const WIDTH: usize = 40;
+
+/// How far a simulation has got: `step` of `total` steps are done.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Progress {
+    pub step: usize,
+    pub total: usize,
+}
+
+impl Progress {
+    pub fn new(step: usize, total: usize) -> Self {
+        Progress { step, total }
+    }
+
+    /// The whole percentage done, which is 100 for a simulation of no steps.
+    pub fn percent(&self) -> usize {
+        (self.step.min(self.total) * 100)
+            .checked_div(self.total)
+            .unwrap_or(100)
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.step >= self.total
+    }
+}
+
+/// Draws a progress bar on stderr, redrawing only when the percentage
+/// changes so it can be updated on every step. A bar that is not drawn does
+/// nothing, so examples can call `update` unconditionally.
+#[derive(Debug, Default)]
+pub struct ProgressBar {
+    label: String,
+    visible: bool,
+    drawn: Option<usize>,
+}
+
+impl ProgressBar {
+    /// A bar that draws nothing.
+    pub fn new(label: &str) -> Self {
+        ProgressBar {
+            label: label.to_string(),
+            ..ProgressBar::default()
+        }
+    }
+
+    pub fn visible(mut self) -> Self {
+        self.visible = true;
+        self
+    }
+
+    /// A bar that is drawn if `AOC_PROGRESS` is set.
+    pub fn from_env(label: &str) -> Self {
+        match env::var(PROGRESS_VAR) {
+            Ok(value) if !value.is_empty() && value != "0" => ProgressBar::new(label).visible(),
+            _ => ProgressBar::new(label),
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn update(&mut self, progress: Progress) {
+        if !self.visible || self.drawn == Some(progress.percent()) {
+            return;
+        }
+        self.drawn = Some(progress.percent());
+
+        let end = if progress.is_done() { "\n" } else { "" };
+        let stderr = io::stderr();
+        let mut out = stderr.lock();
+        // a bar that cannot be drawn is no reason to stop a simulation
+        let _ = write!(out, "\r{} {}{}", self.label, bar(progress), end);
+        let _ = out.flush();
+    }
+}
+
+/// A line such as `[##########..........]  50% (5/10)`.
+pub fn bar(progress: Progress) -> String {
+    let filled = progress.percent() * WIDTH / 100;
+    format!(
+        "[{}{}] {:3}% ({}/{})",
+        "#".repeat(filled),
+        ".".repeat(WIDTH - filled),
+        progress.percent(),
+        progress.step,
+        progress.total
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent() {
+        assert_eq!(Progress::new(0, 10).percent(), 0);
+        assert_eq!(Progress::new(3, 10).percent(), 30);
+        assert_eq!(Progress::new(9_999_999, 10_000_000).percent(), 99);
+        assert_eq!(Progress::new(0, 0).percent(), 100);
+        assert!(Progress::new(0, 0).is_done());
+        assert!(!Progress::new(1, 2).is_done());
+    }
+
+    #[test]
+    fn drawing() {
+        assert_eq!(
+            bar(Progress::new(1, 4)),
+            "[##########..............................]  25% (1/4)"
+        );
+
+        let mut hidden = ProgressBar::new("hidden");
+        hidden.update(Progress::new(1, 4));
+        assert!(!hidden.is_visible());
+        assert_eq!(hidden.drawn, None);
+
+        let mut shown = ProgressBar::new("shown").visible();
+        shown.update(Progress::new(1, 4));
+        assert_eq!(shown.drawn, Some(25));
+    }
+}
@@ -0,0 +1,227 @@
+//! Frames of text for the simulation days: seating, Conway cubes, crab
+//! cups, the lobby floor and the jigsaw search. Their examples show them
+//! when asked through the environment:
+//!
+//! ```text
+//! AOC_ANIMATE=50 cargo run --release --example 011_seating-system
+//! AOC_FRAMES=frames.txt cargo run --release --example 024_lobby-layout
+//! ```
+//!
+//! `AOC_ANIMATE` (`ANIMATE_VAR`) redraws the terminal every so many
+//! milliseconds, and `AOC_FRAMES` (`FRAMES_VAR`) writes the frames to a file
+//! where they can be diffed.
+
+use std::env;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::thread;
+use std::time::Duration;
+
+use crate::error::{AocError, Result};
+
+/// Set this to a number of milliseconds to have the examples of the
+/// simulation days draw each frame in the terminal and pause that long.
+pub const ANIMATE_VAR: &str = "AOC_ANIMATE";
+
+/// Set this to a path to have the examples of the simulation days write
+/// their frames there.
+pub const FRAMES_VAR: &str = "AOC_FRAMES";
+
+/// State that can be drawn as a frame of text.
+pub trait Render {
+    fn render(&self) -> String;
+}
+
+const CLEAR: &str = "\x1b[2J\x1b[H";
+
+/// Draws frames in the terminal, dumps them to a file, both or neither. An
+/// animator that does neither does not even render its frames, so examples
+/// can call `frame` unconditionally.
+#[derive(Debug, Default)]
+pub struct Animator {
+    delay: Option<Duration>,
+    dump: Option<BufWriter<File>>,
+    frames: usize,
+}
+
+impl Animator {
+    /// An animator that draws nothing.
+    pub fn new() -> Self {
+        Animator::default()
+    }
+
+    /// Clear the terminal and draw every frame, pausing for `delay` after
+    /// each one.
+    pub fn terminal(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    /// Append every frame to the file at `path`, under a header line with
+    /// its number and label.
+    pub fn dump(mut self, path: &str) -> Result<Self> {
+        self.dump = Some(BufWriter::new(File::create(path)?));
+        Ok(self)
+    }
+
+    /// Set up from the environment: `AOC_ANIMATE` draws in the terminal,
+    /// pausing for that many milliseconds, and `AOC_FRAMES` names a file to
+    /// dump frames to.
+    pub fn from_env() -> Result<Self> {
+        let mut animator = Animator::new();
+
+        if let Ok(delay) = env::var(ANIMATE_VAR) {
+            let millis = delay.parse::<u64>().map_err(|_| {
+                AocError::InvalidInput(format!("{} must be milliseconds: '{}'", ANIMATE_VAR, delay))
+            })?;
+            animator = animator.terminal(Duration::from_millis(millis));
+        }
+
+        if let Ok(path) = env::var(FRAMES_VAR) {
+            animator = animator.dump(&path)?;
+        }
+
+        Ok(animator)
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.delay.is_some() || self.dump.is_some()
+    }
+
+    /// The number of frames drawn so far.
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    pub fn frame<R: Render + ?Sized>(&mut self, label: &str, state: &R) -> Result<()> {
+        if !self.is_active() {
+            return Ok(());
+        }
+
+        let frame = state.render();
+        self.frames += 1;
+
+        if let Some(ref mut dump) = self.dump {
+            writeln!(dump, "--- frame {}: {} ---", self.frames, label)?;
+            writeln!(dump, "{}", frame.trim_end())?;
+        }
+
+        if let Some(delay) = self.delay {
+            let stdout = io::stdout();
+            let mut out = stdout.lock();
+            write!(out, "{}{}\n{}\n", CLEAR, label, frame.trim_end())?;
+            out.flush()?;
+            thread::sleep(delay);
+        }
+
+        Ok(())
+    }
+
+    /// Draw `state`, then step it and draw it again until `step` returns
+    /// false or `max_steps` steps have been taken. `step` returning false
+    /// means nothing changed, so the last state is not drawn twice.
+    pub fn animate<S, F>(
+        &mut self,
+        name: &str,
+        state: &mut S,
+        max_steps: usize,
+        mut step: F,
+    ) -> Result<()>
+    where
+        S: Render,
+        F: FnMut(&mut S) -> Result<bool>,
+    {
+        self.frame(&format!("{}: start", name), state)?;
+
+        for index in 1..=max_steps {
+            if !step(state)? {
+                break;
+            }
+            self.frame(&format!("{}: step {}", name, index), state)?;
+        }
+
+        self.finish()
+    }
+
+    /// Flush the dump file, if there is one.
+    pub fn finish(&mut self) -> Result<()> {
+        if let Some(ref mut dump) = self.dump {
+            dump.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Lay out cells given by coordinates in rows from `min` to `max`, with `#`
+/// where `on` holds and `.` elsewhere.
+pub fn cells<F: Fn(i64, i64) -> bool>(min: (i64, i64), max: (i64, i64), on: F) -> String {
+    let mut out = String::new();
+    for y in min.1..=max.1 {
+        for x in min.0..=max.0 {
+            out.push(if on(x, y) { '#' } else { '.' });
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    struct Counter(usize);
+
+    impl Render for Counter {
+        fn render(&self) -> String {
+            "*".repeat(self.0)
+        }
+    }
+
+    #[test]
+    fn laying_out_cells() {
+        assert_eq!(cells((0, 0), (2, 1), |x, y| x == y), "#..\n.#.\n");
+        assert_eq!(cells((-1, -1), (-1, -1), |_, _| true), "#\n");
+    }
+
+    #[test]
+    fn inactive() {
+        let mut animator = Animator::new();
+        assert!(!animator.is_active());
+        animator.frame("nothing", &Counter(3)).unwrap();
+        assert_eq!(animator.frames(), 0);
+    }
+
+    #[test]
+    fn dumping() {
+        let path = env::temp_dir().join(format!("aoc-frames-{}.txt", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        let mut animator = Animator::new().dump(path).unwrap();
+        let mut counter = Counter(1);
+        animator
+            .animate("count", &mut counter, 10, |counter| {
+                if counter.0 == 3 {
+                    return Ok(false);
+                }
+                counter.0 += 1;
+                Ok(true)
+            })
+            .unwrap();
+        assert_eq!(animator.frames(), 3);
+        assert_eq!(counter.0, 3);
+
+        assert_eq!(
+            fs::read_to_string(path).unwrap(),
+            "\
+--- frame 1: count: start ---
+*
+--- frame 2: count: step 1 ---
+**
+--- frame 3: count: step 2 ---
+***
+"
+        );
+        fs::remove_file(path).unwrap();
+    }
+}
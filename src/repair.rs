@@ -0,0 +1,387 @@
+//! Day 8 asks for one flip that makes a program terminate. A `Search` can
+//! also change `acc` arguments or delete instructions, allow several edits
+//! at once, and aim for a given accumulator or to avoid an instruction:
+//!
+//! ```
+//! use aoc::console::Program;
+//! use aoc::repair::{EditKind, Goal, Search};
+//!
+//! let program = Program::new(&[
+//!     "nop +0", "acc +1", "jmp +4", "acc +3", "jmp -3", "acc -99", "acc +1", "jmp -4", "acc +6",
+//! ])?;
+//! let fixes = Search::new(Goal::Accumulator(10))
+//!     .kinds(vec![EditKind::Flip, EditKind::AccArgument(vec![3])])
+//!     .max_edits(2)
+//!     .run(&program)?;
+//! assert_eq!(fixes.len(), 2);
+//! # Ok::<(), aoc::error::AocError>(())
+//! ```
+
+use std::fmt;
+
+use crate::budget::Budget;
+use crate::console::{Op, Operand, Program};
+use crate::error::Result;
+use crate::trace;
+use crate::vm::{InstructionSet, Vm};
+
+/// A kind of change a `Search` may make to an instruction.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum EditKind {
+    /// Swap a `jmp` for a `nop` or the other way round.
+    Flip,
+    /// Give an `acc` each of these arguments in turn.
+    AccArgument(Vec<i64>),
+    /// Take an instruction out. Jumps keep their offsets, as if the line had
+    /// been deleted from the input.
+    Delete,
+}
+
+/// A change to the instruction at an address of the original program.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Edit {
+    Flip(usize),
+    AccArgument(usize, i64),
+    Delete(usize),
+}
+
+impl Edit {
+    pub fn pc(&self) -> usize {
+        match *self {
+            Edit::Flip(pc) | Edit::AccArgument(pc, _) | Edit::Delete(pc) => pc,
+        }
+    }
+}
+
+impl fmt::Display for Edit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Edit::Flip(pc) => write!(f, "flip {}", pc),
+            Edit::AccArgument(pc, value) => write!(f, "acc {:+} at {}", value, pc),
+            Edit::Delete(pc) => write!(f, "delete {}", pc),
+        }
+    }
+}
+
+/// What a fixed program has to do. As on the handheld, a program that is
+/// about to run an instruction a second time is taken to loop forever.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Goal {
+    Terminate,
+    /// Terminate with this in the accumulator.
+    Accumulator(i64),
+    /// Terminate without running the instruction at this address of the
+    /// original program.
+    Avoid(usize),
+}
+
+/// A set of edits that meets the goal, and what they make of the program.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fix {
+    pub edits: Vec<Edit>,
+    pub program: Program,
+    /// The accumulator once the fixed program has terminated.
+    pub acc: i64,
+}
+
+impl fmt::Display for Fix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let edits = self
+            .edits
+            .iter()
+            .map(Edit::to_string)
+            .collect::<Vec<String>>();
+        write!(f, "{} (acc={})", edits.join(", "), self.acc)
+    }
+}
+
+/// How far a `Search` has got with edits of one size.
+struct State<'a> {
+    program: &'a Program,
+    size: usize,
+    edits: Vec<Edit>,
+    fixes: Vec<Fix>,
+    budget: Budget,
+    tried: usize,
+}
+
+/// Searches for the fewest edits that make a program meet a goal. By default
+/// it looks for single flips that make the program terminate, which is
+/// day 8's question.
+///
+/// Every set of up to `max_edits` edits to distinct instructions is tried,
+/// smallest first, so the cost grows with the number of instructions to the
+/// power of `max_edits`. It gives up with a `Timeout` if the current budget
+/// runs out.
+#[derive(Debug, Clone)]
+pub struct Search {
+    goal: Goal,
+    kinds: Vec<EditKind>,
+    max_edits: usize,
+    set: InstructionSet,
+}
+
+impl Search {
+    pub fn new(goal: Goal) -> Self {
+        Search {
+            goal,
+            kinds: vec![EditKind::Flip],
+            max_edits: 1,
+            set: InstructionSet::standard(),
+        }
+    }
+
+    /// Search with these kinds of edit instead of only flips.
+    pub fn kinds(mut self, kinds: Vec<EditKind>) -> Self {
+        self.kinds = kinds;
+        self
+    }
+
+    pub fn max_edits(mut self, max_edits: usize) -> Self {
+        self.max_edits = max_edits;
+        self
+    }
+
+    pub fn set(mut self, set: InstructionSet) -> Self {
+        self.set = set;
+        self
+    }
+
+    /// Every fix with the fewest edits, in order of the addresses edited.
+    /// A program that already meets the goal needs no edits, and one that
+    /// cannot be fixed within `max_edits` gets no fixes.
+    pub fn run(&self, program: &Program) -> Result<Vec<Fix>> {
+        let _span = trace::span!("Search::run");
+        let mut state = State {
+            program,
+            size: 0,
+            edits: Vec::new(),
+            fixes: Vec::new(),
+            budget: Budget::current(),
+            tried: 0,
+        };
+
+        for size in 0..=self.max_edits {
+            state.size = size;
+            self.search(&mut state, 0)?;
+            if !state.fixes.is_empty() {
+                trace::event!(
+                    size,
+                    fixes = state.fixes.len(),
+                    tried = state.tried,
+                    "found fixes"
+                );
+                return Ok(state.fixes);
+            }
+        }
+
+        Ok(Vec::new())
+    }
+
+    /// Add every edit at `from` or later to the edits so far until there are
+    /// enough, keeping those that meet the goal.
+    fn search(&self, state: &mut State, from: usize) -> Result<()> {
+        if state.edits.len() == state.size {
+            state.budget.check_at(state.tried)?;
+            state.tried += 1;
+            if let Some(fix) = self.attempt(state.program, &state.edits)? {
+                state.fixes.push(fix);
+            }
+            return Ok(());
+        }
+
+        let len = state.program.instructions().len();
+        for pc in from..len {
+            // leave room for the edits still to come
+            if len - pc < state.size - state.edits.len() {
+                break;
+            }
+            for edit in self.edits_at(state.program, pc) {
+                state.edits.push(edit);
+                self.search(state, pc + 1)?;
+                state.edits.pop();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The edits the search may make to the instruction at `pc`.
+    fn edits_at(&self, program: &Program, pc: usize) -> Vec<Edit> {
+        let instruction = &program.instructions()[pc];
+        let mut edits = Vec::new();
+
+        for kind in &self.kinds {
+            match *kind {
+                EditKind::Flip => {
+                    if instruction.op() == &Op::JMP || instruction.op() == &Op::NOP {
+                        edits.push(Edit::Flip(pc));
+                    }
+                }
+                EditKind::AccArgument(ref values) => {
+                    if instruction.op() == &Op::ACC {
+                        edits.extend(
+                            values
+                                .iter()
+                                .filter(|&&value| instruction.args() != [Operand::Value(value)])
+                                .map(|&value| Edit::AccArgument(pc, value)),
+                        );
+                    }
+                }
+                EditKind::Delete => edits.push(Edit::Delete(pc)),
+            }
+        }
+
+        edits
+    }
+
+    /// The fix made by `edits`, if it meets the goal.
+    fn attempt(&self, program: &Program, edits: &[Edit]) -> Result<Option<Fix>> {
+        // where each instruction of the original ends up, if it is kept
+        let mut addresses = Vec::new();
+        let mut instructions = Vec::new();
+        let mut pending = edits.iter().peekable();
+
+        for (pc, instruction) in program.instructions().iter().enumerate() {
+            let mut instruction = instruction.clone();
+            match pending.next_if(|edit| edit.pc() == pc) {
+                Some(Edit::Flip(_)) => {
+                    instruction.flip();
+                }
+                Some(&Edit::AccArgument(_, value)) => {
+                    instruction = instruction.with_args(vec![Operand::Value(value)]);
+                }
+                Some(Edit::Delete(_)) => {
+                    addresses.push(None);
+                    continue;
+                }
+                None => {}
+            }
+            addresses.push(Some(instructions.len()));
+            instructions.push(instruction);
+        }
+
+        let fixed = Program::from_instructions(instructions);
+        let avoid = match self.goal {
+            Goal::Avoid(pc) => addresses.get(pc).cloned().flatten(),
+            _ => None,
+        };
+
+        let mut vm = Vm::with_set(&fixed, self.set.clone())?;
+        let mut seen = vec![false; fixed.instructions().len()];
+        while !vm.is_halted() {
+            let pc = vm.pc() as usize;
+            if seen[pc] || Some(pc) == avoid {
+                return Ok(None);
+            }
+            seen[pc] = true;
+            if vm.step().is_err() {
+                return Ok(None);
+            }
+        }
+
+        match self.goal {
+            Goal::Accumulator(acc) if vm.acc() != acc => Ok(None),
+            _ => Ok(Some(Fix {
+                edits: edits.to_vec(),
+                program: fixed,
+                acc: vm.acc(),
+            })),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_input;
+
+    fn program() -> Program {
+        Program::new(&test_input(
+            "
+            nop +0
+            acc +1
+            jmp +4
+            acc +3
+            jmp -3
+            acc -99
+            acc +1
+            jmp -4
+            acc +6
+            ",
+        ))
+        .unwrap()
+    }
+
+    fn edits(fixes: &[Fix]) -> Vec<Vec<Edit>> {
+        fixes.iter().map(|fix| fix.edits.clone()).collect()
+    }
+
+    #[test]
+    fn single_flips() {
+        let fixes = Search::new(Goal::Terminate).run(&program()).unwrap();
+        assert_eq!(edits(&fixes), vec![vec![Edit::Flip(7)]]);
+        assert_eq!(fixes[0].acc, 8);
+        assert_eq!(fixes[0].to_string(), "flip 7 (acc=8)");
+        assert_eq!(
+            fixes[0].program,
+            Program::new(&[
+                "nop +0", "acc +1", "jmp +4", "acc +3", "jmp -3", "acc -99", "acc +1", "nop -4",
+                "acc +6",
+            ])
+            .unwrap()
+        );
+
+        let terminating = Program::new(&["acc +2", "jmp +1"]).unwrap();
+        let fixes = Search::new(Goal::Terminate).run(&terminating).unwrap();
+        assert_eq!(edits(&fixes), vec![vec![]]);
+        assert_eq!(fixes[0].acc, 2);
+    }
+
+    #[test]
+    fn goals() {
+        // running the -99 takes flipping 2 and 4 as well
+        let search = Search::new(Goal::Accumulator(-88)).max_edits(2);
+        assert!(search.run(&program()).unwrap().is_empty());
+        let fixes = search.max_edits(3).run(&program()).unwrap();
+        assert_eq!(
+            edits(&fixes),
+            vec![vec![Edit::Flip(2), Edit::Flip(4), Edit::Flip(7)]]
+        );
+
+        let fixes = Search::new(Goal::Avoid(7)).run(&program()).unwrap();
+        assert!(fixes.is_empty());
+
+        let search = Search::new(Goal::Avoid(7))
+            .kinds(vec![EditKind::Flip, EditKind::Delete])
+            .max_edits(2);
+        let fixes = search.run(&program()).unwrap();
+        assert!(fixes.iter().all(|fix| fix.edits.len() == 1));
+        assert!(edits(&fixes).contains(&vec![Edit::Delete(7)]));
+        assert!(!edits(&fixes).contains(&vec![Edit::Flip(7)]));
+    }
+
+    #[test]
+    fn edit_kinds() {
+        let search = Search::new(Goal::Accumulator(10))
+            .kinds(vec![EditKind::Flip, EditKind::AccArgument(vec![3])])
+            .max_edits(2);
+        let fixes = search.run(&program()).unwrap();
+        assert_eq!(
+            edits(&fixes),
+            vec![
+                vec![Edit::AccArgument(1, 3), Edit::Flip(7)],
+                vec![Edit::AccArgument(6, 3), Edit::Flip(7)]
+            ]
+        );
+        assert!(fixes.iter().all(|fix| fix.acc == 10));
+    }
+
+    #[test]
+    fn running_out_of_time() {
+        let budget = Budget::unlimited();
+        budget.cancel();
+        let search = Search::new(Goal::Accumulator(12345)).max_edits(3);
+        assert!(budget.enter(|| search.run(&program())).is_err());
+    }
+}
@@ -0,0 +1,217 @@
+//! Solves days and times each step. `run_all` can spread the days over
+//! rayon's thread pool, in which case the results still come back in day
+//! order and a day that fails or panics does not stop the others.
+
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+use rayon::prelude::*;
+
+use crate::budget::Budget;
+use crate::error::{AocError, Result};
+use crate::input::{Input, Source};
+use crate::solution::{Part, Solver};
+use crate::timing::Timing;
+use crate::trace;
+
+/// The answers of every part of a day, and how long each step took.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Solved {
+    pub answers: Vec<(Part, String)>,
+    pub timing: Timing,
+}
+
+/// Parse the input once and solve every part of the day against it.
+pub fn solve(solution: &dyn Solver, input: &Input) -> Result<Solved> {
+    solve_parts(solution, input, solution.parts())
+}
+
+/// Parse the input once and solve the given parts against it.
+pub fn solve_parts(solution: &dyn Solver, input: &Input, parts: &[Part]) -> Result<Solved> {
+    let _solve = trace::span!("solve", day = solution.day(), name = solution.name());
+
+    let start = Instant::now();
+    let parsed = {
+        let _parse = trace::span!("parse");
+        solution.parse_input(input)?
+    };
+    let parse = start.elapsed();
+
+    let mut answers = Vec::new();
+    let mut timings = Vec::new();
+    for part in parts {
+        let _part = trace::span!("part", part = part.number());
+        let start = Instant::now();
+        answers.push((*part, solution.solve_part(parsed.as_ref(), *part)?));
+        timings.push((*part, start.elapsed()));
+    }
+
+    Ok(Solved {
+        answers,
+        timing: Timing {
+            day: solution.day(),
+            name: solution.name(),
+            parse,
+            parts: timings,
+        },
+    })
+}
+
+/// The outcome of running one day. A day that panicked has an
+/// `AocError::Panicked` result rather than taking the other days down.
+#[derive(Debug)]
+pub struct DayRun {
+    pub day: usize,
+    pub name: &'static str,
+    pub result: Result<Solved>,
+}
+
+/// Run a day against the given input, catching any panic.
+pub fn run(solution: &dyn Solver, input: &Input) -> DayRun {
+    isolated(solution, || solve(solution, input))
+}
+
+/// Run some parts of a day against the given input, catching any panic.
+pub fn run_parts(solution: &dyn Solver, input: &Input, parts: &[Part]) -> DayRun {
+    isolated(solution, || solve_parts(solution, input, parts))
+}
+
+/// Run every solution against its own input on the rayon thread pool. The
+/// runs come back in the order of `solutions`, whichever finishes first.
+pub fn run_all(solutions: &[&dyn Solver]) -> Vec<DayRun> {
+    run_all_limited(solutions, None)
+}
+
+/// As `run_all`, but a day that takes longer than `limit` gives up with a
+/// `Timeout` error instead of holding up the rest.
+pub fn run_all_within(solutions: &[&dyn Solver], limit: Duration) -> Vec<DayRun> {
+    run_all_limited(solutions, Some(limit))
+}
+
+fn run_all_limited(solutions: &[&dyn Solver], limit: Option<Duration>) -> Vec<DayRun> {
+    solutions
+        .par_iter()
+        .map(|solution| {
+            let budget = limit.map(Budget::limit).unwrap_or_default();
+            isolated(*solution, || {
+                budget.enter(|| {
+                    let input = Source::day(solution.day()).load()?;
+                    solve(*solution, &input)
+                })
+            })
+        })
+        .collect()
+}
+
+fn isolated<F: FnOnce() -> Result<Solved>>(solution: &dyn Solver, f: F) -> DayRun {
+    let result = panic::catch_unwind(AssertUnwindSafe(f))
+        .unwrap_or_else(|payload| Err(AocError::Panicked(panic_message(payload))));
+
+    DayRun {
+        day: solution.day(),
+        name: solution.name(),
+        result,
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{self, Solution};
+
+    struct Panicking;
+
+    impl Solution for Panicking {
+        const DAY: usize = 1;
+        const NAME: &'static str = "Panicking";
+
+        type Parsed = ();
+
+        fn parse(&self, _input: &Input) -> Result<Self::Parsed> {
+            Ok(())
+        }
+
+        fn part_one(&self, _parsed: &Self::Parsed) -> Result<String> {
+            Ok("1".to_string())
+        }
+
+        fn part_two(&self, _parsed: &Self::Parsed) -> Result<String> {
+            panic!("part two exploded")
+        }
+    }
+
+    #[test]
+    fn solving() {
+        let input = Input::from("1721\n979\n366\n299\n675\n1456\n");
+        let solved = solve(solution::get(1).unwrap(), &input).unwrap();
+        assert_eq!(
+            solved.answers,
+            vec![
+                (Part::One, "514579".to_string()),
+                (Part::Two, "241861950".to_string())
+            ]
+        );
+        assert_eq!(solved.timing.parts.len(), 2);
+    }
+
+    #[test]
+    fn isolating_panics() {
+        let run = run(&Panicking, &Input::default());
+        assert_eq!(run.name, "Panicking");
+        match run.result {
+            Err(AocError::Panicked(message)) => assert_eq!(message, "part two exploded"),
+            other => panic!("expected a panic, got {:?}", other),
+        }
+
+        let run = run_parts(&Panicking, &Input::default(), &[Part::One]);
+        assert_eq!(
+            run.result.unwrap().answers,
+            vec![(Part::One, "1".to_string())]
+        );
+    }
+
+    #[test]
+    fn running_in_order() {
+        let solutions: Vec<&dyn Solver> = vec![
+            solution::get(10).unwrap(),
+            &Panicking,
+            solution::get(3).unwrap(),
+        ];
+
+        let runs = run_all(&solutions);
+        assert_eq!(
+            runs.iter().map(|run| run.name).collect::<Vec<&str>>(),
+            vec!["Adapter Array", "Panicking", "Toboggan Trajectory"]
+        );
+        assert!(runs[0].result.is_ok());
+        assert!(runs[1].result.is_err());
+        assert!(runs[2].result.is_ok());
+    }
+
+    #[test]
+    fn running_out_of_time() {
+        let runs = run_all_within(&[solution::get(15).unwrap()], Duration::from_millis(1));
+        match runs[0].result {
+            Err(AocError::Timeout(_)) => {}
+            ref other => panic!("expected a timeout, got {:?}", other),
+        }
+
+        let budget = Budget::unlimited();
+        budget.cancel();
+        let input = Input::from("0,3,6\n");
+        assert!(budget
+            .enter(|| solve(solution::get(15).unwrap(), &input))
+            .is_err());
+    }
+}
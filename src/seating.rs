@@ -1,6 +1,12 @@
 use crate::error::{AocError, Result};
+use crate::input::Input;
+use crate::render::Render;
+use crate::solution::Solution;
+use crate::trace;
+use crate::util::parse_lines;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Loc {
     EmptySeat,
     OccupiedSeat,
@@ -14,7 +20,7 @@ impl Loc {
             'L' => Ok(Loc::EmptySeat),
             '.' => Ok(Loc::Floor),
             _ => Err(AocError::InvalidInput(format!(
-                "Unknown location char {}",
+                "Unknown location char '{}'",
                 c
             ))),
         }
@@ -22,6 +28,7 @@ impl Loc {
 }
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Area {
     seats: Vec<Vec<Loc>>,
     search_range: Option<usize>,
@@ -29,25 +36,30 @@ pub struct Area {
 }
 
 impl Area {
-    pub fn new(
-        input: &[String],
+    pub fn new<S: AsRef<str>>(
+        input: &[S],
         search_range: Option<usize>,
         tipping_point: usize,
     ) -> Result<Self> {
         Ok(Area {
-            seats: input
-                .iter()
-                .map(|line| {
-                    line.chars()
-                        .map(|c| Loc::from_char(c))
-                        .collect::<Result<Vec<Loc>>>()
-                })
-                .collect::<Result<Vec<Vec<Loc>>>>()?,
+            seats: parse_lines(input, |line| {
+                line.chars()
+                    .map(|c| Loc::from_char(c))
+                    .collect::<Result<Vec<Loc>>>()
+            })?,
             search_range: search_range,
             tipping_point: tipping_point,
         })
     }
 
+    /// The same seats under other rules: how far away a seat can be seen,
+    /// if not all the way, and how many occupied seats make someone leave.
+    pub fn with_rules(mut self, search_range: Option<usize>, tipping_point: usize) -> Self {
+        self.search_range = search_range;
+        self.tipping_point = tipping_point;
+        self
+    }
+
     fn search_range(&self) -> i64 {
         match self.search_range {
             Some(val) => val as i64,
@@ -109,6 +121,7 @@ impl Area {
     }
 
     pub fn step(&self) -> Result<Self> {
+        let _span = trace::span!("Area::step");
         let mut new_seats = self.seats.clone();
 
         for row in 0..self.seats.len() {
@@ -142,6 +155,62 @@ impl Area {
     }
 }
 
+impl Render for Area {
+    fn render(&self) -> String {
+        self.seats
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|loc| match loc {
+                        Loc::EmptySeat => 'L',
+                        Loc::OccupiedSeat => '#',
+                        Loc::Floor => '.',
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+/// Step the area until nobody moves seats any more.
+fn stabilize(mut area: Area) -> Result<Area> {
+    let _span = trace::span!("stabilize");
+    loop {
+        let new_area = area.step()?;
+
+        if area == new_area {
+            return Ok(area);
+        }
+
+        area = new_area;
+    }
+}
+
+pub struct SeatingSystem;
+
+impl Solution for SeatingSystem {
+    const DAY: usize = 11;
+    const NAME: &'static str = "Seating System";
+
+    // parsed with part one's rules, which part two swaps for its own
+    type Parsed = Area;
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed> {
+        Area::new(&input.lines(), Some(1), 4)
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String> {
+        Ok(stabilize(parsed.clone())?.occupied_seats().to_string())
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String> {
+        Ok(stabilize(parsed.clone().with_rules(None, 5))?
+            .occupied_seats()
+            .to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     mod loc {
@@ -280,6 +349,15 @@ mod tests {
             assert_eq!(a.step().unwrap(), a);
         }
 
+        #[test]
+        fn render() {
+            let a = Area::new(&input(), Some(1), 4).unwrap();
+            assert_eq!(a.render(), input().join("\n"));
+
+            let a = a.step().unwrap();
+            assert_eq!(a.render().lines().next(), Some("#.##.##.##"));
+        }
+
         #[test]
         fn occupied_seats() {
             let state = test_input(
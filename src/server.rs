@@ -0,0 +1,312 @@
+//! Answers puzzle inputs over HTTP for tools that would rather not link
+//! against the library. Built with the `server` feature as `aoc-server`:
+//!
+//! ```text
+//! cargo run --release --features server --bin aoc-server
+//! curl --data-binary @input localhost:8020/day/8/part/2
+//! curl localhost:8020/days
+//! ```
+//!
+//! Answers come with the nanoseconds each step took. Errors name the
+//! `AocError` variant and, for bad input, where it was. Bad input is a 422,
+//! an unknown day or part a 404, a panic a 500 and a solve that runs past
+//! the `--timeout` of `aoc-server` a 503.
+
+use std::io;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tiny_http::{Header, Request, Response};
+
+use crate::budget::Budget;
+use crate::error::{AocError, Result};
+use crate::input::Input;
+use crate::runner::{self, Solved};
+use crate::solution::{self, Part};
+
+/// What to send back for a request: an HTTP status and a JSON body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reply {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl Reply {
+    fn ok(body: Value) -> Self {
+        Reply { status: 200, body }
+    }
+
+    fn error(err: &AocError) -> Self {
+        let status = match *err {
+            AocError::UnknownSolution(_) => 404,
+            AocError::Timeout(_) => 503,
+            AocError::Panicked(_) | AocError::IOError(_) | AocError::Serialization(_) => 500,
+            _ => 422,
+        };
+
+        let message = match *err {
+            AocError::Located(_, ref inner) => inner.to_string(),
+            ref err => err.to_string(),
+        };
+
+        let mut error = json!({
+            "kind": err.kind(),
+            "message": message,
+        });
+        if let Some(span) = err.span() {
+            error["line"] = span.line.into();
+            error["column"] = span.column.into();
+        }
+
+        Reply {
+            status,
+            body: json!({ "error": error }),
+        }
+    }
+}
+
+/// Route a request and answer it, without any networking. Solves run within
+/// the current budget.
+///
+/// - `GET /days` lists the days and their parts
+/// - `POST /day/<day>` solves every part of a day for the input in the body
+/// - `POST /day/<day>/part/<part>` solves a single part
+pub fn handle(method: &str, url: &str, body: &[u8]) -> Reply {
+    let path = url.split('?').next().unwrap_or_default();
+    let segments = path.trim_matches('/').split('/').collect::<Vec<&str>>();
+
+    let result = match (method, segments.as_slice()) {
+        ("GET", ["days"]) => Ok(days()),
+        ("POST", ["day", day]) => solve(day, None, body),
+        ("POST", ["day", day, "part", part]) => solve(day, Some(part), body),
+        (_, ["days"]) | (_, ["day", _]) | (_, ["day", _, "part", _]) => {
+            return Reply {
+                status: 405,
+                body: json!({ "error": {
+                    "kind": "MethodNotAllowed",
+                    "message": format!("{} is not allowed on {}", method, path),
+                }}),
+            };
+        }
+        _ => Err(AocError::UnknownSolution(format!("path {}", path))),
+    };
+
+    match result {
+        Ok(body) => Reply::ok(body),
+        Err(err) => Reply::error(&err),
+    }
+}
+
+fn days() -> Value {
+    solution::solutions()
+        .iter()
+        .map(|solution| {
+            json!({
+                "day": solution.day(),
+                "name": solution.name(),
+                "parts": solution.parts().iter().map(Part::number).collect::<Vec<usize>>(),
+            })
+        })
+        .collect()
+}
+
+fn solve(day: &str, part: Option<&str>, body: &[u8]) -> Result<Value> {
+    let solution = day
+        .parse::<usize>()
+        .map_err(|_| AocError::UnknownSolution(format!("day '{}'", day)))
+        .and_then(solution::get)?;
+
+    let parts = match part {
+        Some(part) => vec![part
+            .parse::<Part>()
+            .map_err(|_| AocError::UnknownSolution(format!("part '{}'", part)))?],
+        None => solution.parts().to_vec(),
+    };
+
+    let text = String::from_utf8(body.to_vec())
+        .map_err(|_| AocError::InvalidInput("Input is not UTF-8".to_string()))?;
+
+    runner::run_parts(solution, &Input::new(text), &parts)
+        .result
+        .map(|solved| solved_json(&solved))
+}
+
+/// Durations are whole nanoseconds, as in the JSON timing report.
+fn solved_json(solved: &Solved) -> Value {
+    let timing = &solved.timing;
+
+    let mut answers = json!({});
+    let mut durations = json!({ "parse_ns": timing.parse.as_nanos() as u64 });
+    for (part, answer) in &solved.answers {
+        answers[part.to_string()] = answer.as_str().into();
+    }
+    for (part, duration) in &timing.parts {
+        durations[format!("part_{}_ns", part.number())] = (duration.as_nanos() as u64).into();
+    }
+    durations["total_ns"] = (timing.total().as_nanos() as u64).into();
+
+    json!({
+        "day": timing.day,
+        "name": timing.name,
+        "answers": answers,
+        "timing": durations,
+    })
+}
+
+/// An HTTP server answering requests with `handle`.
+pub struct Server {
+    http: tiny_http::Server,
+    timeout: Option<Duration>,
+}
+
+impl Server {
+    /// Listen on `addr`, e.g. `127.0.0.1:8020`. Port 0 picks a free port.
+    pub fn bind(addr: &str) -> Result<Self> {
+        let http =
+            tiny_http::Server::http(addr).map_err(|err| io::Error::other(err.to_string()))?;
+
+        Ok(Server {
+            http,
+            timeout: None,
+        })
+    }
+
+    /// Give up on any request that takes longer than `timeout` to solve.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// The address actually listened on.
+    pub fn addr(&self) -> String {
+        self.http.server_addr().to_string()
+    }
+
+    /// Answer requests until the process is stopped. Each request is solved
+    /// on the rayon pool, so a slow day does not hold up the others.
+    pub fn run(&self) {
+        for request in self.http.incoming_requests() {
+            let timeout = self.timeout;
+            rayon::spawn(move || {
+                if let Err(err) = respond(request, timeout) {
+                    eprintln!("error: {}", err.report());
+                }
+            });
+        }
+    }
+
+    /// Wait for a single request and answer it.
+    pub fn handle_one(&self) -> Result<()> {
+        respond(self.http.recv()?, self.timeout)
+    }
+}
+
+/// Each request gets a budget of its own, starting once its body is read.
+fn respond(mut request: Request, timeout: Option<Duration>) -> Result<()> {
+    let mut body = Vec::new();
+    request.as_reader().read_to_end(&mut body)?;
+
+    let budget = timeout.map(Budget::limit).unwrap_or_default();
+    let reply = budget.enter(|| handle(request.method().as_str(), request.url(), &body));
+    let content_type = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+        .expect("static header is valid");
+
+    request.respond(
+        Response::from_string(format!("{}\n", reply.body))
+            .with_status_code(reply.status)
+            .with_header(content_type),
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::thread;
+
+    const EXPENSES: &[u8] = b"1721\n979\n366\n299\n675\n1456\n";
+
+    #[test]
+    fn listing_days() {
+        let reply = handle("GET", "/days", b"");
+        assert_eq!(reply.status, 200);
+        assert_eq!(reply.body.as_array().unwrap().len(), 25);
+        assert_eq!(
+            reply.body[24],
+            json!({"day": 25, "name": "Combo Breaker", "parts": [1]})
+        );
+    }
+
+    #[test]
+    fn solving() {
+        let reply = handle("POST", "/day/1", EXPENSES);
+        assert_eq!(reply.status, 200);
+        assert_eq!(reply.body["name"], "Report Repair");
+        assert_eq!(
+            reply.body["answers"],
+            json!({"part 1": "514579", "part 2": "241861950"})
+        );
+        assert!(reply.body["timing"]["part_2_ns"].is_u64());
+
+        let reply = handle("POST", "/day/001/part/2?verbose", EXPENSES);
+        assert_eq!(reply.body["answers"], json!({"part 2": "241861950"}));
+        assert!(reply.body["timing"]["part_1_ns"].is_null());
+    }
+
+    #[test]
+    fn errors() {
+        let reply = handle("POST", "/day/8", b"nop +0\nadd +3\n");
+        assert_eq!(reply.status, 422);
+        assert_eq!(reply.body["error"]["kind"], "UnknownOperation");
+        assert_eq!(reply.body["error"]["line"], 2);
+        assert_eq!(reply.body["error"]["column"], 1);
+
+        assert_eq!(handle("POST", "/day/26", b"").status, 404);
+        assert_eq!(handle("POST", "/day/25/part/2", b"1\n2\n").status, 404);
+        assert_eq!(handle("POST", "/day/1/part/3", EXPENSES).status, 404);
+        assert_eq!(handle("GET", "/nowhere", b"").status, 404);
+        assert_eq!(handle("GET", "/day/1", b"").status, 405);
+        assert_eq!(handle("POST", "/day/1", &[0xff, 0xfe]).status, 422);
+    }
+
+    #[test]
+    fn timing_out() {
+        let budget = Budget::unlimited();
+        budget.cancel();
+        let reply = budget.enter(|| handle("POST", "/day/15/part/2", b"0,3,6\n"));
+        assert_eq!(reply.status, 503);
+        assert_eq!(reply.body["error"]["kind"], "Timeout");
+    }
+
+    #[test]
+    fn serving() {
+        let server = Server::bind("127.0.0.1:0")
+            .unwrap()
+            .with_timeout(Duration::from_secs(60));
+        let addr = server.addr();
+        let handler = thread::spawn(move || server.handle_one().unwrap());
+
+        let mut stream = TcpStream::connect(&addr).unwrap();
+        write!(
+            stream,
+            "POST /day/1/part/1 HTTP/1.1\r\nHost: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            addr,
+            EXPENSES.len()
+        )
+        .unwrap();
+        stream.write_all(EXPENSES).unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        handler.join().unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+        assert!(response.contains("application/json"));
+        let body = response.split("\r\n\r\n").nth(1).unwrap();
+        let body: Value = serde_json::from_str(body).unwrap();
+        assert_eq!(body["answers"]["part 1"], "514579");
+    }
+}
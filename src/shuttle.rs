@@ -1,8 +1,13 @@
+use crate::budget::Budget;
 use crate::error::{AocError, Result};
+use crate::input::Input;
+use crate::solution::Solution;
+use crate::trace;
 use std::fmt;
 use std::str::FromStr;
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Bus {
     id: usize,
     in_service: bool,
@@ -53,23 +58,38 @@ impl FromStr for Bus {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Schedule {
     buses: Vec<Bus>,
 }
 
 impl Schedule {
     pub fn earliest_departure(&self, start: usize) -> Option<(usize, Bus)> {
+        self.earliest_departure_within(start, &Budget::unlimited())
+            .ok()
+            .flatten()
+    }
+
+    /// As `earliest_departure`, but giving up with a `Timeout` error if the
+    /// budget runs out.
+    pub fn earliest_departure_within(
+        &self,
+        start: usize,
+        budget: &Budget,
+    ) -> Result<Option<(usize, Bus)>> {
+        let _span = trace::span!("Schedule::earliest_departure_within");
         let buses = self.buses_in_service().collect::<Vec<&Bus>>();
 
         for i in start..(start + 1_000_000) {
+            budget.check_at(i - start)?;
             for bus in &buses {
                 if bus.departs_at(i) {
-                    return Some((i - start, *bus.clone()));
+                    return Ok(Some((i - start, *bus.clone())));
                 }
             }
         }
 
-        None
+        Ok(None)
     }
 
     pub fn buses_in_service(&self) -> impl Iterator<Item = &Bus> {
@@ -77,6 +97,15 @@ impl Schedule {
     }
 
     pub fn sync_departures(&self) -> Option<usize> {
+        self.sync_departures_within(&Budget::unlimited())
+            .ok()
+            .flatten()
+    }
+
+    /// As `sync_departures`, but giving up with a `Timeout` error if the
+    /// budget runs out. Without a solution the search never ends otherwise.
+    pub fn sync_departures_within(&self, budget: &Budget) -> Result<Option<usize>> {
+        let _span = trace::span!("Schedule::sync_departures_within");
         let mut max = 0;
         let mut max_offset = 0;
 
@@ -97,7 +126,8 @@ impl Schedule {
             }
         }
 
-        let first_intersection = self.calc_first_intersection(max_offset, second_max_offset);
+        let first_intersection =
+            self.calc_first_intersection(max_offset, second_max_offset, budget)?;
         let mut g = Generator::new(max, second_max, first_intersection, max_offset);
 
         let buses = self
@@ -111,11 +141,12 @@ impl Schedule {
             loop {
                 let next_candidate = g.next();
                 if self.check_departures(next_candidate, &buses) {
-                    return Some(next_candidate);
+                    return Ok(Some(next_candidate));
                 }
+                budget.check_at(g.n)?;
             }
         }
-        None
+        Ok(None)
     }
 
     pub fn check_departures(&self, cur: usize, buses: &[(usize, &Bus)]) -> bool {
@@ -127,16 +158,24 @@ impl Schedule {
         true
     }
 
-    fn calc_first_intersection(&self, bus_idx: usize, other_idx: usize) -> usize {
+    fn calc_first_intersection(
+        &self,
+        bus_idx: usize,
+        other_idx: usize,
+        budget: &Budget,
+    ) -> Result<usize> {
         let left = self.buses[bus_idx];
         let right = self.buses[other_idx];
 
         let mut cur = left.id() - bus_idx;
+        let mut steps = 0;
         loop {
             if (cur + other_idx) % right.id() == 0 {
-                return cur;
+                return Ok(cur);
             }
             cur += left.id();
+            steps += 1;
+            budget.check_at(steps)?;
         }
     }
 }
@@ -155,6 +194,7 @@ impl FromStr for Schedule {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Generator {
     pub left: usize,
     pub right: usize,
@@ -185,6 +225,49 @@ impl Generator {
     }
 }
 
+pub struct ShuttleSearch;
+
+impl Solution for ShuttleSearch {
+    const DAY: usize = 13;
+    const NAME: &'static str = "Shuttle Search";
+
+    // (earliest departure time, schedule)
+    type Parsed = (usize, Schedule);
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed> {
+        let lines = input.lines();
+        let mut lines = lines.iter();
+
+        let start = lines
+            .next()
+            .ok_or_else(|| AocError::InvalidInput("Missing start time".to_string()))?
+            .parse::<usize>()?;
+        let schedule = Schedule::from_str(
+            lines
+                .next()
+                .ok_or_else(|| AocError::InvalidInput("Missing schedule".to_string()))?,
+        )?;
+
+        Ok((start, schedule))
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String> {
+        parsed
+            .1
+            .earliest_departure_within(parsed.0, &Budget::current())?
+            .map(|(wait, bus)| (wait * bus.id()).to_string())
+            .ok_or_else(|| AocError::InvalidInput("No bus departs in time".to_string()))
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String> {
+        parsed
+            .1
+            .sync_departures_within(&Budget::current())?
+            .map(|time| time.to_string())
+            .ok_or_else(|| AocError::InvalidInput("No buses in service".to_string()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -369,6 +452,25 @@ mod tests {
             assert_eq!(s.sync_departures(), Some(1068781));
         }
 
+        #[test]
+        fn running_out_of_time() {
+            let budget = Budget::unlimited();
+            budget.cancel();
+
+            let s = Schedule::from_str("7,13,x,x,59,x,31,19").unwrap();
+            assert!(matches!(
+                s.earliest_departure_within(939, &budget),
+                Err(AocError::Timeout(_))
+            ));
+
+            // no time is 0 mod 4 and 5 mod 6, so this would search forever
+            let s = Schedule::from_str("4,6").unwrap();
+            assert!(matches!(
+                s.sync_departures_within(&budget),
+                Err(AocError::Timeout(_))
+            ));
+        }
+
         #[test]
         fn foo() {
             for i in 0..1 {
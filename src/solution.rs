@@ -0,0 +1,297 @@
+//! Every day as a `Solution`: `parse` the input once, then answer
+//! `part_one` and `part_two` from what it parsed. `get(day)` looks a day up
+//! in the registry the `aoc` binary runs from.
+//!
+//! Alternate implementations of a part, such as day 8's
+//! `correct_parallel` and `correct_recursive`, are listed in `VARIANTS`
+//! so `aoc cross-check` can compare them with the default answer. Slow ones
+//! only run when asked for with `--slow`.
+
+use std::any::Any;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{AocError, Result};
+use crate::input::Input;
+use crate::{
+    adapter, boarding, calculator, combat, console, cups, customs, docking, encryption, expense,
+    food, game, jigsaw, lobby, luggage, message, navigation, passport, password, power, seating,
+    shuttle, ticket, toboggan, xmas,
+};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub fn number(&self) -> usize {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(AocError::InvalidInput(format!("Unknown part: '{}'", s))),
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "part {}", self.number())
+    }
+}
+
+pub const BOTH_PARTS: &[Part] = &[Part::One, Part::Two];
+
+/// A named alternate implementation of one part of a day, e.g. the
+/// recursive and iterative takes on correcting the day 8 program. Variants
+/// work on the same parsed input as the part they stand in for.
+pub struct Variant<P: 'static> {
+    pub part: Part,
+    pub name: &'static str,
+    /// Too slow for a full puzzle input, only worth running on examples
+    pub slow: bool,
+    pub solve: fn(&P) -> Result<String>,
+}
+
+impl<P> Variant<P> {
+    pub fn info(&self) -> VariantInfo {
+        VariantInfo {
+            part: self.part,
+            name: self.name,
+            slow: self.slow,
+        }
+    }
+}
+
+/// What the registry knows about a variant without its parsed input type.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct VariantInfo {
+    pub part: Part,
+    pub name: &'static str,
+    pub slow: bool,
+}
+
+/// What a day parses its input into. With the `serde` feature this has to
+/// be serializable, so parsed models can be dumped as JSON.
+#[cfg(feature = "serde")]
+pub trait Model: serde::Serialize {}
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> Model for T {}
+
+#[cfg(not(feature = "serde"))]
+pub trait Model {}
+
+#[cfg(not(feature = "serde"))]
+impl<T> Model for T {}
+
+/// A Solution ties together parsing the puzzle input of a day and solving
+/// both of its parts. The parsed representation is computed once and shared
+/// by the parts, so parts that need to mutate it work on a copy.
+pub trait Solution: Send + Sync {
+    const DAY: usize;
+    const NAME: &'static str;
+
+    /// The parts this day has. Only day 25 differs from the default.
+    const PARTS: &'static [Part] = BOTH_PARTS;
+
+    type Parsed: Model + Send + 'static;
+
+    /// Alternate implementations of the parts, which should all give the
+    /// same answers as `part_one` and `part_two`.
+    const VARIANTS: &'static [Variant<Self::Parsed>] = &[];
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed>;
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String>;
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String>;
+}
+
+/// Solver is the object-safe face of a Solution, which lets solutions for
+/// different days live side by side in the registry.
+pub trait Solver: Send + Sync {
+    fn day(&self) -> usize;
+    fn name(&self) -> &'static str;
+    fn parts(&self) -> &'static [Part];
+    fn parse_input(&self, input: &Input) -> Result<Box<dyn Any + Send>>;
+    fn solve_part(&self, parsed: &(dyn Any + Send), part: Part) -> Result<String>;
+    fn variants(&self) -> Vec<VariantInfo>;
+    fn solve_variant(&self, parsed: &(dyn Any + Send), name: &str) -> Result<String>;
+
+    /// The parsed input as JSON.
+    #[cfg(feature = "serde")]
+    fn model(&self, parsed: &(dyn Any + Send)) -> Result<serde_json::Value>;
+
+    fn solve(&self, input: &Input, part: Part) -> Result<String> {
+        self.solve_part(self.parse_input(input)?.as_ref(), part)
+    }
+}
+
+impl<T: Solution> Solver for T {
+    fn day(&self) -> usize {
+        T::DAY
+    }
+
+    fn name(&self) -> &'static str {
+        T::NAME
+    }
+
+    fn parts(&self) -> &'static [Part] {
+        T::PARTS
+    }
+
+    fn parse_input(&self, input: &Input) -> Result<Box<dyn Any + Send>> {
+        Ok(Box::new(self.parse(input)?))
+    }
+
+    fn solve_part(&self, parsed: &(dyn Any + Send), part: Part) -> Result<String> {
+        if !T::PARTS.contains(&part) {
+            return Err(AocError::UnknownSolution(format!(
+                "day {} has no {}",
+                T::DAY,
+                part
+            )));
+        }
+
+        match part {
+            Part::One => self.part_one(downcast::<T>(parsed)?),
+            Part::Two => self.part_two(downcast::<T>(parsed)?),
+        }
+    }
+
+    fn variants(&self) -> Vec<VariantInfo> {
+        T::VARIANTS.iter().map(|variant| variant.info()).collect()
+    }
+
+    fn solve_variant(&self, parsed: &(dyn Any + Send), name: &str) -> Result<String> {
+        let variant = T::VARIANTS
+            .iter()
+            .find(|variant| variant.name == name)
+            .ok_or_else(|| {
+                AocError::UnknownSolution(format!("day {} variant '{}'", T::DAY, name))
+            })?;
+
+        (variant.solve)(downcast::<T>(parsed)?)
+    }
+
+    #[cfg(feature = "serde")]
+    fn model(&self, parsed: &(dyn Any + Send)) -> Result<serde_json::Value> {
+        Ok(serde_json::to_value(downcast::<T>(parsed)?)?)
+    }
+}
+
+fn downcast<T: Solution>(parsed: &(dyn Any + Send)) -> Result<&T::Parsed> {
+    parsed.downcast_ref::<T::Parsed>().ok_or_else(|| {
+        AocError::InvalidInput(format!("Parsed input does not belong to day {}", T::DAY))
+    })
+}
+
+pub const NUM_DAYS: usize = 25;
+
+static SOLUTIONS: [&dyn Solver; NUM_DAYS] = [
+    &expense::ReportRepair,
+    &password::PasswordPhilosophy,
+    &toboggan::TobogganTrajectory,
+    &passport::PassportProcessing,
+    &boarding::BinaryBoarding,
+    &customs::CustomCustoms,
+    &luggage::HandyHaversacks,
+    &console::HandheldHalting,
+    &xmas::EncodingError,
+    &adapter::AdapterArray,
+    &seating::SeatingSystem,
+    &navigation::RainRisk,
+    &shuttle::ShuttleSearch,
+    &docking::DockingData,
+    &game::RambunctiousRecitation,
+    &ticket::TicketTranslation,
+    &power::ConwayCubes,
+    &calculator::OperationOrder,
+    &message::MonsterMessages,
+    &jigsaw::JurassicJigsaw,
+    &food::AllergenAssessment,
+    &combat::CrabCombat,
+    &cups::CrabCups,
+    &lobby::LobbyLayout,
+    &encryption::ComboBreaker,
+];
+
+/// All registered solutions, ordered by day.
+pub fn solutions() -> &'static [&'static dyn Solver] {
+    &SOLUTIONS
+}
+
+/// The solution for the given day (1 through 25).
+pub fn get(day: usize) -> Result<&'static dyn Solver> {
+    day.checked_sub(1)
+        .and_then(|index| SOLUTIONS.get(index))
+        .copied()
+        .ok_or_else(|| AocError::UnknownSolution(format!("day {}", day)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_is_ordered_by_day() {
+        for (index, solution) in solutions().iter().enumerate() {
+            assert_eq!(solution.day(), index + 1);
+        }
+        assert_eq!(solutions().len(), NUM_DAYS);
+    }
+
+    #[test]
+    fn getting_solutions() {
+        assert_eq!(get(1).unwrap().name(), "Report Repair");
+        assert_eq!(get(25).unwrap().name(), "Combo Breaker");
+        assert!(get(0).is_err());
+        assert!(get(26).is_err());
+    }
+
+    #[test]
+    fn solving() {
+        let input = Input::from("1721\n979\n366\n299\n675\n1456\n");
+
+        let solution = get(1).unwrap();
+        let parsed = solution.parse_input(&input).unwrap();
+        assert_eq!(
+            solution.solve_part(parsed.as_ref(), Part::One).unwrap(),
+            "514579"
+        );
+        assert_eq!(solution.solve(&input, Part::Two).unwrap(), "241861950");
+
+        // parsed input of one day cannot be handed to another
+        assert!(get(2)
+            .unwrap()
+            .solve_part(parsed.as_ref(), Part::One)
+            .is_err());
+    }
+
+    #[test]
+    fn missing_parts() {
+        let solution = get(25).unwrap();
+        assert_eq!(solution.parts(), &[Part::One]);
+        let input = Input::from("5764801\n17807724\n");
+        assert!(solution.solve(&input, Part::Two).is_err());
+        assert_eq!(solution.solve(&input, Part::One).unwrap(), "14897079");
+    }
+
+    #[test]
+    fn parsing_parts() {
+        assert_eq!(Part::from_str("1").unwrap(), Part::One);
+        assert_eq!(Part::from_str("2").unwrap(), Part::Two);
+        assert!(Part::from_str("3").is_err());
+    }
+}
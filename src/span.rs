@@ -0,0 +1,120 @@
+use std::fmt;
+
+/// Span points at the part of the input an error came from. Lines and
+/// columns are 1-based, and columns count characters rather than bytes.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Span {
+    pub file: Option<String>,
+    pub line: usize,
+    pub column: usize,
+    pub width: usize,
+    pub text: String,
+}
+
+impl Span {
+    pub fn new(line: usize, column: usize, width: usize, text: &str) -> Self {
+        Span {
+            file: None,
+            line,
+            column,
+            width,
+            text: text.to_string(),
+        }
+    }
+
+    /// Find the span of `fragment` within the given lines, the first of which
+    /// is line `first_line`. Without a fragment, or if it cannot be found,
+    /// the whole first line is the span.
+    pub fn locate<S: AsRef<str>>(lines: &[S], first_line: usize, fragment: Option<&str>) -> Self {
+        let found = fragment
+            .filter(|fragment| !fragment.is_empty())
+            .and_then(|fragment| {
+                lines.iter().enumerate().find_map(|(index, line)| {
+                    let line = line.as_ref();
+                    line.find(fragment).map(|byte| {
+                        Span::new(
+                            first_line + index,
+                            line[..byte].chars().count() + 1,
+                            fragment.chars().count(),
+                            line,
+                        )
+                    })
+                })
+            });
+
+        found.unwrap_or_else(|| {
+            let text = lines.first().map(|line| line.as_ref()).unwrap_or("");
+            Span::new(first_line, 1, text.chars().count(), text)
+        })
+    }
+
+    /// The offending line with a caret under the error, e.g.
+    ///
+    /// ```text
+    ///   --> input:3:5
+    ///    |
+    ///  3 | jmp +x
+    ///    |     ^^
+    /// ```
+    pub fn snippet(&self) -> String {
+        let number = self.line.to_string();
+        let gutter = " ".repeat(number.len());
+
+        format!(
+            "{}--> {}\n{} |\n{} | {}\n{} | {}{}",
+            gutter,
+            self,
+            gutter,
+            number,
+            self.text,
+            gutter,
+            " ".repeat(self.column.saturating_sub(1)),
+            "^".repeat(self.width.max(1))
+        )
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.file {
+            Some(ref file) => write!(f, "{}:{}:{}", file, self.line, self.column),
+            None => write!(f, "line {}, column {}", self.line, self.column),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locating() {
+        let lines = vec!["nop +0", "jmp +x", "acc +1"];
+
+        let span = Span::locate(&lines, 1, Some("+x"));
+        assert_eq!(span, Span::new(2, 5, 2, "jmp +x"));
+
+        let span = Span::locate(&lines[1..], 2, None);
+        assert_eq!(span, Span::new(2, 1, 6, "jmp +x"));
+
+        let span = Span::locate(&lines, 10, Some("missing"));
+        assert_eq!(span, Span::new(10, 1, 6, "nop +0"));
+
+        // columns count characters
+        let span = Span::locate(&["ü x"], 1, Some("x"));
+        assert_eq!(span.column, 3);
+    }
+
+    #[test]
+    fn formatting() {
+        let mut span = Span::new(12, 5, 2, "jmp +x");
+        assert_eq!(span.to_string(), "line 12, column 5");
+        assert_eq!(
+            span.snippet(),
+            "  --> line 12, column 5\n   |\n12 | jmp +x\n   |     ^^"
+        );
+
+        span.file = Some("input".to_string());
+        assert_eq!(span.to_string(), "input:12:5");
+    }
+}
@@ -1,8 +1,13 @@
 use crate::error::{AocError, Result};
+use crate::input::Input;
+use crate::solution::{Part, Solution, Variant};
+use crate::trace;
+use crate::util::{collect_lines, numbered_blocks, parse_lines, Collected};
 use std::collections::HashSet;
 use std::str::FromStr;
 
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Range(usize, usize);
 
 impl Range {
@@ -46,6 +51,7 @@ impl FromStr for Range {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rule {
     pub name: String,
     pub ranges: Vec<Range>,
@@ -102,6 +108,7 @@ impl FromStr for Rule {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ticket {
     pub values: Vec<usize>,
     pub is_valid: bool,
@@ -130,18 +137,20 @@ impl FromStr for Ticket {
 }
 
 #[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TicketValidator {
     pub rules: Vec<Rule>,
 }
 
 impl TicketValidator {
-    pub fn from_input(input: &[String]) -> Result<Self> {
-        Ok(Self::new(
-            &input
-                .iter()
-                .map(|line| Rule::from_str(line))
-                .collect::<Result<Vec<Rule>>>()?,
-        ))
+    pub fn from_input<S: AsRef<str>>(input: &[S]) -> Result<Self> {
+        Ok(Self::new(&parse_lines(input, Rule::from_str)?))
+    }
+
+    /// A validator of every rule that parsed, and the errors of those that
+    /// did not.
+    pub fn from_input_lenient<S: AsRef<str>>(input: &[S]) -> Collected<Self> {
+        collect_lines(input, Rule::from_str).map(|rules| Self::new(&rules))
     }
 
     pub fn new(rules: &Vec<Rule>) -> Self {
@@ -175,6 +184,7 @@ impl TicketValidator {
     }
 
     pub fn determine_rule_order(&mut self, tickets: &[Ticket]) -> Result<()> {
+        let _span = trace::span!("TicketValidator::determine_rule_order");
         let mut acc = Vec::new();
         let mut used: HashSet<&Rule> = HashSet::new();
 
@@ -212,6 +222,7 @@ impl TicketValidator {
     }
 
     pub fn determine_rule_order_fast(&mut self, tickets: &[Ticket]) -> Result<()> {
+        let _span = trace::span!("TicketValidator::determine_rule_order_fast");
         let mut col_map = self.make_col_map(tickets);
         let mut indicies = (0..col_map.len()).collect::<HashSet<usize>>();
 
@@ -231,6 +242,7 @@ impl TicketValidator {
             .into_iter()
             .map(|col| col.into_iter().next().unwrap().to_owned())
             .collect::<Vec<Rule>>();
+        trace::event!(rules = self.rules.len(), "determined rule order");
 
         Ok(())
     }
@@ -290,6 +302,117 @@ impl TicketValidator {
     }
 }
 
+/// The rules, our ticket and the nearby tickets, as separated by blank lines
+/// in the puzzle input.
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Notes {
+    pub validator: TicketValidator,
+    pub ticket: Ticket,
+    pub nearby_tickets: Vec<Ticket>,
+}
+
+impl Notes {
+    pub fn from_input<S: AsRef<str>>(input: &[S]) -> Result<Self> {
+        let _span = trace::span!("Notes::from_input");
+        let missing = |what: &str| AocError::InvalidInput(format!("Missing {}", what));
+        let mut blocks = numbered_blocks(input);
+
+        let (first_line, rules) = blocks.next().ok_or_else(|| missing("rules"))?;
+        let validator =
+            TicketValidator::from_input(rules).map_err(|e| e.locate(rules, first_line))?;
+
+        // the ticket sections start with a "your ticket:" style header line
+        let (first_line, ours) = blocks
+            .next()
+            .filter(|(_, section)| section.len() > 1)
+            .ok_or_else(|| missing("our ticket"))?;
+        let ticket = parse_lines(&ours[1..2], Ticket::from_str)
+            .map_err(|e| e.locate(&ours[1..2], first_line + 1))?
+            .remove(0);
+
+        let (first_line, nearby) = blocks.next().ok_or_else(|| missing("nearby tickets"))?;
+        let nearby_tickets = parse_lines(&nearby[1..], Ticket::from_str)
+            .map_err(|e| e.locate(&nearby[1..], first_line + 1))?;
+
+        Ok(Notes {
+            validator,
+            ticket,
+            nearby_tickets,
+        })
+    }
+
+    /// Validate the nearby tickets, returning the sum of all values that do
+    /// not match any rule.
+    pub fn error_rate(&mut self) -> usize {
+        let _span = trace::span!("Notes::error_rate");
+        let validator = &self.validator;
+        self.nearby_tickets
+            .iter_mut()
+            .filter_map(|ticket| validator.validate(ticket))
+            .map(|vals| vals.iter().sum::<usize>())
+            .sum()
+    }
+}
+
+pub struct TicketTranslation;
+
+impl Solution for TicketTranslation {
+    const DAY: usize = 16;
+    const NAME: &'static str = "Ticket Translation";
+
+    type Parsed = Notes;
+
+    // the backtracking search takes minutes on a real input
+    const VARIANTS: &'static [Variant<Self::Parsed>] = &[Variant {
+        part: Part::Two,
+        name: "determine_rule_order",
+        slow: true,
+        solve: |notes| departure_product(notes, TicketValidator::determine_rule_order),
+    }];
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed> {
+        Notes::from_input(&input.lines())
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String> {
+        Ok(parsed.clone().error_rate().to_string())
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String> {
+        departure_product(parsed, TicketValidator::determine_rule_order_fast)
+    }
+}
+
+/// The product of the departure fields of our ticket, once `order` has put
+/// the rules in field order.
+fn departure_product(
+    notes: &Notes,
+    order: fn(&mut TicketValidator, &[Ticket]) -> Result<()>,
+) -> Result<String> {
+    let mut notes = notes.clone();
+    notes.error_rate();
+
+    let valid_tickets = notes
+        .nearby_tickets
+        .iter()
+        .filter(|ticket| ticket.is_valid)
+        .cloned()
+        .collect::<Vec<Ticket>>();
+
+    order(&mut notes.validator, &valid_tickets)?;
+
+    Ok(notes
+        .validator
+        .rules
+        .iter()
+        .enumerate()
+        .filter(|(_, r)| r.name.starts_with("departure"))
+        .map(|(index, _)| notes.ticket.values[index])
+        .product::<usize>()
+        .to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -487,4 +610,37 @@ mod tests {
             )
         }
     }
+
+    mod notes {
+        use super::*;
+        use crate::util::test_input;
+
+        #[test]
+        fn from_input() {
+            let input = test_input(
+                "
+                class: 1-3 or 5-7
+                row: 6-11 or 33-44
+                seat: 13-40 or 45-50
+
+                your ticket:
+                7,1,14
+
+                nearby tickets:
+                7,3,47
+                40,4,50
+                55,2,20
+                38,6,12
+                ",
+            );
+
+            let mut notes = Notes::from_input(&input).unwrap();
+            assert_eq!(notes.validator.rules.len(), 3);
+            assert_eq!(notes.ticket, Ticket::new(&vec![7, 1, 14]));
+            assert_eq!(notes.nearby_tickets.len(), 4);
+            assert_eq!(notes.error_rate(), 71);
+
+            assert!(Notes::from_input(&input[..3]).is_err());
+        }
+    }
 }
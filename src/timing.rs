@@ -0,0 +1,363 @@
+//! How long each day takes to parse and to solve each part, as a table,
+//! JSON or markdown. Every day runs once, so it is an overview; the
+//! criterion benches are the place for careful numbers.
+//!
+//! ```text
+//! cargo run --release -- time 16 -f json
+//! cargo run --release --example timing markdown
+//! AOC_TIMING=table cargo run --release --example 016_ticket-translation
+//! ```
+
+use std::env;
+use std::fmt::Write;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::error::{AocError, Result};
+use crate::input::{Input, Source};
+use crate::runner::solve;
+use crate::solution::{self, Part, Solver, BOTH_PARTS};
+
+/// Set this to `table`, `json` or `markdown` to have the examples print how
+/// long their day took.
+pub const TIMING_VAR: &str = "AOC_TIMING";
+
+/// How long one day took to parse its input and solve each of its parts.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Timing {
+    pub day: usize,
+    pub name: &'static str,
+    pub parse: Duration,
+    pub parts: Vec<(Part, Duration)>,
+}
+
+impl Timing {
+    pub fn part(&self, part: Part) -> Option<Duration> {
+        self.parts
+            .iter()
+            .find(|(p, _)| *p == part)
+            .map(|(_, duration)| *duration)
+    }
+
+    pub fn total(&self) -> Duration {
+        self.parse
+            + self
+                .parts
+                .iter()
+                .map(|(_, duration)| *duration)
+                .sum::<Duration>()
+    }
+}
+
+/// Time a single run of parsing and every part.
+pub fn time(solution: &dyn Solver, input: &Input) -> Result<Timing> {
+    solve(solution, input).map(|solved| solved.timing)
+}
+
+/// Time a day against its own input and print it in the format named by
+/// `AOC_TIMING`, or do nothing if that is not set. The examples call this
+/// once they have printed their answers.
+pub fn example(day: usize) -> Result<()> {
+    let format = match env::var(TIMING_VAR) {
+        Ok(format) => format.parse::<Format>()?,
+        Err(_) => return Ok(()),
+    };
+
+    let report = Report::run(&[solution::get(day)?])?;
+    print!("{}", report.render(format));
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Format {
+    Table,
+    Json,
+    Markdown,
+}
+
+impl FromStr for Format {
+    type Err = AocError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "table" => Ok(Format::Table),
+            "json" => Ok(Format::Json),
+            "markdown" | "md" => Ok(Format::Markdown),
+            _ => Err(AocError::InvalidInput(format!("Unknown format: '{}'", s))),
+        }
+    }
+}
+
+/// Timings for a number of days, rendered as a table for reading, or as JSON
+/// or markdown for keeping a log across changes.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Report {
+    pub days: Vec<Timing>,
+}
+
+impl Report {
+    /// Time each solution against its own input in the examples directory.
+    pub fn run(solutions: &[&dyn Solver]) -> Result<Self> {
+        let mut days = Vec::new();
+        for solution in solutions {
+            let input = Source::day(solution.day()).load()?;
+            days.push(time(*solution, &input)?);
+        }
+
+        Ok(Report { days })
+    }
+
+    pub fn total(&self) -> Duration {
+        self.days.iter().map(|timing| timing.total()).sum()
+    }
+
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            Format::Table => self.table(),
+            Format::Json => self.json(),
+            Format::Markdown => self.markdown(),
+        }
+    }
+
+    fn table(&self) -> String {
+        let width = self
+            .days
+            .iter()
+            .map(|timing| timing.name.len())
+            .max()
+            .unwrap_or(0)
+            .max("total".len());
+
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "day  {:<width$}  {:>12}  {:>12}  {:>12}  {:>12}",
+            "name",
+            "parse",
+            "part 1",
+            "part 2",
+            "total",
+            width = width
+        );
+
+        for timing in &self.days {
+            let _ = writeln!(
+                out,
+                "{:>3}  {:<width$}  {:>12}  {:>12}  {:>12}  {:>12}",
+                timing.day,
+                timing.name,
+                millis(Some(timing.parse)),
+                millis(timing.part(Part::One)),
+                millis(timing.part(Part::Two)),
+                millis(Some(timing.total())),
+                width = width
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "     {:<width$}  {:>12}  {:>12}  {:>12}  {:>12}",
+            "total",
+            "",
+            "",
+            "",
+            millis(Some(self.total())),
+            width = width
+        );
+
+        out
+    }
+
+    fn markdown(&self) -> String {
+        let mut out = String::new();
+        out.push_str("| day | name | parse | part 1 | part 2 | total |\n");
+        out.push_str("| ---: | --- | ---: | ---: | ---: | ---: |\n");
+
+        for timing in &self.days {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} | {} | {} | {} |",
+                timing.day,
+                timing.name,
+                millis(Some(timing.parse)),
+                millis(timing.part(Part::One)),
+                millis(timing.part(Part::Two)),
+                millis(Some(timing.total()))
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "| | **total** | | | | **{}** |",
+            millis(Some(self.total()))
+        );
+
+        out
+    }
+
+    /// Durations are whole nanoseconds, and a part a day does not have is
+    /// null.
+    fn json(&self) -> String {
+        let days = self
+            .days
+            .iter()
+            .map(|timing| {
+                let parts = BOTH_PARTS
+                    .iter()
+                    .map(|part| {
+                        let ns = timing
+                            .part(*part)
+                            .map(|duration| duration.as_nanos().to_string())
+                            .unwrap_or_else(|| "null".to_string());
+                        format!("\"part_{}_ns\": {}", part.number(), ns)
+                    })
+                    .collect::<Vec<String>>()
+                    .join(", ");
+
+                format!(
+                    "    {{\"day\": {}, \"name\": {}, \"parse_ns\": {}, {}, \"total_ns\": {}}}",
+                    timing.day,
+                    json_string(timing.name),
+                    timing.parse.as_nanos(),
+                    parts,
+                    timing.total().as_nanos()
+                )
+            })
+            .collect::<Vec<String>>();
+
+        format!(
+            "{{\n  \"days\": [\n{}\n  ],\n  \"total_ns\": {}\n}}\n",
+            days.join(",\n"),
+            self.total().as_nanos()
+        )
+    }
+}
+
+/// A string as a JSON string literal, quoted and escaped.
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            ch if ch.is_control() => {
+                let _ = write!(out, "\\u{:04x}", ch as u32);
+            }
+            ch => out.push(ch),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn millis(duration: Option<Duration>) -> String {
+    match duration {
+        Some(duration) => format!("{:.3} ms", duration.as_secs_f64() * 1000.0),
+        None => "-".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution;
+
+    fn report() -> Report {
+        Report {
+            days: vec![
+                Timing {
+                    day: 1,
+                    name: "Report Repair",
+                    parse: Duration::from_micros(20),
+                    parts: vec![
+                        (Part::One, Duration::from_micros(5)),
+                        (Part::Two, Duration::from_micros(1500)),
+                    ],
+                },
+                Timing {
+                    day: 25,
+                    name: "Combo Breaker",
+                    parse: Duration::from_micros(1),
+                    parts: vec![(Part::One, Duration::from_millis(2))],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn timing() {
+        let input = Input::from("1721\n979\n366\n299\n675\n1456\n");
+        let timing = time(solution::get(1).unwrap(), &input).unwrap();
+        assert_eq!(timing.day, 1);
+        assert_eq!(timing.parts.len(), 2);
+        assert!(timing.total() >= timing.parse);
+
+        let timing = time(
+            solution::get(25).unwrap(),
+            &Input::from("5764801\n17807724\n"),
+        )
+        .unwrap();
+        assert_eq!(timing.part(Part::Two), None);
+
+        assert!(time(solution::get(1).unwrap(), &Input::from("x")).is_err());
+    }
+
+    #[test]
+    fn totals() {
+        let report = report();
+        assert_eq!(report.days[0].total(), Duration::from_micros(1525));
+        assert_eq!(report.total(), Duration::from_micros(3526));
+    }
+
+    #[test]
+    fn rendering() {
+        let report = report();
+
+        assert_eq!(
+            report.render(Format::Table),
+            "\
+day  name                  parse        part 1        part 2         total
+  1  Report Repair      0.020 ms      0.005 ms      1.500 ms      1.525 ms
+ 25  Combo Breaker      0.001 ms      2.000 ms             -      2.001 ms
+     total                                                        3.526 ms
+"
+        );
+
+        assert_eq!(
+            report.render(Format::Markdown),
+            "\
+| day | name | parse | part 1 | part 2 | total |
+| ---: | --- | ---: | ---: | ---: | ---: |
+| 1 | Report Repair | 0.020 ms | 0.005 ms | 1.500 ms | 1.525 ms |
+| 25 | Combo Breaker | 0.001 ms | 2.000 ms | - | 2.001 ms |
+| | **total** | | | | **3.526 ms** |
+"
+        );
+
+        assert_eq!(
+            report.render(Format::Json),
+            r#"{
+  "days": [
+    {"day": 1, "name": "Report Repair", "parse_ns": 20000, "part_1_ns": 5000, "part_2_ns": 1500000, "total_ns": 1525000},
+    {"day": 25, "name": "Combo Breaker", "parse_ns": 1000, "part_1_ns": 2000000, "part_2_ns": null, "total_ns": 2001000}
+  ],
+  "total_ns": 3526000
+}
+"#
+        );
+    }
+
+    #[test]
+    fn escaping() {
+        assert_eq!(json_string("Report Repair"), "\"Report Repair\"");
+        assert_eq!(json_string("a \"b\"\\c\nd\te"), r#""a \"b\"\\c\nd\u0009e""#);
+    }
+
+    #[test]
+    fn formats() {
+        assert_eq!(Format::from_str("json").unwrap(), Format::Json);
+        assert_eq!(Format::from_str("md").unwrap(), Format::Markdown);
+        assert!(Format::from_str("csv").is_err());
+    }
+}
@@ -1,18 +1,22 @@
 use crate::error::{AocError, Result};
+use crate::input::Input;
+use crate::solution::Solution;
+use crate::trace;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Forest {
     layout: Vec<Vec<char>>,
     width: usize,
 }
 
 impl Forest {
-    pub fn new(spec: &[String]) -> Result<Self> {
+    pub fn new<S: AsRef<str>>(spec: &[S]) -> Result<Self> {
         if spec.is_empty() {
             return Err(AocError::ForestDefinitionError("No rows".to_string()));
         }
 
         let mut layout: Vec<Vec<char>> = Vec::new();
-        let width = spec[0].len();
+        let width = spec[0].as_ref().len();
 
         if width < 1 {
             return Err(AocError::ForestDefinitionError(
@@ -20,19 +24,21 @@ impl Forest {
             ));
         }
 
-        for row in spec {
-            if row.len() != width {
+        for (index, row) in spec.iter().enumerate() {
+            if row.as_ref().len() != width {
                 return Err(AocError::ForestDefinitionError(
                     "Not all columns are the same length".to_string(),
-                ));
+                )
+                .locate(&spec[index..=index], index + 1));
             }
-            layout.push(row.chars().collect());
+            layout.push(row.as_ref().chars().collect());
         }
 
         Ok(Forest { layout, width })
     }
 
     pub fn traverse(&self, row: usize, col: usize) -> usize {
+        let _span = trace::span!("Forest::traverse");
         let mut count = 0;
         let mut c = 0;
         for r in (0..self.layout.len()).step_by(row) {
@@ -46,6 +52,31 @@ impl Forest {
     }
 }
 
+pub struct TobogganTrajectory;
+
+impl Solution for TobogganTrajectory {
+    const DAY: usize = 3;
+    const NAME: &'static str = "Toboggan Trajectory";
+
+    type Parsed = Forest;
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed> {
+        Forest::new(&input.lines())
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String> {
+        Ok(parsed.traverse(1, 3).to_string())
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String> {
+        Ok([(1, 1), (1, 3), (1, 5), (1, 7), (2, 1)]
+            .iter()
+            .map(|(row, col)| parsed.traverse(*row, *col))
+            .product::<usize>()
+            .to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -75,7 +106,7 @@ mod tests {
         let f = Forest::new(&spec());
         assert!(f.is_ok());
 
-        let f = Forest::new(&Vec::new());
+        let f = Forest::new(&Vec::<String>::new());
         assert!(f.is_err());
 
         let mut spec = spec();
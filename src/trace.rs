@@ -0,0 +1,353 @@
+//! Spans around parsing, each part and the main steps of a day, with the
+//! `tracing` feature. With `AOC_TRACE` set the binary prints where the
+//! time went:
+//!
+//! ```text
+//! $ AOC_TRACE=1 cargo run --release --features tracing -- time 20
+//! solve{day=20 name=Jurassic Jigsaw}  122.987 ms
+//! |- parse  4.258 ms
+//! |  `- Grid::from_input  4.192 ms
+//! ...
+//! ```
+//!
+//! Spans of the same name under one parent are merged, with a count. A span
+//! entered on another thread prints a tree of its own.
+
+use std::env;
+#[cfg(feature = "tracing")]
+use std::fmt::{self, Write};
+#[cfg(feature = "tracing")]
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "tracing")]
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "tracing")]
+use tracing::field::{Field, Visit};
+#[cfg(feature = "tracing")]
+use tracing::span::{Attributes, Id};
+#[cfg(feature = "tracing")]
+use tracing::{Event, Subscriber};
+#[cfg(feature = "tracing")]
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+#[cfg(feature = "tracing")]
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::error::{AocError, Result};
+
+/// Set this to print a timing tree of every solve to stderr. Needs the
+/// `tracing` feature.
+pub const TRACE_VAR: &str = "AOC_TRACE";
+
+/// Enter a span until the returned guard is dropped. Without the `tracing`
+/// feature the arguments are not even evaluated.
+#[cfg(feature = "tracing")]
+macro_rules! span {
+    ($($arg:tt)*) => {
+        tracing::info_span!($($arg)*).entered()
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! span {
+    ($($arg:tt)*) => {
+        $crate::trace::NoSpan
+    };
+}
+
+/// Record an event in the current span, or nothing without the `tracing`
+/// feature.
+macro_rules! event {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::info!($($arg)*);
+    };
+}
+
+pub(crate) use event;
+pub(crate) use span;
+
+/// What `span!` gives without the `tracing` feature.
+#[cfg(not(feature = "tracing"))]
+pub struct NoSpan;
+
+/// Print a timing tree of every solve to stderr if `AOC_TRACE` is set.
+/// Fails if it is set but aoc was built without the `tracing` feature.
+pub fn install_from_env() -> Result<()> {
+    if env::var(TRACE_VAR).map_or(true, |value| value.is_empty()) {
+        return Ok(());
+    }
+
+    #[cfg(feature = "tracing")]
+    {
+        let subscriber = tracing_subscriber::registry().with(TimingTree::new().printing());
+        tracing::subscriber::set_global_default(subscriber)
+            .map_err(|err| AocError::InvalidInput(err.to_string()))
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    Err(AocError::InvalidInput(format!(
+        "{} is set, but aoc was built without the tracing feature",
+        TRACE_VAR
+    )))
+}
+
+/// A span and everything that happened in it. Siblings with the same label
+/// are merged, so a span entered a thousand times is one node with a count
+/// of a thousand. Events are nodes with no time of their own.
+#[cfg(feature = "tracing")]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub label: String,
+    pub count: usize,
+    pub elapsed: Duration,
+    pub children: Vec<Node>,
+}
+
+#[cfg(feature = "tracing")]
+impl Node {
+    fn new(label: String) -> Self {
+        Node {
+            label,
+            count: 1,
+            elapsed: Duration::default(),
+            children: Vec::new(),
+        }
+    }
+
+    fn add(&mut self, child: Node) {
+        match self.children.iter_mut().find(|c| c.label == child.label) {
+            Some(existing) => {
+                existing.count += child.count;
+                existing.elapsed += child.elapsed;
+                for grandchild in child.children {
+                    existing.add(grandchild);
+                }
+            }
+            None => self.children.push(child),
+        }
+    }
+
+    /// The tree as indented lines, with the total time and count of each
+    /// span.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        self.render_into(&mut out, "", "");
+        out
+    }
+
+    fn render_into(&self, out: &mut String, first: &str, rest: &str) {
+        let _ = write!(out, "{}{}", first, self.label);
+        if self.count > 1 {
+            let _ = write!(out, " x{}", self.count);
+        }
+        if !self.label.starts_with('-') {
+            let _ = write!(out, "  {:.3} ms", self.elapsed.as_secs_f64() * 1000.0);
+        }
+        out.push('\n');
+
+        for (index, child) in self.children.iter().enumerate() {
+            if index + 1 == self.children.len() {
+                child.render_into(out, &format!("{}`- ", rest), &format!("{}   ", rest));
+            } else {
+                child.render_into(out, &format!("{}|- ", rest), &format!("{}|  ", rest));
+            }
+        }
+    }
+}
+
+/// A tracing layer that builds a tree of `Node`s for every root span, and
+/// keeps it or prints it to stderr when the span closes. Spans entered on
+/// other threads start trees of their own.
+#[cfg(feature = "tracing")]
+#[derive(Debug, Clone, Default)]
+pub struct TimingTree {
+    roots: Arc<Mutex<Vec<Node>>>,
+    print: bool,
+}
+
+#[cfg(feature = "tracing")]
+struct Open {
+    start: Instant,
+    node: Node,
+}
+
+#[cfg(feature = "tracing")]
+impl TimingTree {
+    /// A layer that keeps its trees for `roots`.
+    pub fn new() -> Self {
+        TimingTree::default()
+    }
+
+    /// A layer that prints its trees instead.
+    pub fn printing(mut self) -> Self {
+        self.print = true;
+        self
+    }
+
+    /// The trees of the root spans closed so far.
+    pub fn roots(&self) -> Vec<Node> {
+        self.roots.lock().expect("poisoned timing tree").clone()
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl<S> Layer<S> for TimingTree
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut label = Label::new(attrs.metadata().name());
+        attrs.record(&mut label);
+
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(Open {
+                start: Instant::now(),
+                node: Node::new(label.finish()),
+            });
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut label = Label::new("-");
+        event.record(&mut label);
+
+        if let Some(span) = ctx.event_span(event) {
+            if let Some(open) = span.extensions_mut().get_mut::<Open>() {
+                open.node.add(Node::new(label.finish()));
+            }
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let span = match ctx.span(&id) {
+            Some(span) => span,
+            None => return,
+        };
+        let open = match span.extensions_mut().remove::<Open>() {
+            Some(open) => open,
+            None => return,
+        };
+        let mut node = open.node;
+        node.elapsed = open.start.elapsed();
+
+        if let Some(parent) = span.parent() {
+            if let Some(open) = parent.extensions_mut().get_mut::<Open>() {
+                open.node.add(node);
+            }
+        } else if self.print {
+            eprint!("{}", node.render());
+        } else {
+            self.roots.lock().expect("poisoned timing tree").push(node);
+        }
+    }
+}
+
+/// Collects a span's name and fields, or an event's message and fields,
+/// into a label such as `part{part=1}`.
+#[cfg(feature = "tracing")]
+struct Label {
+    name: String,
+    fields: Vec<String>,
+}
+
+#[cfg(feature = "tracing")]
+impl Label {
+    fn new(name: &str) -> Self {
+        Label {
+            name: name.to_string(),
+            fields: Vec::new(),
+        }
+    }
+
+    fn finish(self) -> String {
+        if self.fields.is_empty() {
+            self.name
+        } else if self.name == "-" {
+            format!("- {}", self.fields.join(" "))
+        } else {
+            format!("{}{{{}}}", self.name, self.fields.join(" "))
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl Visit for Label {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record_debug(field, &format_args!("{}", value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.fields.insert(0, format!("{:?}", value));
+        } else {
+            self.fields.push(format!("{}={:?}", field.name(), value));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "tracing")]
+    use super::*;
+
+    #[cfg(not(feature = "tracing"))]
+    #[test]
+    fn disabled() {
+        // without the feature the arguments are never evaluated
+        let _span = span!("unused", value = unreachable!());
+        event!("never recorded {}", unreachable!());
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn timing_tree() {
+        let tree = TimingTree::new();
+        let subscriber = tracing_subscriber::registry().with(tree.clone());
+
+        tracing::subscriber::with_default(subscriber, || {
+            let _solve = span!("solve", day = 20);
+            for part in 1..=2 {
+                let _part = span!("part", part);
+                for _ in 0..3 {
+                    let _search = span!("search");
+                    event!(found = true, "arranged");
+                }
+            }
+        });
+
+        let roots = tree.roots();
+        assert_eq!(roots.len(), 1);
+
+        let solve = &roots[0];
+        assert_eq!(solve.label, "solve{day=20}");
+        assert_eq!(solve.children.len(), 2);
+        assert_eq!(solve.children[1].label, "part{part=2}");
+
+        let search = &solve.children[0].children[0];
+        assert_eq!((search.label.as_str(), search.count), ("search", 3));
+        assert_eq!(search.children[0].label, "- arranged found=true");
+        assert_eq!(search.children[0].count, 3);
+        assert!(solve.elapsed >= search.elapsed);
+
+        let rendered = solve.render();
+        let lines = rendered
+            .lines()
+            .map(|line| match line.rsplit_once("  ") {
+                Some((label, time)) if time.ends_with(" ms") => label,
+                _ => line,
+            })
+            .collect::<Vec<&str>>();
+        assert_eq!(
+            lines,
+            vec![
+                "solve{day=20}",
+                "|- part{part=1}",
+                "|  `- search x3",
+                "|     `- - arranged found=true x3",
+                "`- part{part=2}",
+                "   `- search x3",
+                "      `- - arranged found=true x3",
+            ]
+        );
+    }
+}
@@ -1,9 +1,31 @@
+//! Reading and parsing input lines. Errors from `parse_lines` and
+//! `parse_blocks` carry the line and column they came from, which the binary
+//! prints under the offending line:
+//!
+//! ```text
+//! error: Unknown operation: 'add'
+//!  --> path/to/input:4:1
+//!   |
+//! 4 | add +3
+//!   | ^^^
+//! ```
+//!
+//! `collect_lines` and `collect_blocks` carry on past bad lines, to report
+//! them all at once. `load_example` returns a fenced example from a day's
+//! `README.md`, so tests do not retype them.
+
+use std::env;
+use std::ffi::OsString;
 use std::fs::{self, File};
 use std::io::{BufRead, BufReader};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use crate::error::{AocError, Result};
 
+/// Set this to look for day directories somewhere other than `examples` in
+/// the current directory.
+pub const EXAMPLES_DIR_VAR: &str = "AOC_EXAMPLES_DIR";
+
 pub fn load_lines(file: &str) -> Result<Vec<String>> {
     let mut lines = Vec::new();
     for line in BufReader::new(File::open(Path::new(file))?).lines() {
@@ -21,17 +43,41 @@ pub fn load_input(day: &str) -> Result<Vec<String>> {
 }
 
 pub fn load_named_input(day: &str, name: &str) -> Result<Vec<String>> {
+    match named_input_path(day, name)?.to_str() {
+        Some(file) => load_lines(file),
+        None => Err(AocError::InputError(format!(
+            "Could not find or load input for {}: '{}'",
+            day, name
+        ))),
+    }
+}
+
+pub fn examples_dir() -> PathBuf {
+    examples_dir_from(env::var_os(EXAMPLES_DIR_VAR))
+}
+
+fn examples_dir_from(var: Option<OsString>) -> PathBuf {
+    var.filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("examples"))
+}
+
+pub fn named_input_path(day: &str, name: &str) -> Result<PathBuf> {
     //
     // examples/003_toboggan-trajectory/<name>
     //
-    let examples_dir = Path::new("examples");
-    for entry in fs::read_dir(examples_dir)? {
+    let examples_dir = examples_dir();
+    let entries = fs::read_dir(&examples_dir).map_err(|e| {
+        AocError::InputError(format!(
+            "Could not read examples directory {:?} ({}), set {} to override: {}",
+            examples_dir, day, EXAMPLES_DIR_VAR, e
+        ))
+    })?;
+    for entry in entries {
         let entry = entry?;
         let path = entry.path();
         if path.is_dir() && entry.file_name().into_string()?.starts_with(day) {
-            if let Some(file) = path.join(name).to_str() {
-                return load_lines(file);
-            }
+            return Ok(path.join(name));
         }
     }
     Err(AocError::InputError(format!(
@@ -40,6 +86,168 @@ pub fn load_named_input(day: &str, name: &str) -> Result<Vec<String>> {
     )))
 }
 
+pub fn load_example(day: &str, index: usize) -> Result<Vec<String>> {
+    //
+    // the index-th fenced code block of examples/003_toboggan-trajectory/README.md
+    //
+    example_blocks(&load_named_input(day, "README.md")?)
+        .into_iter()
+        .nth(index)
+        .ok_or_else(|| {
+            AocError::InputError(format!("No example {} in the README for {}", index, day))
+        })
+}
+
+/// Collect the contents of every ``` fenced block in a README, in order.
+/// An unterminated block runs to the end of the file.
+pub fn example_blocks(lines: &[String]) -> Vec<Vec<String>> {
+    let mut blocks = Vec::new();
+    let mut current: Option<Vec<String>> = None;
+
+    for line in lines {
+        if line.trim_start().starts_with("```") {
+            match current.take() {
+                Some(block) => blocks.push(block),
+                None => current = Some(Vec::new()),
+            }
+        } else if let Some(ref mut block) = current {
+            block.push(line.to_string());
+        }
+    }
+
+    if let Some(block) = current {
+        blocks.push(block);
+    }
+
+    blocks
+}
+
+/// Parse each line with `parse`. An error is tagged with the line and column
+/// it came from, counting the first line as line 1.
+pub fn parse_lines<S, T, F>(lines: &[S], mut parse: F) -> Result<Vec<T>>
+where
+    S: AsRef<str>,
+    F: FnMut(&str) -> Result<T>,
+{
+    lines
+        .iter()
+        .enumerate()
+        .map(|(index, line)| {
+            parse(line.as_ref()).map_err(|e| e.locate(&lines[index..=index], index + 1))
+        })
+        .collect()
+}
+
+/// What parsing in collect-all mode makes of an input: whatever could be
+/// built from the lines that parsed, and the located error of every line
+/// that did not.
+#[derive(Debug)]
+pub struct Collected<T> {
+    pub parsed: T,
+    pub errors: Vec<AocError>,
+}
+
+impl<T> Collected<T> {
+    pub fn is_clean(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// The line numbers of the errors, in input order.
+    pub fn error_lines(&self) -> Vec<usize> {
+        self.errors
+            .iter()
+            .filter_map(|e| e.span().map(|span| span.line))
+            .collect()
+    }
+
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Collected<U> {
+        Collected {
+            parsed: f(self.parsed),
+            errors: self.errors,
+        }
+    }
+
+    /// Back to strict parsing: the first error, if there was one.
+    pub fn into_result(self) -> Result<T> {
+        match self.errors.into_iter().next() {
+            Some(e) => Err(e),
+            None => Ok(self.parsed),
+        }
+    }
+}
+
+/// Like `parse_lines`, but keep going after a bad line and return every
+/// item that parsed along with every error.
+pub fn collect_lines<S, T, F>(lines: &[S], mut parse: F) -> Collected<Vec<T>>
+where
+    S: AsRef<str>,
+    F: FnMut(&str) -> Result<T>,
+{
+    let mut collected = Collected {
+        parsed: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    for (index, line) in lines.iter().enumerate() {
+        match parse(line.as_ref()) {
+            Ok(item) => collected.parsed.push(item),
+            Err(e) => collected
+                .errors
+                .push(e.locate(&lines[index..=index], index + 1)),
+        }
+    }
+
+    collected
+}
+
+/// Like `parse_blocks`, but keep going after a bad block and return every
+/// item that parsed along with every error.
+pub fn collect_blocks<S, T, F>(lines: &[S], mut parse: F) -> Collected<Vec<T>>
+where
+    S: AsRef<str>,
+    F: FnMut(&[S]) -> Result<T>,
+{
+    let mut collected = Collected {
+        parsed: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    for (first_line, block) in numbered_blocks(lines) {
+        match parse(block) {
+            Ok(item) => collected.parsed.push(item),
+            Err(e) => collected.errors.push(e.locate(block, first_line)),
+        }
+    }
+
+    collected
+}
+
+/// Parse each group of lines between blank lines with `parse`. An error is
+/// tagged with the line and column it came from within the whole input.
+pub fn parse_blocks<S, T, F>(lines: &[S], mut parse: F) -> Result<Vec<T>>
+where
+    S: AsRef<str>,
+    F: FnMut(&[S]) -> Result<T>,
+{
+    numbered_blocks(lines)
+        .map(|(first_line, block)| parse(block).map_err(|e| e.locate(block, first_line)))
+        .collect()
+}
+
+/// The non-empty groups of lines between blank lines, with the line number
+/// each group starts on.
+pub fn numbered_blocks<S: AsRef<str>>(lines: &[S]) -> impl Iterator<Item = (usize, &[S])> {
+    let mut start = 0;
+    lines
+        .split(|line| line.as_ref().trim().is_empty())
+        .map(move |block| {
+            let first_line = start + 1;
+            start += block.len() + 1;
+            (first_line, block)
+        })
+        .filter(|(_, block)| !block.is_empty())
+}
+
 pub fn test_input(input: &str) -> Vec<String> {
     // TODO: figure out if trim is the right thing to do - MCL - 2020-12-10
     input
@@ -49,9 +257,41 @@ pub fn test_input(input: &str) -> Vec<String> {
         .collect()
 }
 
+/// Serialize a map as a list of key and value pairs, for maps keyed by
+/// something JSON cannot use as an object key. Use it with
+/// `#[serde(with = "crate::util::pairs")]`.
+#[cfg(feature = "serde")]
+pub mod pairs {
+    use std::collections::HashMap;
+    use std::hash::Hash;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<K, V, S>(map: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        K: Serialize,
+        V: Serialize,
+        S: Serializer,
+    {
+        serializer.collect_seq(map.iter())
+    }
+
+    pub fn deserialize<'de, K, V, D>(deserializer: D) -> Result<HashMap<K, V>, D::Error>
+    where
+        K: Deserialize<'de> + Eq + Hash,
+        V: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        Ok(Vec::<(K, V)>::deserialize(deserializer)?
+            .into_iter()
+            .collect())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::span::Span;
 
     #[test]
     fn test_input_conversion() {
@@ -78,4 +318,178 @@ mod tests {
         ";
         assert_eq!(test_input(input), expected);
     }
+
+    #[test]
+    fn extracting_example_blocks() {
+        let readme = test_input(
+            "
+            For example:
+            ```
+            1721
+            979
+            ```
+            Some text
+
+            ```text
+            a
+
+            b
+            ```
+            ```
+            unterminated
+            ",
+        );
+
+        assert_eq!(
+            example_blocks(&readme),
+            vec![
+                vec!["1721".to_string(), "979".to_string()],
+                vec!["a".to_string(), "".to_string(), "b".to_string()],
+                vec!["unterminated".to_string()],
+            ]
+        );
+        assert!(example_blocks(&test_input("no blocks")).is_empty());
+    }
+
+    #[test]
+    fn parsing_lines() {
+        let lines = test_input(
+            "
+            1
+            2
+            x3
+            ",
+        );
+
+        assert_eq!(
+            parse_lines(&lines[..2], |line| Ok(line.parse::<usize>()?)).unwrap(),
+            vec![1, 2]
+        );
+
+        let err = parse_lines(&lines, |line| {
+            line.parse::<usize>()
+                .map_err(|_| AocError::InvalidInput(format!("Not a number: '{}'", line)))
+        })
+        .unwrap_err();
+        assert_eq!(err.span().unwrap(), &Span::new(3, 1, 2, "x3"));
+        assert_eq!(
+            err.report(),
+            "Invalid input: 'Not a number: 'x3''\n --> line 3, column 1\n  |\n3 | x3\n  | ^^"
+        );
+    }
+
+    #[test]
+    fn parsing_blocks() {
+        let lines = test_input(
+            "
+            a
+
+
+            b c
+            d e
+
+            f
+            g x
+            ",
+        );
+
+        let err = parse_blocks(&lines, |block| {
+            parse_lines(block, |line| {
+                if line.contains('x') {
+                    Err(AocError::InvalidInput("x".to_string()))
+                } else {
+                    Ok(line.len())
+                }
+            })
+        })
+        .unwrap_err();
+        assert_eq!(err.span().unwrap(), &Span::new(8, 3, 1, "g x"));
+
+        let err = parse_blocks(&lines, |block| {
+            if block.len() == 2 {
+                Err(AocError::InvalidInput("d".to_string()))
+            } else {
+                Ok(block.len())
+            }
+        })
+        .unwrap_err();
+        assert_eq!(err.span().unwrap(), &Span::new(5, 1, 1, "d e"));
+
+        let counts = parse_blocks(&lines, |block| Ok(block.len())).unwrap();
+        assert_eq!(counts, vec![1, 2, 2]);
+    }
+
+    #[test]
+    fn collecting_lines() {
+        let lines = test_input(
+            "
+            1
+            x2
+            3
+            4y
+            ",
+        );
+
+        let collected = collect_lines(&lines, |line| Ok(line.parse::<usize>()?));
+        assert_eq!(collected.parsed, vec![1, 3]);
+        assert_eq!(collected.error_lines(), vec![2, 4]);
+        assert!(!collected.is_clean());
+
+        let first = collect_lines(&lines, |line| Ok(line.parse::<usize>()?))
+            .into_result()
+            .unwrap_err();
+        assert_eq!(first.span().unwrap().line, 2);
+
+        let collected = collect_lines(&lines[..1], |line| Ok(line.parse::<usize>()?));
+        assert!(collected.is_clean());
+        assert_eq!(collected.map(|items| items.len()).into_result().unwrap(), 1);
+    }
+
+    #[test]
+    fn collecting_blocks() {
+        let lines = test_input(
+            "
+            a
+            b
+
+            c x
+
+            d
+            e x
+            ",
+        );
+
+        let collected = collect_blocks(&lines, |block| {
+            parse_lines(block, |line| {
+                if line.contains('x') {
+                    Err(AocError::InvalidInput("x".to_string()))
+                } else {
+                    Ok(line.len())
+                }
+            })
+        });
+        assert_eq!(collected.parsed, vec![vec![1, 1]]);
+        assert_eq!(collected.error_lines(), vec![4, 7]);
+        assert_eq!(collected.errors[1].span().unwrap().column, 3);
+    }
+
+    #[test]
+    fn examples_dir_override() {
+        assert_eq!(examples_dir_from(None), PathBuf::from("examples"));
+        assert_eq!(
+            examples_dir_from(Some(OsString::new())),
+            PathBuf::from("examples")
+        );
+        assert_eq!(
+            examples_dir_from(Some(OsString::from("/tmp/aoc"))),
+            PathBuf::from("/tmp/aoc")
+        );
+    }
+
+    #[test]
+    fn loading_examples() {
+        assert_eq!(load_example("001", 0).unwrap()[0], "1721");
+        assert_eq!(load_example("010", 1).unwrap()[0], "28");
+        assert!(load_example("001", 1).is_err());
+    }
 }
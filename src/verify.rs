@@ -0,0 +1,296 @@
+//! Checks answers against those recorded in each day's `answers` file.
+//! A new day is recorded with
+//!
+//! ```text
+//! cargo run --release -- run 16 > examples/016_ticket-translation/answers
+//! ```
+//!
+//! and `aoc verify` then exits non-zero if any answer changes or a part
+//! fails.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+use crate::error::{AocError, Result};
+use crate::input::Input;
+use crate::solution::{Part, Solver};
+use crate::util::load_named_input;
+
+/// Answers holds the known correct answers for a day, as recorded in
+/// examples/NNN_<name>/answers. The file uses the same `part N: <answer>`
+/// lines that `aoc run` prints, so it can be recorded by redirecting that
+/// output. Lines of the form `example I part N: <answer>` record the answers
+/// for the I-th example block of the day's README.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Answers {
+    answers: HashMap<Part, String>,
+    examples: BTreeMap<usize, Answers>,
+}
+
+impl Answers {
+    pub fn from_input(input: &[String]) -> Result<Self> {
+        let mut answers = Answers::default();
+
+        for line in input.iter().filter(|line| !line.trim().is_empty()) {
+            let invalid = || AocError::AnswerFileError(line.to_string());
+
+            let mut parts = line.splitn(2, ':');
+            let mut label = parts.next().ok_or_else(invalid)?.trim();
+            let answer = parts
+                .next()
+                .map(|answer| answer.trim())
+                .filter(|answer| !answer.is_empty())
+                .ok_or_else(invalid)?;
+
+            let mut target = &mut answers;
+            if let Some(rest) = label.strip_prefix("example ") {
+                let mut words = rest.splitn(2, ' ');
+                let index = words.next().ok_or_else(invalid)?.parse::<usize>()?;
+                label = words.next().ok_or_else(invalid)?;
+                target = target.examples.entry(index).or_default();
+            }
+
+            let part = label
+                .strip_prefix("part ")
+                .ok_or_else(invalid)
+                .and_then(Part::from_str)?;
+
+            if target.answers.insert(part, answer.to_string()).is_some() {
+                return Err(AocError::AnswerFileError(format!(
+                    "{} recorded more than once: '{}'",
+                    part, line
+                )));
+            }
+        }
+
+        Ok(answers)
+    }
+
+    /// Load the answers file for a day. A day without an answers file simply
+    /// has no known answers.
+    pub fn load(day: usize) -> Result<Self> {
+        match load_named_input(&format!("{:03}", day), "answers") {
+            Ok(lines) => Self::from_input(&lines),
+            Err(AocError::IOError(ref e)) if e.kind() == io::ErrorKind::NotFound => {
+                Ok(Self::default())
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn get(&self, part: Part) -> Option<&str> {
+        self.answers.get(&part).map(|answer| answer.as_ref())
+    }
+
+    /// The answers recorded for README examples, by example index.
+    pub fn examples(&self) -> impl Iterator<Item = (usize, &Answers)> {
+        self.examples
+            .iter()
+            .map(|(index, answers)| (*index, answers))
+    }
+}
+
+/// The result of comparing one part against its recorded answer.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Outcome {
+    Match,
+    Mismatch { expected: String, actual: String },
+    Missing { actual: String },
+    Failed(String),
+}
+
+impl Outcome {
+    pub fn check(expected: Option<&str>, actual: Result<String>) -> Self {
+        match (expected, actual) {
+            (_, Err(e)) => Outcome::Failed(e.to_string()),
+            (None, Ok(actual)) => Outcome::Missing { actual },
+            (Some(expected), Ok(actual)) if expected == actual => Outcome::Match,
+            (Some(expected), Ok(actual)) => Outcome::Mismatch {
+                expected: expected.to_string(),
+                actual,
+            },
+        }
+    }
+
+    /// Whether this outcome should fail a verification run. Missing answers
+    /// are reported but do not count as failures.
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Outcome::Mismatch { .. } | Outcome::Failed(_))
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Match => write!(f, "ok"),
+            Outcome::Mismatch { expected, actual } => {
+                write!(f, "MISMATCH expected {}, got {}", expected, actual)
+            }
+            Outcome::Missing { actual } => write!(f, "missing answer, got {}", actual),
+            Outcome::Failed(e) => write!(f, "FAILED {}", e),
+        }
+    }
+}
+
+/// Run every part of a solution and compare the results against the answers.
+pub fn verify(solution: &dyn Solver, input: &Input, answers: &Answers) -> Vec<(Part, Outcome)> {
+    let parsed = solution.parse_input(input);
+
+    solution
+        .parts()
+        .iter()
+        .map(|part| {
+            let outcome = match parsed {
+                Ok(ref parsed) => Outcome::check(
+                    answers.get(*part),
+                    solution.solve_part(parsed.as_ref(), *part),
+                ),
+                Err(ref e) => Outcome::Failed(e.to_string()),
+            };
+            (*part, outcome)
+        })
+        .collect()
+}
+
+/// Tallies of outcomes over a verification run.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct Summary {
+    pub matched: usize,
+    pub mismatched: usize,
+    pub missing: usize,
+    pub failed: usize,
+}
+
+impl Summary {
+    pub fn record(&mut self, outcome: &Outcome) {
+        match outcome {
+            Outcome::Match => self.matched += 1,
+            Outcome::Mismatch { .. } => self.mismatched += 1,
+            Outcome::Missing { .. } => self.missing += 1,
+            Outcome::Failed(_) => self.failed += 1,
+        }
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.mismatched == 0 && self.failed == 0
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ok, {} mismatched, {} missing, {} failed",
+            self.matched, self.mismatched, self.missing, self.failed
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution;
+    use crate::util::test_input;
+
+    #[test]
+    fn parsing_answers() {
+        let input = test_input(
+            "
+            part 1: 514579
+            part 2: 241861950
+            ",
+        );
+
+        let answers = Answers::from_input(&input).unwrap();
+        assert_eq!(answers.get(Part::One), Some("514579"));
+        assert_eq!(answers.get(Part::Two), Some("241861950"));
+
+        let answers = Answers::from_input(&test_input("part 1: a,b: c")).unwrap();
+        assert_eq!(answers.get(Part::One), Some("a,b: c"));
+        assert_eq!(answers.get(Part::Two), None);
+
+        let answers = Answers::from_input(&test_input(
+            "
+            part 1: 1
+            example 0 part 1: 2
+            example 0 part 2: 3
+            example 2 part 1: 4
+            ",
+        ))
+        .unwrap();
+        assert_eq!(answers.get(Part::One), Some("1"));
+        let examples = answers.examples().collect::<Vec<(usize, &Answers)>>();
+        assert_eq!(examples.len(), 2);
+        assert_eq!(examples[0].0, 0);
+        assert_eq!(examples[0].1.get(Part::Two), Some("3"));
+        assert_eq!(examples[1].0, 2);
+        assert_eq!(examples[1].1.get(Part::One), Some("4"));
+
+        assert!(Answers::from_input(&test_input("514579")).is_err());
+        assert!(Answers::from_input(&test_input("example part 1: 1")).is_err());
+        assert!(Answers::from_input(&test_input("example a part 1: 1")).is_err());
+        assert!(Answers::from_input(&test_input("part 3: 514579")).is_err());
+        assert!(Answers::from_input(&test_input("part 1:")).is_err());
+        assert!(Answers::from_input(&test_input("part 1: 1\npart 1: 2")).is_err());
+    }
+
+    #[test]
+    fn checking() {
+        assert_eq!(
+            Outcome::check(Some("1"), Ok("1".to_string())),
+            Outcome::Match
+        );
+        assert_eq!(
+            Outcome::check(Some("1"), Ok("2".to_string())),
+            Outcome::Mismatch {
+                expected: "1".to_string(),
+                actual: "2".to_string()
+            }
+        );
+        assert_eq!(
+            Outcome::check(None, Ok("2".to_string())),
+            Outcome::Missing {
+                actual: "2".to_string()
+            }
+        );
+        assert!(Outcome::check(Some("1"), Err(AocError::NoOutlier)).is_failure());
+        assert!(!Outcome::check(None, Ok("2".to_string())).is_failure());
+    }
+
+    #[test]
+    fn verifying() {
+        let input = test_input(
+            "
+            1721
+            979
+            366
+            299
+            675
+            1456
+            ",
+        );
+        let answers = Answers::from_input(&test_input("part 1: 514579\npart 2: 1")).unwrap();
+
+        let outcomes = verify(
+            solution::get(1).unwrap(),
+            &Input::from_lines(&input),
+            &answers,
+        );
+
+        let mut summary = Summary::default();
+        outcomes
+            .iter()
+            .for_each(|(_, outcome)| summary.record(outcome));
+
+        assert_eq!(outcomes[0], (Part::One, Outcome::Match));
+        assert!(outcomes[1].1.is_failure());
+        assert_eq!(summary.matched, 1);
+        assert_eq!(summary.mismatched, 1);
+        assert!(!summary.is_success());
+
+        let outcomes = verify(solution::get(1).unwrap(), &Input::from("foo"), &answers);
+        assert!(outcomes.iter().all(|(_, outcome)| outcome.is_failure()));
+    }
+}
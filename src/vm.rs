@@ -0,0 +1,898 @@
+//! A machine for day 8's boot code and programs like it: an instruction
+//! pointer, named registers and a count of the cycles run.
+//!
+//! The handheld only knows `acc`, `jmp` and `nop`. The standard
+//! `InstructionSet` adds conditional jumps, register arithmetic and `halt`:
+//!
+//! ```text
+//! mov a +6
+//! mov b +7
+//! acc a
+//! subr b +1
+//! jnz b -2
+//! ```
+//!
+//! An opcode is anything that implements `Opcode`, and
+//! `InstructionSet::with` adds one to a set. `Vm::run` checks the current
+//! `Budget`, so a program that never halts can still be stopped.
+
+use std::fmt;
+use std::ops::Range;
+use std::sync::{Arc, OnceLock};
+
+use crate::budget::Budget;
+use crate::console::{Instruction, Op, Operand, Program};
+use crate::error::{AocError, Result};
+use crate::span::Span;
+use crate::util::{collect_lines, parse_lines, Collected};
+
+/// The register the handheld's `acc` instruction adds to. Every machine has
+/// it, whatever else its program uses.
+pub const ACC: &str = "acc";
+
+/// What an operand of an opcode may be.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Kind {
+    /// A register or a number.
+    Value,
+    /// A number, and only a number.
+    Number,
+    /// A register, such as the one an arithmetic opcode writes to.
+    Register,
+    /// A number of instructions to jump by, from the current one.
+    Offset,
+}
+
+/// An operand once loaded into a machine, with any register resolved to its
+/// place among the machine's registers.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Arg {
+    Value(i64),
+    Register(usize),
+}
+
+/// Where execution goes after an instruction.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Flow {
+    Next,
+    Jump(i64),
+    Halt,
+}
+
+/// An operation a machine can run. Opcodes are registered with an
+/// `InstructionSet`, which parses and loads the instructions using them.
+pub trait Opcode: Send + Sync {
+    /// The mnemonic, e.g. `jmp`.
+    fn name(&self) -> &'static str;
+
+    /// The operands the opcode takes, checked when parsing and loading.
+    fn operands(&self) -> &'static [Kind];
+
+    fn execute(&self, registers: &mut Registers, args: &[Arg]) -> Result<Flow>;
+
+    /// Everywhere control may go after an instruction with these operands,
+    /// for analysing a program without running it.
+    fn targets(&self, args: &[Operand]) -> Vec<Flow>;
+}
+
+/// An opcode made from a function, which is how the standard ones are
+/// defined and usually the easiest way to add one.
+#[derive(Clone, Copy)]
+pub struct FnOpcode {
+    pub name: &'static str,
+    pub operands: &'static [Kind],
+    pub run: fn(&mut Registers, &[Arg]) -> Result<Flow>,
+    /// One of `falls_through`, `jumps`, `branches` and `halts` will usually
+    /// do.
+    pub targets: fn(&[Operand]) -> Vec<Flow>,
+}
+
+impl Opcode for FnOpcode {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn operands(&self) -> &'static [Kind] {
+        self.operands
+    }
+
+    fn execute(&self, registers: &mut Registers, args: &[Arg]) -> Result<Flow> {
+        (self.run)(registers, args)
+    }
+
+    fn targets(&self, args: &[Operand]) -> Vec<Flow> {
+        (self.targets)(args)
+    }
+}
+
+/// Targets of an opcode that always goes on to the next instruction.
+pub fn falls_through(_: &[Operand]) -> Vec<Flow> {
+    vec![Flow::Next]
+}
+
+/// Targets of an opcode that always jumps by its last operand.
+pub fn jumps(args: &[Operand]) -> Vec<Flow> {
+    offset(args).into_iter().collect()
+}
+
+/// Targets of an opcode that may jump by its last operand.
+pub fn branches(args: &[Operand]) -> Vec<Flow> {
+    let mut targets = vec![Flow::Next];
+    targets.extend(offset(args));
+    targets
+}
+
+/// Targets of an opcode that stops the machine.
+pub fn halts(_: &[Operand]) -> Vec<Flow> {
+    vec![Flow::Halt]
+}
+
+fn offset(args: &[Operand]) -> Option<Flow> {
+    match args.last() {
+        Some(Operand::Value(offset)) => Some(Flow::Jump(*offset)),
+        _ => None,
+    }
+}
+
+/// The handheld's own `acc`, `jmp` and `nop`, which only take numbers.
+const HANDHELD: &[FnOpcode] = &[
+    FnOpcode {
+        name: "acc",
+        operands: &[Kind::Number],
+        run: acc,
+        targets: falls_through,
+    },
+    FnOpcode {
+        name: "jmp",
+        operands: &[Kind::Offset],
+        run: jmp,
+        targets: jumps,
+    },
+    FnOpcode {
+        name: "nop",
+        operands: &[Kind::Number],
+        run: nop,
+        targets: falls_through,
+    },
+];
+
+/// The handheld's `acc`, `jmp` and `nop`, taking registers as well, plus:
+///
+/// - `jz x +n` and `jnz x +n` jump if `x` is or is not zero
+/// - `mov r x`, `addr r x`, `subr r x` and `mulr r x` set, add to, subtract
+///   from and multiply register `r` by `x`
+/// - `halt` stops the machine
+const STANDARD: &[FnOpcode] = &[
+    FnOpcode {
+        name: "acc",
+        operands: &[Kind::Value],
+        run: acc,
+        targets: falls_through,
+    },
+    FnOpcode {
+        name: "jmp",
+        operands: &[Kind::Offset],
+        run: jmp,
+        targets: jumps,
+    },
+    FnOpcode {
+        name: "nop",
+        operands: &[Kind::Value],
+        run: nop,
+        targets: falls_through,
+    },
+    FnOpcode {
+        name: "jz",
+        operands: &[Kind::Value, Kind::Offset],
+        run: jz,
+        targets: branches,
+    },
+    FnOpcode {
+        name: "jnz",
+        operands: &[Kind::Value, Kind::Offset],
+        run: jnz,
+        targets: branches,
+    },
+    FnOpcode {
+        name: "mov",
+        operands: &[Kind::Register, Kind::Value],
+        run: mov,
+        targets: falls_through,
+    },
+    FnOpcode {
+        name: "addr",
+        operands: &[Kind::Register, Kind::Value],
+        run: addr,
+        targets: falls_through,
+    },
+    FnOpcode {
+        name: "subr",
+        operands: &[Kind::Register, Kind::Value],
+        run: subr,
+        targets: falls_through,
+    },
+    FnOpcode {
+        name: "mulr",
+        operands: &[Kind::Register, Kind::Value],
+        run: mulr,
+        targets: falls_through,
+    },
+    FnOpcode {
+        name: "halt",
+        operands: &[],
+        run: halt,
+        targets: halts,
+    },
+];
+
+fn acc(registers: &mut Registers, args: &[Arg]) -> Result<Flow> {
+    let value = checked(registers.acc().checked_add(registers.value(args[0])), "acc")?;
+    registers.values[0] = value;
+    Ok(Flow::Next)
+}
+
+fn jmp(registers: &mut Registers, args: &[Arg]) -> Result<Flow> {
+    Ok(Flow::Jump(registers.value(args[0])))
+}
+
+fn nop(_: &mut Registers, _: &[Arg]) -> Result<Flow> {
+    Ok(Flow::Next)
+}
+
+fn jz(registers: &mut Registers, args: &[Arg]) -> Result<Flow> {
+    match registers.value(args[0]) {
+        0 => Ok(Flow::Jump(registers.value(args[1]))),
+        _ => Ok(Flow::Next),
+    }
+}
+
+fn jnz(registers: &mut Registers, args: &[Arg]) -> Result<Flow> {
+    match registers.value(args[0]) {
+        0 => Ok(Flow::Next),
+        _ => Ok(Flow::Jump(registers.value(args[1]))),
+    }
+}
+
+fn mov(registers: &mut Registers, args: &[Arg]) -> Result<Flow> {
+    registers.write(args[0], registers.value(args[1]))?;
+    Ok(Flow::Next)
+}
+
+fn addr(registers: &mut Registers, args: &[Arg]) -> Result<Flow> {
+    let value = registers
+        .value(args[0])
+        .checked_add(registers.value(args[1]));
+    registers.write(args[0], checked(value, "addr")?)?;
+    Ok(Flow::Next)
+}
+
+fn subr(registers: &mut Registers, args: &[Arg]) -> Result<Flow> {
+    let value = registers
+        .value(args[0])
+        .checked_sub(registers.value(args[1]));
+    registers.write(args[0], checked(value, "subr")?)?;
+    Ok(Flow::Next)
+}
+
+fn mulr(registers: &mut Registers, args: &[Arg]) -> Result<Flow> {
+    let value = registers
+        .value(args[0])
+        .checked_mul(registers.value(args[1]));
+    registers.write(args[0], checked(value, "mulr")?)?;
+    Ok(Flow::Next)
+}
+
+fn halt(_: &mut Registers, _: &[Arg]) -> Result<Flow> {
+    Ok(Flow::Halt)
+}
+
+/// The result of checked arithmetic, or an error if it overflowed. Programs
+/// may hold any number, so this is bad input rather than a bug.
+fn checked(value: Option<i64>, name: &str) -> Result<i64> {
+    value.ok_or_else(|| AocError::InvalidProgram(format!("{} overflowed", name)))
+}
+
+/// Named registers, starting with `acc`. Others are added as the
+/// instructions using them are loaded, in the order they first appear.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Registers {
+    names: Vec<String>,
+    values: Vec<i64>,
+}
+
+impl Default for Registers {
+    fn default() -> Self {
+        Registers::new()
+    }
+}
+
+impl Registers {
+    pub fn new() -> Self {
+        Registers {
+            names: vec![ACC.to_string()],
+            values: vec![0],
+        }
+    }
+
+    pub fn acc(&self) -> i64 {
+        self.values[0]
+    }
+
+    pub fn get(&self, name: &str) -> Option<i64> {
+        self.names
+            .iter()
+            .position(|n| n == name)
+            .map(|index| self.values[index])
+    }
+
+    /// Set a register, adding it if there is none of that name.
+    pub fn set(&mut self, name: &str, value: i64) {
+        let index = self.index(name);
+        self.values[index] = value;
+    }
+
+    /// The registers and their values, `acc` first.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, i64)> {
+        self.names
+            .iter()
+            .map(String::as_str)
+            .zip(self.values.iter().cloned())
+    }
+
+    pub fn value(&self, arg: Arg) -> i64 {
+        match arg {
+            Arg::Value(value) => value,
+            Arg::Register(index) => self.values[index],
+        }
+    }
+
+    pub fn write(&mut self, arg: Arg, value: i64) -> Result<()> {
+        match arg {
+            Arg::Register(index) => {
+                self.values[index] = value;
+                Ok(())
+            }
+            Arg::Value(_) => Err(AocError::InvalidInstruction(
+                "Cannot write to a number".to_string(),
+            )),
+        }
+    }
+
+    fn index(&mut self, name: &str) -> usize {
+        match self.names.iter().position(|n| n == name) {
+            Some(index) => index,
+            None => {
+                self.names.push(name.to_string());
+                self.values.push(0);
+                self.names.len() - 1
+            }
+        }
+    }
+
+    fn load(&mut self, operand: &Operand) -> Arg {
+        match *operand {
+            Operand::Value(value) => Arg::Value(value),
+            Operand::Register(ref name) => Arg::Register(self.index(name)),
+        }
+    }
+}
+
+/// The opcodes a program may use. New ones are added with `with`, and
+/// replace any existing opcode of the same name.
+///
+/// Sets are cheap to clone, sharing their opcodes until one is added.
+#[derive(Clone)]
+pub struct InstructionSet {
+    opcodes: Arc<Vec<Arc<dyn Opcode>>>,
+}
+
+static HANDHELD_SET: OnceLock<InstructionSet> = OnceLock::new();
+static STANDARD_SET: OnceLock<InstructionSet> = OnceLock::new();
+
+impl fmt::Debug for InstructionSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.opcodes.iter().map(|opcode| opcode.name()))
+            .finish()
+    }
+}
+
+impl Default for InstructionSet {
+    fn default() -> Self {
+        InstructionSet::standard()
+    }
+}
+
+impl InstructionSet {
+    pub fn empty() -> Self {
+        InstructionSet {
+            opcodes: Arc::new(Vec::new()),
+        }
+    }
+
+    /// The opcodes described on `HANDHELD`, which is all day 8's boot code
+    /// may use.
+    pub fn handheld() -> Self {
+        HANDHELD_SET
+            .get_or_init(|| InstructionSet::of(HANDHELD))
+            .clone()
+    }
+
+    /// The opcodes described on `STANDARD`.
+    pub fn standard() -> Self {
+        STANDARD_SET
+            .get_or_init(|| InstructionSet::of(STANDARD))
+            .clone()
+    }
+
+    fn of(opcodes: &[FnOpcode]) -> Self {
+        opcodes
+            .iter()
+            .fold(InstructionSet::empty(), |set, opcode| set.with(*opcode))
+    }
+
+    pub fn with<O: Opcode + 'static>(mut self, opcode: O) -> Self {
+        let opcode: Arc<dyn Opcode> = Arc::new(opcode);
+        let index = self.position(opcode.name());
+        let opcodes = Arc::make_mut(&mut self.opcodes);
+        match index {
+            Some(index) => opcodes[index] = opcode,
+            None => opcodes.push(opcode),
+        }
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn Opcode> {
+        self.position(name)
+            .map(|index| self.opcodes[index].as_ref())
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.opcodes.iter().map(|opcode| opcode.name()).collect()
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.opcodes.iter().position(|opcode| opcode.name() == name)
+    }
+
+    /// Parse a line such as `jnz a -3`. Registers are names and numbers
+    /// are signed, as in `acc +1`.
+    pub fn instruction(&self, input: &str) -> Result<Instruction> {
+        self.parse(input, None)
+    }
+
+    /// As `instruction`, but a name where a jump offset belongs is a label,
+    /// which `label` turns into the offset to it.
+    pub fn instruction_with_labels<F: Fn(&str) -> Option<i64>>(
+        &self,
+        input: &str,
+        label: F,
+    ) -> Result<Instruction> {
+        self.parse(input, Some(&label))
+    }
+
+    fn parse(&self, input: &str, label: Option<Labels<'_>>) -> Result<Instruction> {
+        let mut parts = input.split_whitespace();
+        let name = parts
+            .next()
+            .ok_or_else(|| AocError::InvalidInstruction(input.to_string()))?;
+        let tokens = parts.collect::<Vec<&str>>();
+
+        let opcode = match self.get(name) {
+            Some(opcode) => opcode,
+            None if tokens.is_empty() => {
+                return Err(AocError::InvalidInstruction(input.to_string()))
+            }
+            None => return Err(AocError::UnknownOperation(name.to_string())),
+        };
+        if tokens.len() != opcode.operands().len() {
+            return Err(AocError::InvalidInstruction(input.to_string()));
+        }
+
+        let args = tokens
+            .iter()
+            .zip(opcode.operands())
+            .map(|(token, kind)| operand(token, *kind, input, label))
+            .collect::<Result<Vec<Operand>>>()?;
+
+        Ok(Instruction::from_parts(Op::new(name), args))
+    }
+
+    pub fn program<S: AsRef<str>>(&self, input: &[S]) -> Result<Program> {
+        parse_lines(input, |line| self.instruction(line)).map(Program::from_instructions)
+    }
+
+    /// A program of every line that parsed, and the errors of those that did
+    /// not.
+    pub fn program_lenient<S: AsRef<str>>(&self, input: &[S]) -> Collected<Program> {
+        collect_lines(input, |line| self.instruction(line)).map(Program::from_instructions)
+    }
+
+    /// Run a single instruction against some registers, outside of any
+    /// machine.
+    pub fn execute(&self, instruction: &Instruction, registers: &mut Registers) -> Result<Flow> {
+        let mut args = Vec::new();
+        let opcode = self.load(instruction, registers, &mut args)?;
+        self.opcodes[opcode].execute(registers, &args)
+    }
+
+    /// Everywhere control may go after an instruction, as offsets from it.
+    pub fn targets(&self, instruction: &Instruction) -> Result<Vec<Flow>> {
+        let name = instruction.op().name();
+        self.get(name)
+            .map(|opcode| opcode.targets(instruction.args()))
+            .ok_or_else(|| AocError::UnknownOperation(name.to_string()))
+    }
+
+    /// Check an instruction against its opcode, pushing its operands onto
+    /// `args` and giving the opcode's index.
+    fn load(
+        &self,
+        instruction: &Instruction,
+        registers: &mut Registers,
+        args: &mut Vec<Arg>,
+    ) -> Result<usize> {
+        let name = instruction.op().name();
+        let opcode = self
+            .position(name)
+            .ok_or_else(|| AocError::UnknownOperation(name.to_string()))?;
+
+        let kinds = self.opcodes[opcode].operands();
+        let operands = instruction.args();
+        let fits = kinds.len() == operands.len()
+            && kinds.iter().zip(operands).all(|(kind, operand)| {
+                matches!(
+                    (kind, operand),
+                    (Kind::Value, _)
+                        | (Kind::Register, Operand::Register(_))
+                        | (Kind::Number | Kind::Offset, Operand::Value(_))
+                )
+            });
+        if !fits {
+            return Err(AocError::InvalidInstruction(instruction.to_string()));
+        }
+
+        args.extend(operands.iter().map(|operand| registers.load(operand)));
+        Ok(opcode)
+    }
+}
+
+fn operand(token: &str, kind: Kind, input: &str, label: Option<Labels<'_>>) -> Result<Operand> {
+    if let Some(label) = label.filter(|_| kind == Kind::Offset && is_label(token)) {
+        return label(token).map(Operand::Value).ok_or_else(|| {
+            AocError::UnknownLabel(token.to_string()).at(Span::locate(&[input], 1, Some(token)))
+        });
+    }
+
+    let is_name = token.chars().all(|c| c.is_ascii_alphabetic());
+    match kind {
+        Kind::Value | Kind::Register if is_name => Ok(Operand::Register(token.to_string())),
+        Kind::Register => Err(AocError::InvalidInstruction(format!(
+            "'{}' is not a register",
+            token
+        ))),
+        Kind::Value | Kind::Number | Kind::Offset => token
+            .parse::<i64>()
+            .map(Operand::Value)
+            .map_err(|e| AocError::from(e).at(Span::locate(&[input], 1, Some(token)))),
+    }
+}
+
+/// Turns a label into the offset to it, when parsing allows labels.
+type Labels<'a> = &'a dyn Fn(&str) -> Option<i64>;
+
+/// Whether `token` could be a label: a letter or underscore, then letters,
+/// digits and underscores.
+pub fn is_label(token: &str) -> bool {
+    let mut chars = token.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// How `run_until_repeat` stopped.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Exit {
+    /// The program ran off its end or reached a `halt`.
+    Halted,
+    /// The instruction at this address was about to run a second time.
+    Repeated(usize),
+}
+
+/// An instruction ready to run: its opcode and where its operands are in
+/// `Vm::args`.
+#[derive(Debug, Clone)]
+struct Loaded {
+    opcode: usize,
+    args: Range<usize>,
+}
+
+/// A machine running a program: an instruction pointer, registers, a count
+/// of the instructions run so far and the instruction set they came from.
+/// The program is loaded once, so the machine keeps no copy of it.
+#[derive(Debug, Clone)]
+pub struct Vm {
+    set: InstructionSet,
+    code: Vec<Loaded>,
+    args: Vec<Arg>,
+    pc: i64,
+    registers: Registers,
+    cycles: usize,
+    halted: bool,
+}
+
+impl Vm {
+    /// Load a program with the standard instruction set.
+    pub fn new(program: &Program) -> Result<Self> {
+        Vm::with_set(program, InstructionSet::standard())
+    }
+
+    pub fn with_set(program: &Program, set: InstructionSet) -> Result<Self> {
+        let mut registers = Registers::new();
+        let mut args = Vec::new();
+        let code = program
+            .instructions()
+            .iter()
+            .map(|instruction| {
+                let start = args.len();
+                set.load(instruction, &mut registers, &mut args)
+                    .map(|opcode| Loaded {
+                        opcode,
+                        args: start..args.len(),
+                    })
+            })
+            .collect::<Result<Vec<Loaded>>>()?;
+
+        Ok(Vm {
+            set,
+            code,
+            args,
+            pc: 0,
+            halted: program.instructions().is_empty(),
+            registers,
+            cycles: 0,
+        })
+    }
+
+    /// The address of the next instruction to run.
+    pub fn pc(&self) -> i64 {
+        self.pc
+    }
+
+    pub fn registers(&self) -> &Registers {
+        &self.registers
+    }
+
+    pub fn registers_mut(&mut self) -> &mut Registers {
+        &mut self.registers
+    }
+
+    pub fn acc(&self) -> i64 {
+        self.registers.acc()
+    }
+
+    /// The number of instructions run so far.
+    pub fn cycles(&self) -> usize {
+        self.cycles
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    /// Run the next instruction, returning whether the machine is still
+    /// running. Jumping anywhere but into the program or just past its end
+    /// is an error.
+    pub fn step(&mut self) -> Result<bool> {
+        if self.halted {
+            return Ok(false);
+        }
+
+        let pc = self.pc;
+        let loaded = &self.code[pc as usize];
+        let args = &self.args[loaded.args.clone()];
+        let flow = self.set.opcodes[loaded.opcode]
+            .execute(&mut self.registers, args)
+            .map_err(|err| match err {
+                AocError::InvalidProgram(message) => {
+                    AocError::InvalidProgram(format!("{} at {}", message, pc))
+                }
+                err => err,
+            })?;
+        self.cycles += 1;
+
+        let eof = self.code.len() as i64;
+        self.pc = match flow {
+            Flow::Next => pc + 1,
+            Flow::Jump(offset) => pc
+                .checked_add(offset)
+                .ok_or_else(|| AocError::InvalidProgram(format!("jump overflowed at {}", pc)))?,
+            Flow::Halt => {
+                self.halted = true;
+                return Ok(false);
+            }
+        };
+
+        if self.pc == eof {
+            self.halted = true;
+        } else if self.pc < 0 || self.pc > eof {
+            return Err(AocError::InvalidProgram(format!(
+                "Attempted to access instruction location out of bounds {} of {}",
+                self.pc, eof
+            )));
+        }
+
+        Ok(!self.halted)
+    }
+
+    /// Run until the machine halts, giving up with a `Timeout` error if the
+    /// current budget runs out first.
+    pub fn run(&mut self) -> Result<()> {
+        let budget = Budget::current();
+        while self.step()? {
+            budget.check_at(self.cycles)?;
+        }
+        Ok(())
+    }
+
+    /// Run until the machine halts or is about to run an instruction for the
+    /// second time, which for the handheld's `acc`, `jmp` and `nop` means it
+    /// never will halt.
+    pub fn run_until_repeat(&mut self) -> Result<Exit> {
+        let mut seen = vec![false; self.code.len()];
+
+        while !self.halted {
+            let pc = self.pc as usize;
+            if seen[pc] {
+                return Ok(Exit::Repeated(pc));
+            }
+            seen[pc] = true;
+            self.step()?;
+        }
+
+        Ok(Exit::Halted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::test_input;
+
+    fn program(source: &str) -> Program {
+        InstructionSet::standard()
+            .program(&test_input(source))
+            .unwrap()
+    }
+
+    #[test]
+    fn parsing() {
+        let set = InstructionSet::standard();
+        assert_eq!(set.instruction("jnz a -3").unwrap().to_string(), "jnz a -3");
+        assert_eq!(set.instruction("acc b").unwrap().to_string(), "acc b");
+        assert_eq!(set.instruction("halt").unwrap().to_string(), "halt");
+
+        assert!(matches!(
+            set.instruction("halt +1"),
+            Err(AocError::InvalidInstruction(_))
+        ));
+        assert!(matches!(
+            set.instruction("mov +1 +2"),
+            Err(AocError::InvalidInstruction(_))
+        ));
+        assert!(matches!(
+            set.instruction("jmp a"),
+            Err(AocError::Located(..))
+        ));
+        assert!(matches!(
+            set.instruction("add a b"),
+            Err(AocError::UnknownOperation(_))
+        ));
+    }
+
+    #[test]
+    fn registers() {
+        // multiply 6 by 7 with a loop, leaving the answer in acc
+        let mut vm = Vm::new(&program(
+            "
+            mov a +6
+            mov b +7
+            acc a
+            subr b +1
+            jnz b -2
+            halt
+            acc +1000
+            ",
+        ))
+        .unwrap();
+        vm.run().unwrap();
+
+        assert!(vm.is_halted());
+        assert_eq!(vm.acc(), 42);
+        assert_eq!(vm.registers().get("a"), Some(6));
+        assert_eq!(vm.registers().get("b"), Some(0));
+        assert_eq!(vm.registers().get("c"), None);
+        assert_eq!(vm.cycles(), 2 + 3 * 7 + 1);
+        assert_eq!(vm.pc(), 5);
+        assert_eq!(
+            vm.registers().iter().collect::<Vec<(&str, i64)>>(),
+            vec![("acc", 42), ("a", 6), ("b", 0)]
+        );
+    }
+
+    #[test]
+    fn stepping() {
+        let mut vm = Vm::new(&program("acc +1\njz acc +2\nmulr acc +5\njmp -3")).unwrap();
+
+        assert!(vm.step().unwrap());
+        assert!(vm.step().unwrap());
+        assert_eq!((vm.pc(), vm.acc()), (2, 1));
+        assert!(vm.step().unwrap());
+        assert_eq!(vm.acc(), 5);
+        assert_eq!(vm.run_until_repeat().unwrap(), Exit::Repeated(3));
+
+        let mut vm = Vm::new(&program("jmp +2")).unwrap();
+        assert!(matches!(vm.step(), Err(AocError::InvalidProgram(_))));
+    }
+
+    #[test]
+    fn overflowing() {
+        let overflows = |source: &str, message: &str| {
+            let mut vm = Vm::new(&program(source)).unwrap();
+            match vm.run() {
+                Err(AocError::InvalidProgram(err)) => assert_eq!(err, message),
+                result => panic!("{}: {:?}", source, result),
+            }
+        };
+
+        overflows("acc +9223372036854775807\nacc +1", "acc overflowed at 1");
+        overflows(
+            "mov a -9223372036854775807\naddr a -2",
+            "addr overflowed at 1",
+        );
+        overflows(
+            "mov a -9223372036854775807\nsubr a +2",
+            "subr overflowed at 1",
+        );
+        overflows(
+            "mov a +9223372036854775807\nmulr a a",
+            "mulr overflowed at 1",
+        );
+        overflows("nop +0\njmp +9223372036854775807", "jump overflowed at 1");
+    }
+
+    #[test]
+    fn registering_opcodes() {
+        fn dbl(registers: &mut Registers, args: &[Arg]) -> Result<Flow> {
+            registers.write(args[0], registers.value(args[0]) * 2)?;
+            Ok(Flow::Next)
+        }
+
+        let source = test_input("acc +3\ndbl acc\ndbl acc");
+        assert!(InstructionSet::standard().program(&source).is_err());
+
+        let set = InstructionSet::standard().with(FnOpcode {
+            name: "dbl",
+            operands: &[Kind::Register],
+            run: dbl,
+            targets: falls_through,
+        });
+        let program = set.program(&source).unwrap();
+        assert!(Vm::new(&program).is_err());
+
+        let mut vm = Vm::with_set(&program, set).unwrap();
+        vm.run().unwrap();
+        assert_eq!(vm.acc(), 12);
+    }
+
+    #[test]
+    fn running_out_of_time() {
+        let mut vm = Vm::new(&program("jmp +0")).unwrap();
+        let budget = Budget::unlimited();
+        budget.cancel();
+
+        assert!(matches!(
+            budget.enter(|| vm.run()),
+            Err(AocError::Timeout(_))
+        ));
+    }
+}
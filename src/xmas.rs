@@ -1,21 +1,24 @@
 use crate::error::{AocError, Result};
+use crate::input::Input;
+use crate::solution::{Part, Solution, Variant};
+use crate::trace;
+use crate::util::parse_lines;
 use itertools::Itertools;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Document {
     data: Vec<u64>,
 }
 
 impl Document {
-    pub fn new(input: &[String]) -> Result<Self> {
+    pub fn new<S: AsRef<str>>(input: &[S]) -> Result<Self> {
         Ok(Document {
-            data: input
-                .iter()
-                .map(|line| line.parse::<u64>())
-                .collect::<std::result::Result<Vec<u64>, std::num::ParseIntError>>()?,
+            data: parse_lines(input, |line| Ok(line.parse::<u64>()?))?,
         })
     }
 
     pub fn find_outlier(&self, preamble_size: usize) -> Result<u64> {
+        let _span = trace::span!("Document::find_outlier");
         for (index, target) in self.data[preamble_size..self.data.len()].iter().enumerate() {
             if !self.check(index + preamble_size, preamble_size, target) {
                 return Ok(*target);
@@ -64,6 +67,7 @@ impl Document {
     }
 
     pub fn find_weakness(&self, target: u64) -> Result<u64> {
+        let _span = trace::span!("Document::find_weakness");
         let mut left = 0;
         let mut right = 1;
 
@@ -109,6 +113,38 @@ impl Document {
     }
 }
 
+pub struct EncodingError;
+
+impl Solution for EncodingError {
+    const DAY: usize = 9;
+    const NAME: &'static str = "Encoding Error";
+
+    type Parsed = Document;
+
+    const VARIANTS: &'static [Variant<Self::Parsed>] = &[Variant {
+        part: Part::Two,
+        name: "find_weakness_slow",
+        slow: false,
+        solve: |document| {
+            let outlier = document.find_outlier(25)?;
+            Ok(document.find_weakness_slow(outlier)?.to_string())
+        },
+    }];
+
+    fn parse(&self, input: &Input) -> Result<Self::Parsed> {
+        Document::new(&input.lines())
+    }
+
+    fn part_one(&self, parsed: &Self::Parsed) -> Result<String> {
+        Ok(parsed.find_outlier(25)?.to_string())
+    }
+
+    fn part_two(&self, parsed: &Self::Parsed) -> Result<String> {
+        let outlier = parsed.find_outlier(25)?;
+        Ok(parsed.find_weakness(outlier)?.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;